@@ -28,9 +28,11 @@ use alloc::{
 };
 use core::{
     borrow::Borrow,
+    cmp::Ordering,
     fmt::Debug,
     hash::Hash,
     iter::{self, FusedIterator},
+    mem,
     ops::{Add, AddAssign, Deref, Sub, SubAssign},
     slice,
 };
@@ -44,7 +46,7 @@ use manta_crypto::{
             Allocate, Allocator, Variable,
         },
         bool::{Assert, AssertEq, Bool, ConditionalSelect},
-        num::Zero,
+        num::{One, Zero},
         ops::BitAnd,
         Has,
     },
@@ -696,9 +698,31 @@ pub trait AssetMap<I, V>: Default {
     /// Returns the sum of all the assets in `self`.
     fn assets(&self) -> AssetList<I, V>;
 
+    /// Returns the sum of all the assets in `self` stored at keys which satisfy `f`.
+    fn assets_where<F>(&self, f: F) -> AssetList<I, V>
+    where
+        F: FnMut(&Self::Key) -> bool;
+
     /// Selects asset keys which total up to at least `asset` in value.
+    ///
+    /// This method uses the [`Greedy`] coin selection strategy. See [`select_with`] for using
+    /// other strategies.
+    ///
+    /// [`select_with`]: Self::select_with
     fn select(&self, asset: &Asset<I, V>) -> Selection<I, V, Self>;
 
+    /// Selects asset keys which total up to at least `asset` in value using the coin selection
+    /// `strategy`.
+    fn select_with<T, R>(
+        &self,
+        asset: &Asset<I, V>,
+        strategy: &T,
+        rng: &mut R,
+    ) -> Selection<I, V, Self>
+    where
+        T: SelectionStrategy<Self::Key, V>,
+        R: RngCore + ?Sized;
+
     /// Returns at most `n` zero assets with the given `id`.
     fn zeroes(&self, n: usize, id: &I) -> Vec<Self::Key>;
 
@@ -770,49 +794,50 @@ macro_rules! impl_asset_map_for_maps_body {
                 .collect()
         }
 
+        #[inline]
+        fn assets_where<F>(&self, mut f: F) -> AssetList<$I, $V>
+        where
+            F: FnMut(&Self::Key) -> bool,
+        {
+            self.iter()
+                .filter(move |(key, _)| f(key))
+                .flat_map(move |(_, assets)| assets.iter().cloned())
+                .collect()
+        }
+
         #[inline]
         fn select(&self, asset: &Asset<$I, $V>) -> Selection<$I, $V, Self> {
             if asset.value == Default::default() {
                 return Selection::default();
             }
-            let mut sum = Asset::<$I, $V>::zero(asset.id.clone());
-            let mut values = Vec::new();
-            let mut min_max_asset = Option::<(&$K, &$V)>::None;
-            let map = self
-                .iter()
-                .map(|(key, assets)| assets.iter().map(move |asset| (key, asset)))
-                .flatten()
-                .filter_map(|(key, item)| {
-                    if item.value != Default::default() && item.id == asset.id {
-                        Some((key, &item.value))
-                    } else {
-                        None
-                    }
-                });
-            for (key, value) in map {
-                if value > &asset.value {
-                    min_max_asset = Some(match min_max_asset.take() {
-                        Some(best) if value >= &best.1 => best,
-                        _ => (key, value),
-                    });
-                } else if value == &asset.value {
-                    return Selection::new(Default::default(), vec![(key.clone(), value.clone())]);
-                } else {
-                    sum.value.add_assign(value.clone());
-                    values.push((key.clone(), value.clone()));
-                }
-            }
-            if let Some((best_key, best_value)) = min_max_asset {
-                return Selection::new(
-                    best_value - &asset.value,
-                    vec![(best_key.clone(), best_value.clone())],
-                );
-            }
-            if sum.value < asset.value {
-                Selection::default()
-            } else {
-                Selection::new(&sum.value - &asset.value, values)
+            Selection::from_values(
+                &asset.value,
+                greedy_select(&asset.value, selection_candidates(self.iter(), &asset.id)),
+            )
+        }
+
+        #[inline]
+        fn select_with<T, R>(
+            &self,
+            asset: &Asset<$I, $V>,
+            strategy: &T,
+            rng: &mut R,
+        ) -> Selection<$I, $V, Self>
+        where
+            T: SelectionStrategy<Self::Key, $V>,
+            R: RngCore + ?Sized,
+        {
+            if asset.value == Default::default() {
+                return Selection::default();
             }
+            Selection::from_values(
+                &asset.value,
+                strategy.select(
+                    &asset.value,
+                    selection_candidates(self.iter(), &asset.id),
+                    rng,
+                ),
+            )
         }
 
         #[inline]
@@ -899,6 +924,341 @@ where
     impl_asset_map_for_maps_body! { K, I, V, HashMapEntry }
 }
 
+/// Returns the keys and values of all the non-zero assets in `assets` with the given `id`.
+#[inline]
+fn selection_candidates<'m, K, I, V, M>(assets: M, id: &I) -> Vec<(K, V)>
+where
+    K: 'm + Clone,
+    I: 'm + PartialEq,
+    V: 'm + Clone + Default + PartialEq,
+    M: IntoIterator<Item = (&'m K, &'m Vec<Asset<I, V>>)>,
+{
+    assets
+        .into_iter()
+        .flat_map(move |(key, assets)| assets.iter().map(move |asset| (key, asset)))
+        .filter(move |(_, asset)| asset.value != Default::default() && &asset.id == id)
+        .map(|(key, asset)| (key.clone(), asset.value.clone()))
+        .collect()
+}
+
+/// Selects the smallest of the `candidates` which covers `target` on its own, or otherwise all
+/// of the `candidates` which are smaller than `target`.
+#[inline]
+fn greedy_select<K, V>(target: &V, candidates: Vec<(K, V)>) -> Option<Vec<(K, V)>>
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    let mut sum = V::default();
+    let mut values = Vec::new();
+    let mut min_max_asset = Option::<(K, V)>::None;
+    for (key, value) in candidates {
+        match value.partial_cmp(target) {
+            Some(Ordering::Greater) => {
+                if !matches!(&min_max_asset, Some((_, best)) if &value >= best) {
+                    min_max_asset = Some((key, value));
+                }
+            }
+            Some(Ordering::Equal) => return Some(vec![(key, value)]),
+            _ => {
+                sum += value.clone();
+                values.push((key, value));
+            }
+        }
+    }
+    if let Some(best) = min_max_asset {
+        return Some(vec![best]);
+    }
+    (&sum >= target).then_some(values)
+}
+
+/// Takes `candidates` in order until they total up to at least `target`.
+#[inline]
+fn select_in_order<K, V, T>(target: &V, candidates: T) -> Option<Vec<(K, V)>>
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+    T: IntoIterator<Item = (K, V)>,
+{
+    let mut sum = V::default();
+    let mut values = Vec::new();
+    for (key, value) in candidates {
+        if &sum >= target {
+            break;
+        }
+        sum += value.clone();
+        values.push((key, value));
+    }
+    (&sum >= target).then_some(values)
+}
+
+/// Coin Selection Strategy
+///
+/// A coin selection strategy decides which assets are spent to pay for a given value. The
+/// candidates are all the non-zero assets in an [`AssetMap`] with the requested asset id. See
+/// [`AssetMap::select_with`] for more.
+pub trait SelectionStrategy<K, V> {
+    /// Selects some of the `candidates` which total up to at least `target`, returning `None` if
+    /// all of the `candidates` together do not reach `target`.
+    fn select<R>(&self, target: &V, candidates: Vec<(K, V)>, rng: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized;
+}
+
+/// Greedy Coin Selection
+///
+/// Selects the smallest asset which covers the target on its own, or otherwise all of the assets
+/// which are smaller than the target. This is the strategy used by [`AssetMap::select`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Greedy;
+
+impl<K, V> SelectionStrategy<K, V> for Greedy
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, candidates: Vec<(K, V)>, _: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        greedy_select(target, candidates)
+    }
+}
+
+/// Smallest-First Coin Selection
+///
+/// Selects assets in increasing order of value, consolidating dust at the cost of spending many
+/// assets at once.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SmallestFirst;
+
+impl<K, V> SelectionStrategy<K, V> for SmallestFirst
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, mut candidates: Vec<(K, V)>, _: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        candidates.sort_by(|lhs, rhs| lhs.1.partial_cmp(&rhs.1).unwrap_or(Ordering::Equal));
+        select_in_order(target, candidates)
+    }
+}
+
+/// Largest-First Coin Selection
+///
+/// Selects assets in decreasing order of value, spending as few assets as possible.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LargestFirst;
+
+impl<K, V> SelectionStrategy<K, V> for LargestFirst
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, mut candidates: Vec<(K, V)>, _: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        candidates.sort_by(|lhs, rhs| rhs.1.partial_cmp(&lhs.1).unwrap_or(Ordering::Equal));
+        select_in_order(target, candidates)
+    }
+}
+
+/// Branch-and-Bound Coin Selection
+///
+/// Searches for a set of assets which totals up to exactly the target, so that no change is
+/// left over. The search gives up after [`MAX_STEPS`](Self::MAX_STEPS) steps, in which case the
+/// assets are selected with [`LargestFirst`] instead.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BranchAndBound;
+
+impl BranchAndBound {
+    /// Maximum Number of Search Steps
+    pub const MAX_STEPS: usize = 100_000;
+
+    /// Searches for a subset of `values` which adds up to `target`, where `remaining[i]` is the
+    /// sum of `values[i..]`, returning which of the `values` were chosen.
+    ///
+    /// The search is a depth-first traversal which first tries to include each value and then to
+    /// exclude it. It keeps the included values on an explicit stack instead of recursing, and
+    /// gives up after visiting [`MAX_STEPS`](Self::MAX_STEPS) nodes.
+    #[inline]
+    fn search<V>(values: &[V], remaining: &[V], target: &V) -> Option<Vec<bool>>
+    where
+        V: AddAssign + Clone + Default + PartialOrd,
+    {
+        let mut selected = vec![false; values.len()];
+        let mut included = Vec::<(usize, V)>::new();
+        let mut index = 0;
+        let mut sum = V::default();
+        let mut steps = Self::MAX_STEPS;
+        loop {
+            if &sum == target {
+                return Some(selected);
+            }
+            let mut backtrack = index == values.len() || steps == 0;
+            if !backtrack {
+                steps -= 1;
+                let mut bound = sum.clone();
+                bound += remaining[index].clone();
+                backtrack = &bound < target;
+            }
+            if backtrack {
+                let (last, previous) = included.pop()?;
+                selected[last] = false;
+                sum = previous;
+                index = last + 1;
+                continue;
+            }
+            let mut next = sum.clone();
+            next += values[index].clone();
+            if &next <= target {
+                selected[index] = true;
+                included.push((index, mem::replace(&mut sum, next)));
+            }
+            index += 1;
+        }
+    }
+}
+
+impl<K, V> SelectionStrategy<K, V> for BranchAndBound
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, mut candidates: Vec<(K, V)>, rng: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        candidates.sort_by(|lhs, rhs| rhs.1.partial_cmp(&lhs.1).unwrap_or(Ordering::Equal));
+        let values = candidates
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect::<Vec<_>>();
+        let mut remaining = vec![V::default(); values.len()];
+        let mut sum = V::default();
+        for (index, value) in values.iter().enumerate().rev() {
+            sum += value.clone();
+            remaining[index] = sum.clone();
+        }
+        if let Some(selected) = Self::search(&values, &remaining, target) {
+            return Some(
+                candidates
+                    .into_iter()
+                    .zip(selected)
+                    .filter_map(|(candidate, is_selected)| is_selected.then_some(candidate))
+                    .collect(),
+            );
+        }
+        LargestFirst.select(target, candidates, rng)
+    }
+}
+
+/// Random Coin Selection
+///
+/// Selects assets in a random order until they cover the target, so that the selected assets do
+/// not depend on the value distribution of the wallet.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RandomSelection;
+
+impl<K, V> SelectionStrategy<K, V> for RandomSelection
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, mut candidates: Vec<(K, V)>, rng: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut sum = V::default();
+        let mut values = Vec::new();
+        while &sum < target && !candidates.is_empty() {
+            let index = rng.gen_range(0..candidates.len());
+            let (key, value) = candidates.swap_remove(index);
+            sum += value.clone();
+            values.push((key, value));
+        }
+        (&sum >= target).then_some(values)
+    }
+}
+
+/// Coin Selection
+///
+/// This `enum` chooses one of the coin selection strategies defined in this module at runtime,
+/// for example, as part of a signing request.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CoinSelection {
+    /// [`Greedy`] Coin Selection
+    #[default]
+    Greedy,
+
+    /// [`SmallestFirst`] Coin Selection
+    SmallestFirst,
+
+    /// [`LargestFirst`] Coin Selection
+    LargestFirst,
+
+    /// [`BranchAndBound`] Coin Selection
+    BranchAndBound,
+
+    /// [`RandomSelection`] Coin Selection
+    Random,
+}
+
+impl<K, V> SelectionStrategy<K, V> for CoinSelection
+where
+    V: AddAssign + Clone + Default + PartialOrd,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, candidates: Vec<(K, V)>, rng: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        match self {
+            Self::Greedy => Greedy.select(target, candidates, rng),
+            Self::SmallestFirst => SmallestFirst.select(target, candidates, rng),
+            Self::LargestFirst => LargestFirst.select(target, candidates, rng),
+            Self::BranchAndBound => BranchAndBound.select(target, candidates, rng),
+            Self::Random => RandomSelection.select(target, candidates, rng),
+        }
+    }
+}
+
+/// Non-Fungible Coin Selection
+///
+/// Wraps the coin selection strategy `S`, treating every asset of unit value as a non-fungible
+/// token. A target of unit value is paid with exactly one such asset whenever one is available,
+/// and these assets are never selected together with other assets, so they are never split into
+/// change. All other targets are paid by `S` out of the remaining assets.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NonFungible<S = CoinSelection>(pub S);
+
+impl<K, V, S> SelectionStrategy<K, V> for NonFungible<S>
+where
+    V: One + PartialEq,
+    S: SelectionStrategy<K, V>,
+{
+    #[inline]
+    fn select<R>(&self, target: &V, mut candidates: Vec<(K, V)>, rng: &mut R) -> Option<Vec<(K, V)>>
+    where
+        R: RngCore + ?Sized,
+    {
+        let unit = V::one(&mut ());
+        if target == &unit {
+            if let Some(index) = candidates.iter().position(|(_, value)| value == &unit) {
+                return Some(vec![candidates.swap_remove(index)]);
+            }
+        }
+        candidates.retain(|(_, value)| value != &unit);
+        self.0.select(target, candidates, rng)
+    }
+}
+
 /// Asset Selection
 ///
 /// This `struct` is created by the [`select`](AssetMap::select) method of [`AssetMap`]. See its
@@ -933,6 +1293,26 @@ where
         Self { change, values }
     }
 
+    /// Builds a new [`Selection`] from the `values` selected to pay for `target`, which is empty
+    /// if there are no `values`.
+    #[inline]
+    fn from_values(target: &V, values: Option<Vec<(M::Key, V)>>) -> Self
+    where
+        V: AddAssign + Clone + Default,
+        for<'v> &'v V: Sub<Output = V>,
+    {
+        match values {
+            Some(values) if !values.is_empty() => {
+                let mut sum = V::default();
+                for (_, value) in &values {
+                    sum += value.clone();
+                }
+                Self::new(&sum - target, values)
+            }
+            _ => Default::default(),
+        }
+    }
+
     /// Returns `true` if `self` is an empty [`Selection`].
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -197,7 +197,7 @@ impl AccountMap for VecAccountMap {
                 .try_into()
                 .expect("AccountIndex is not allowed to exceed IndexType::MAX."),
         );
-        self.push(Default::default());
+        self.push(index);
         index
     }
 
@@ -287,6 +287,12 @@ where
     pub fn keys(&self) -> &H {
         &self.key
     }
+
+    /// Returns the [`AccountIndex`] of `self`.
+    #[inline]
+    pub fn index(&self) -> AccountIndex {
+        self.index
+    }
 }
 
 impl<H> DeriveAddress for Account<H>
@@ -375,6 +381,16 @@ where
         self.get(Default::default()).unwrap()
     }
 
+    /// Returns an iterator over all the accounts in `self`.
+    #[inline]
+    pub fn accounts(&self) -> impl '_ + Iterator<Item = Account<H>>
+    where
+        H: Clone,
+    {
+        (0..=self.accounts.last_account().index())
+            .filter_map(move |index| self.get(AccountIndex::new(index)))
+    }
+
     /// Adds a new account to the map, returning the new account parameter.
     #[inline]
     pub fn create_account(&mut self) -> AccountIndex {
@@ -28,9 +28,12 @@ use crate::{
         TransferPost, TransferPostingKeyRef, Utxo, VerifyingContext,
     },
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::{fmt::Debug, hash::Hash};
-use manta_crypto::rand::{CryptoRng, RngCore};
+use manta_crypto::{
+    eclair::num::One,
+    rand::{CryptoRng, RngCore},
+};
 use manta_util::{create_seal, seal};
 
 #[cfg(feature = "serde")]
@@ -229,7 +232,7 @@ where
     derive(Deserialize, Serialize),
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum TransferShape {
     /// [`ToPrivate`] Transfer
     ToPrivate,
@@ -348,7 +351,6 @@ impl TransferShape {
 #[derive(derivative::Derivative)]
 #[derivative(
     Clone(bound = "Asset<C>: Clone, Address<C>: Clone"),
-    Copy(bound = "Asset<C>: Copy, Address<C>: Copy"),
     Debug(bound = "Asset<C>: Debug, Address<C>: Debug"),
     Eq(bound = "Asset<C>: Eq, Address<C>: Eq"),
     Hash(bound = "Asset<C>: Hash, Address<C>: Hash"),
@@ -366,6 +368,13 @@ where
 
     /// Convert Private Asset into Public Asset
     ToPublic(Asset<C>),
+
+    /// Private Transfer Assets to Addresses
+    ///
+    /// The payments are signed in order, and the change of each payment is spent by the next
+    /// payment with the same asset id, so that all of them can be posted without synchronizing
+    /// in between.
+    BatchPrivateTransfer(Vec<(Asset<C>, Address<C>)>),
 }
 
 impl<C> Transaction<C>
@@ -373,42 +382,91 @@ where
     C: Configuration,
 {
     /// Checks that `self` can be executed for a given `balance` state, returning the
-    /// transaction kind if successful, and returning the asset back if the balance was
-    /// insufficient.
+    /// transaction kind if successful.
+    ///
+    /// Every asset id for which `is_non_fungible` returns `true` is treated as a non-fungible
+    /// token, which can only be withdrawn whole, i.e. with unit value. Withdrawing any other
+    /// value of such an asset returns a
+    /// [`PartialNonFungibleTransfer`](TransactionCheckError::PartialNonFungibleTransfer) error.
     #[inline]
-    pub fn check<F>(&self, balance: F) -> Result<TransactionKind<C>, &Asset<C>>
+    pub fn check<F, N>(
+        &self,
+        mut balance: F,
+        mut is_non_fungible: N,
+    ) -> Result<TransactionKind<C>, TransactionCheckError<C>>
     where
-        F: FnOnce(&Asset<C>) -> bool,
+        F: FnMut(&Asset<C>) -> bool,
+        N: FnMut(&C::AssetId) -> bool,
     {
+        let mut check_whole = move |asset: &Asset<C>| {
+            if is_non_fungible(&asset.id) && asset.value != C::AssetValue::one(&mut ()) {
+                Err(TransactionCheckError::PartialNonFungibleTransfer(
+                    asset.clone(),
+                ))
+            } else {
+                Ok(())
+            }
+        };
         match self {
             Self::ToPrivate(asset) => Ok(TransactionKind::Deposit(asset.clone())),
             Self::PrivateTransfer(asset, _) | Self::ToPublic(asset) => {
+                check_whole(asset)?;
                 if balance(asset) {
                     Ok(TransactionKind::Withdraw(asset.clone()))
                 } else {
-                    Err(asset)
+                    Err(TransactionCheckError::InsufficientBalance(asset.clone()))
+                }
+            }
+            Self::BatchPrivateTransfer(payments) => {
+                for (asset, _) in payments {
+                    check_whole(asset)?;
+                }
+                let totals = Self::batch_totals(payments);
+                match totals.iter().find(|asset| !balance(asset)) {
+                    Some(asset) => Err(TransactionCheckError::InsufficientBalance(asset.clone())),
+                    _ => Ok(TransactionKind::BatchWithdraw(totals)),
                 }
             }
         }
     }
 
+    /// Returns the total amount paid for each asset id in `payments`, sorted by asset id.
+    #[inline]
+    pub fn batch_totals(payments: &[(Asset<C>, Address<C>)]) -> Vec<Asset<C>> {
+        let mut totals = BTreeMap::<C::AssetId, C::AssetValue>::new();
+        for (asset, _) in payments {
+            *totals.entry(asset.id.clone()).or_default() += asset.value.clone();
+        }
+        totals
+            .into_iter()
+            .map(|(id, value)| Asset::<C>::new(id, value))
+            .collect()
+    }
+
     /// Returns the associated [`TransferShape`] for this [`Transaction`].
+    ///
+    /// A [`BatchPrivateTransfer`](Self::BatchPrivateTransfer) is signed as a sequence of
+    /// [`PrivateTransfer`]s, so its shape is [`TransferShape::PrivateTransfer`].
     #[inline]
     pub fn shape(&self) -> TransferShape {
         match self {
             Self::ToPrivate(_) => TransferShape::ToPrivate,
-            Self::PrivateTransfer(_, _) => TransferShape::PrivateTransfer,
+            Self::PrivateTransfer(_, _) | Self::BatchPrivateTransfer(_) => {
+                TransferShape::PrivateTransfer
+            }
             Self::ToPublic(_) => TransferShape::ToPublic,
         }
     }
 
-    /// Returns the amount of value being transfered in `self`.
+    /// Returns the amount of value being transfered in `self`, or `None` if `self` is a
+    /// [`BatchPrivateTransfer`](Self::BatchPrivateTransfer), which can transfer several asset ids.
     #[inline]
-    pub fn value(&self) -> &C::AssetValue {
+    pub fn value(&self) -> Option<&C::AssetValue> {
         match self {
-            Self::ToPrivate(asset) => &asset.value,
-            Self::PrivateTransfer(asset, _) => &asset.value,
-            Self::ToPublic(asset) => &asset.value,
+            Self::ToPrivate(asset) => Some(&asset.value),
+            Self::PrivateTransfer(asset, _) => Some(&asset.value),
+            Self::ToPublic(asset) => Some(&asset.value),
+            Self::BatchPrivateTransfer(_) => None,
         }
     }
 
@@ -418,10 +476,54 @@ where
     where
         C::AssetValue: Default + PartialEq,
     {
-        self.value() == &Default::default()
+        match self {
+            Self::BatchPrivateTransfer(payments) => payments
+                .iter()
+                .all(|(asset, _)| asset.value == Default::default()),
+            _ => self.value() == Some(&Default::default()),
+        }
     }
 }
 
+/// Transaction Check Error
+///
+/// This `enum` is the error state of the [`Transaction::check`] method.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug"),
+    Eq(bound = "Asset<C>: Eq"),
+    Hash(bound = "Asset<C>: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq")
+)]
+pub enum TransactionCheckError<C>
+where
+    C: Configuration,
+{
+    /// Insufficient Balance Error
+    ///
+    /// The balance state does not contain enough of the given asset.
+    InsufficientBalance(Asset<C>),
+
+    /// Partial Non-Fungible Transfer Error
+    ///
+    /// The transaction withdraws a value of a non-fungible asset other than its unit value, which
+    /// would split the token.
+    PartialNonFungibleTransfer(Asset<C>),
+}
+
 /// Transaction Kind
 #[cfg_attr(
     feature = "serde",
@@ -438,7 +540,6 @@ where
 #[derive(derivative::Derivative)]
 #[derivative(
     Clone(bound = "Asset<C>: Clone"),
-    Copy(bound = "Asset<C>: Copy"),
     Debug(bound = "Asset<C>: Debug"),
     Hash(bound = "Asset<C>: Hash"),
     Eq(bound = "Asset<C>: Eq"),
@@ -457,6 +558,11 @@ where
     ///
     /// A transaction of this kind will result in a withdraw of `asset`.
     Withdraw(Asset<C>),
+
+    /// Batch Withdraw Transaction
+    ///
+    /// A transaction of this kind will result in a withdraw of each of the `assets`.
+    BatchWithdraw(Vec<Asset<C>>),
 }
 
 /// Transfer Asset Selection
@@ -628,6 +734,34 @@ where
             TransferShape::ToPublic => &self.to_public,
         }
     }
+
+    /// Verifies the validity proofs of all of the `posts`, batching together the posts which
+    /// share a [`TransferShape`]. Returns `false` if any post does not have a canonical shape or
+    /// has an invalid proof. See [`TransferPost::has_valid_proofs`] for more.
+    #[inline]
+    pub fn has_valid_proofs<R>(
+        &self,
+        posts: &[TransferPost<C>],
+        rng: &mut R,
+    ) -> Result<bool, ProofSystemError<C>>
+    where
+        C: Sized,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let mut batches = BTreeMap::<_, Vec<_>>::new();
+        for post in posts {
+            match TransferShape::from_post(post) {
+                Some(shape) => batches.entry(shape).or_default().push(post),
+                _ => return Ok(false),
+            }
+        }
+        for (shape, batch) in batches {
+            if !TransferPost::has_valid_proofs(self.select(shape), batch, rng)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
 
 /// Generates proving and verifying multi-contexts for the canonical transfer shapes.
@@ -35,7 +35,9 @@ use crate::{
         canonical::TransferShape,
         receiver::{ReceiverLedger, ReceiverPostError},
         sender::{SenderLedger, SenderPostError},
-        utxo::{auth, Mint, NullifierIndependence, Spend, UtxoIndependence, UtxoReconstruct},
+        utxo::{
+            auth, Mint, NoteMemo, NullifierIndependence, Spend, UtxoIndependence, UtxoReconstruct,
+        },
     },
 };
 use core::{fmt::Debug, hash::Hash, iter::Sum, ops::AddAssign};
@@ -49,6 +51,7 @@ use manta_crypto::{
             Allocate, Allocator, Constant, Var, Variable,
         },
         bool::{Assert, AssertEq},
+        num::One,
         ops::Add,
     },
     rand::{CryptoRng, RngCore, Sample},
@@ -76,6 +79,14 @@ pub mod test;
 #[doc(inline)]
 pub use canonical::Shape;
 
+/// Memo Encoding Version
+///
+/// A [`TransferPostBody`] whose receivers carry memos is encoded as this byte, followed by the
+/// encoding of the body without its memos and then by the optional memo of every receiver. A body
+/// without memos keeps the original encoding, which always starts with the `0` or `1` tag of its
+/// optional asset id, so the two encodings can never be confused with each other.
+pub const MEMO_ENCODING_VERSION: u8 = 2;
+
 /// Returns `true` if the [`Transfer`] with this shape would have public participants.
 #[inline]
 pub const fn has_public_participants(sources: usize, sinks: usize) -> bool {
@@ -103,7 +114,7 @@ pub trait Configuration {
     type AssetId: Clone + Ord;
 
     /// Asset Value Type
-    type AssetValue: AddAssign + Clone + Default + PartialOrd + Sum;
+    type AssetValue: AddAssign + Clone + Default + One + PartialOrd + Sum;
 
     /// Associated Data Type
     type AssociatedData: Default;
@@ -115,7 +126,7 @@ pub trait Configuration {
     type Nullifier: Independence<NullifierIndependence>;
 
     /// Identifier Type
-    type Identifier: Clone + Sample;
+    type Identifier: Clone + Sample + utxo::QueryVisibility;
 
     /// Address Type
     type Address: Clone;
@@ -159,7 +170,8 @@ pub trait Configuration {
             Secret = Self::SpendSecret,
             Nullifier = Self::Nullifier,
             Identifier = Self::Identifier,
-        > + utxo::UtxoReconstruct;
+        > + utxo::NoteMemo
+        + utxo::UtxoReconstruct;
 
     /// Authorization Context Variable Type
     type AuthorizationContextVar: Variable<
@@ -366,6 +378,15 @@ pub type BaseNote<C> = Var<<C as Configuration>::NoteVar, Public, <C as Configur
 /// Note Type
 pub type Note<C> = utxo::Note<Parameters<C>>;
 
+/// Decryption Key Type
+pub type DecryptionKey<C> = utxo::DecryptionKey<Parameters<C>>;
+
+/// Memo Type
+pub type Memo<C> = utxo::Memo<Parameters<C>>;
+
+/// Encrypted Memo Type
+pub type EncryptedMemo<C> = utxo::EncryptedMemo<Parameters<C>>;
+
 /// Nullifier Type
 pub type Nullifier<C> = utxo::Nullifier<Parameters<C>>;
 
@@ -1532,18 +1553,39 @@ where
     SenderPost<C>: Encode,
     ReceiverPost<C>: Encode,
     Proof<C>: Encode,
+    EncryptedMemo<C>: Encode,
 {
     #[inline]
     fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
     where
         W: Write,
     {
+        let memos = self
+            .receiver_posts
+            .iter()
+            .map(|post| C::Parameters::encrypted_memo(&post.note))
+            .collect::<Vec<_>>();
+        let has_memos = memos.iter().any(Option::is_some);
+        if has_memos {
+            MEMO_ENCODING_VERSION.encode(&mut writer)?;
+        }
         self.asset_id.encode(&mut writer)?;
         self.sources.encode(&mut writer)?;
         self.sender_posts.encode(&mut writer)?;
         self.receiver_posts.encode(&mut writer)?;
         self.sinks.encode(&mut writer)?;
         self.proof.encode(&mut writer)?;
+        if has_memos {
+            for memo in memos {
+                match memo {
+                    Some(memo) => {
+                        1u8.encode(&mut writer)?;
+                        memo.encode(&mut writer)?;
+                    }
+                    _ => 0u8.encode(&mut writer)?,
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -1660,6 +1702,29 @@ where
         )
     }
 
+    /// Verifies the validity proofs of all of the `posts` against the same `verifying_context`,
+    /// returning `false` if any of them is invalid. See [`ProofSystem::verify_batch`] for more.
+    #[inline]
+    pub fn has_valid_proofs<'p, I, R>(
+        verifying_context: &VerifyingContext<C>,
+        posts: I,
+        rng: &mut R,
+    ) -> Result<bool, ProofSystemError<C>>
+    where
+        C: 'p,
+        I: IntoIterator<Item = &'p Self>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        C::ProofSystem::verify_batch(
+            verifying_context,
+            &posts
+                .into_iter()
+                .map(|post| (post.generate_proof_input(), &post.body.proof))
+                .collect::<Vec<_>>(),
+            rng,
+        )
+    }
+
     /// Asserts that `self` has a valid proof. See [`has_valid_proof`](Self::has_valid_proof) for
     /// more.
     #[inline]
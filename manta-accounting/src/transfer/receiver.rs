@@ -16,7 +16,7 @@
 
 //! Transfer Receiver
 
-use crate::transfer::utxo::{DeriveMint, Identifier, Mint, Note, QueryIdentifier};
+use crate::transfer::utxo::{DeriveMint, Identifier, Mint, Note, NoteMemo, QueryIdentifier};
 use core::{fmt::Debug, hash::Hash, iter};
 use manta_crypto::{
     accumulator::{Accumulator, ItemHashFunction},
@@ -107,6 +107,30 @@ where
         Self::new(secret, utxo, note)
     }
 
+    /// Samples a new [`Receiver`] that will control `asset` at the given `address`, attaching
+    /// `memo` to its note if it is given.
+    #[inline]
+    pub fn sample_with_memo<R>(
+        parameters: &M,
+        address: M::Address,
+        asset: M::Asset,
+        associated_data: M::AssociatedData,
+        memo: Option<&M::Memo>,
+        rng: &mut R,
+    ) -> Self
+    where
+        M: DeriveMint + NoteMemo,
+        M::Address: Clone,
+        R: RngCore + ?Sized,
+    {
+        let memo_address = memo.map(|memo| (address.clone(), memo));
+        let (secret, utxo, mut note) = parameters.derive_mint(address, asset, associated_data, rng);
+        if let Some((address, memo)) = memo_address {
+            parameters.attach_memo(&address, memo, &mut note, rng);
+        }
+        Self::new(secret, utxo, note)
+    }
+
     /// Inserts the [`Utxo`] corresponding to `self` into the `utxo_accumulator` with the intention
     /// of returning a proof later.
     ///
@@ -232,6 +232,9 @@ pub trait DeriveDecryptionKey: AuthorizationContextType {
     ) -> Self::DecryptionKey;
 }
 
+/// Decryption Key Type
+pub type DecryptionKey<T> = <T as DeriveDecryptionKey>::DecryptionKey;
+
 /// Note Opening
 pub trait NoteOpen: AssetType + DeriveDecryptionKey + IdentifierType + NoteType + UtxoType {
     /// Tries to open `note` with `decryption_key`, returning a note [`Identifier`] and its stored
@@ -306,12 +309,61 @@ pub trait UtxoReconstruct: NoteOpen + DeriveAddress<SecretKey = Self::Decryption
     }
 }
 
+/// Memo
+pub trait MemoType {
+    /// Memo Type
+    type Memo;
+}
+
+/// Memo Type
+pub type Memo<T> = <T as MemoType>::Memo;
+
+/// Encrypted Memo Type
+pub type EncryptedMemo<T> = <T as NoteMemo>::EncryptedMemo;
+
+/// Note Memo
+///
+/// Memos are attached to notes after the UTXO is minted, so neither the UTXO nor the transfer
+/// proof commit to them.
+pub trait NoteMemo: AddressType + DeriveDecryptionKey + MemoType + NoteType {
+    /// Encrypted Memo Type
+    type EncryptedMemo;
+
+    /// Returns the encrypted memo attached to `note`, if any.
+    fn encrypted_memo(note: &Self::Note) -> Option<&Self::EncryptedMemo>;
+
+    /// Encrypts `memo` for `address` and attaches it to `note`, replacing any memo that was
+    /// already attached.
+    fn attach_memo<R>(
+        &self,
+        address: &Self::Address,
+        memo: &Self::Memo,
+        note: &mut Self::Note,
+        rng: &mut R,
+    ) where
+        R: RngCore + ?Sized;
+
+    /// Tries to decrypt the memo attached to `note` with `decryption_key`, returning `None` if
+    /// there is no memo or if the memo was not encrypted for `decryption_key`.
+    fn open_memo(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        note: &Self::Note,
+    ) -> Option<Self::Memo>;
+}
+
 /// Query Identifier Value
 pub trait QueryIdentifier: IdentifierType + UtxoType {
     /// Queries the underlying identifier from `self` and `utxo`.
     fn query_identifier(&self, utxo: &Self::Utxo) -> Self::Identifier;
 }
 
+/// Query Visibility
+pub trait QueryVisibility {
+    /// Returns `true` if `self` refers to a transparent UTXO, i.e. a UTXO whose asset is public.
+    fn is_transparent(&self) -> bool;
+}
+
 /// UTXO Minting
 pub trait Mint<COM = ()>: AssetType + NoteType + UtxoType {
     /// Secret Type
@@ -65,6 +65,11 @@ use manta_util::serde::{Deserialize, Serialize};
 pub const VERSION: u8 = 1;
 
 /// UTXO Visibility
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum Visibility {
     /// Opaque UTXO
@@ -333,8 +338,25 @@ pub trait Configuration: BaseConfiguration<Bool = bool> {
     /// Schnorr Hash Function
     type SchnorrHashFunction: Clone
         + schnorr::HashFunction<Scalar = Self::Scalar, Group = Self::Group, Message = Vec<u8>>;
+
+    /// Memo Type
+    type Memo;
+
+    /// Base Encryption Scheme for [`Memo`](Self::Memo)
+    type MemoBaseEncryptionScheme: Default
+        + Encrypt<EncryptionKey = Self::Group, Header = (), Plaintext = Self::Memo, Randomness = ()>
+        + Decrypt<DecryptionKey = Self::Group, DecryptedPlaintext = Option<Self::Memo>>;
 }
 
+/// Memo Encryption Scheme
+pub type MemoEncryptionScheme<C> = Hybrid<
+    StandardDiffieHellman<<C as BaseConfiguration>::Scalar, <C as BaseConfiguration>::Group>,
+    <C as Configuration>::MemoBaseEncryptionScheme,
+>;
+
+/// Encrypted Memo
+pub type EncryptedMemo<C> = EncryptedMessage<MemoEncryptionScheme<C>>;
+
 /// Asset Type
 pub type Asset<C, COM = ()> =
     asset::Asset<<C as BaseConfiguration<COM>>::AssetId, <C as BaseConfiguration<COM>>::AssetValue>;
@@ -979,6 +1001,13 @@ where
     type Note = FullIncomingNote<C>;
 }
 
+impl<C> utxo::MemoType for Parameters<C>
+where
+    C: Configuration<Bool = bool>,
+{
+    type Memo = C::Memo;
+}
+
 impl<C> utxo::UtxoType for Parameters<C>
 where
     C: Configuration<Bool = bool>,
@@ -1373,6 +1402,7 @@ where
     C: Configuration<Bool = bool>,
     C::LightIncomingBaseEncryptionScheme:
         Decrypt<DecryptionKey = C::Group, DecryptedPlaintext = Option<IncomingPlaintext<C>>>,
+    Asset<C>: Clone,
 {
     #[inline]
     fn open(
@@ -1398,9 +1428,14 @@ where
                 &note.light_incoming_note.ciphertext,
                 &mut (),
             )?;
+            let asset = if utxo.is_transparent {
+                utxo.public_asset.clone()
+            } else {
+                plaintext.asset
+            };
             Some((
                 Identifier::new(utxo.is_transparent, plaintext.utxo_commitment_randomness),
-                plaintext.asset,
+                asset,
             ))
         } else {
             None
@@ -1408,6 +1443,62 @@ where
     }
 }
 
+impl<C> utxo::NoteMemo for Parameters<C>
+where
+    C: Configuration<Bool = bool>,
+    C::Scalar: Sample,
+{
+    type EncryptedMemo = EncryptedMemo<C>;
+
+    #[inline]
+    fn encrypted_memo(note: &Self::Note) -> Option<&Self::EncryptedMemo> {
+        note.memo.as_ref()
+    }
+
+    #[inline]
+    fn attach_memo<R>(
+        &self,
+        address: &Self::Address,
+        memo: &Self::Memo,
+        note: &mut Self::Note,
+        rng: &mut R,
+    ) where
+        R: RngCore + ?Sized,
+    {
+        note.memo = Some(
+            MemoEncryptionScheme::<C>::new(
+                StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
+                Default::default(),
+            )
+            .encrypt_into(
+                &address.receiving_key,
+                &Randomness::from_key(rng.gen()),
+                (),
+                memo,
+                &mut (),
+            ),
+        );
+    }
+
+    #[inline]
+    fn open_memo(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        note: &Self::Note,
+    ) -> Option<Self::Memo> {
+        MemoEncryptionScheme::<C>::new(
+            StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
+            Default::default(),
+        )
+        .decrypt(
+            decryption_key,
+            &(),
+            &note.memo.as_ref()?.ciphertext,
+            &mut (),
+        )
+    }
+}
+
 impl<C> utxo::DeriveAddress for Parameters<C>
 where
     C: Configuration<Bool = bool>,
@@ -1639,8 +1730,8 @@ where
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "AddressPartition<C>: Deserialize<'de>, IncomingNote<C>: Deserialize<'de>, LightIncomingNote<C>: Deserialize<'de>",
-            serialize = "AddressPartition<C>: Serialize, IncomingNote<C>: Serialize, LightIncomingNote<C>: Serialize",
+            deserialize = "AddressPartition<C>: Deserialize<'de>, IncomingNote<C>: Deserialize<'de>, LightIncomingNote<C>: Deserialize<'de>, EncryptedMemo<C>: Deserialize<'de>",
+            serialize = "AddressPartition<C>: Serialize, IncomingNote<C>: Serialize, LightIncomingNote<C>: Serialize, EncryptedMemo<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -1649,19 +1740,25 @@ where
 #[derive(derivative::Derivative)]
 #[derivative(
     Clone(
-        bound = "AddressPartition<C>: Clone, IncomingNote<C>: Clone, LightIncomingNote<C>: Clone"
+        bound = "AddressPartition<C>: Clone, IncomingNote<C>: Clone, LightIncomingNote<C>: Clone, EncryptedMemo<C>: Clone"
+    ),
+    Copy(
+        bound = "AddressPartition<C>: Copy, IncomingNote<C>: Copy, LightIncomingNote<C>: Copy, EncryptedMemo<C>: Copy"
     ),
-    Copy(bound = "AddressPartition<C>: Copy, IncomingNote<C>: Copy, LightIncomingNote<C>: Copy"),
     Debug(
-        bound = "AddressPartition<C>: Debug, IncomingNote<C>: Debug, LightIncomingNote<C>: Debug"
+        bound = "AddressPartition<C>: Debug, IncomingNote<C>: Debug, LightIncomingNote<C>: Debug, EncryptedMemo<C>: Debug"
     ),
     Default(
         bound = "AddressPartition<C>: Default, IncomingNote<C>: Default, LightIncomingNote<C>: Default"
     ),
-    Eq(bound = "AddressPartition<C>: Eq, IncomingNote<C>: Eq, LightIncomingNote<C>: Eq"),
-    Hash(bound = "AddressPartition<C>: Hash, IncomingNote<C>: Hash, LightIncomingNote<C>: Hash"),
+    Eq(
+        bound = "AddressPartition<C>: Eq, IncomingNote<C>: Eq, LightIncomingNote<C>: Eq, EncryptedMemo<C>: Eq"
+    ),
+    Hash(
+        bound = "AddressPartition<C>: Hash, IncomingNote<C>: Hash, LightIncomingNote<C>: Hash, EncryptedMemo<C>: Hash"
+    ),
     PartialEq(
-        bound = "AddressPartition<C>: cmp::PartialEq, IncomingNote<C>: cmp::PartialEq, LightIncomingNote<C>: cmp::PartialEq"
+        bound = "AddressPartition<C>: cmp::PartialEq, IncomingNote<C>: cmp::PartialEq, LightIncomingNote<C>: cmp::PartialEq, EncryptedMemo<C>: cmp::PartialEq"
     )
 )]
 pub struct FullIncomingNote<C>
@@ -1676,13 +1773,36 @@ where
 
     /// Light Incoming Note
     pub light_incoming_note: LightIncomingNote<C>,
+
+    /// Encrypted Memo
+    ///
+    /// The memo is not part of the transfer proof. It is only encrypted for the recipient.
+    ///
+    /// The memo is not part of the [`Encode`] implementation of the note either. It is encoded by
+    /// the [`TransferPostBody`] which carries the note instead, see [`MEMO_ENCODING_VERSION`] for
+    /// more. A note is only [`Copy`] if its [`EncryptedMemo`] is, which is not the case for memo
+    /// ciphertexts stored on the heap.
+    ///
+    /// # Malleability
+    ///
+    /// The authorization signature of a post is computed over the encoding of its whole body, so
+    /// it covers the memo as well. Posts without an authorization signature, like
+    /// [`ToPrivate`](crate::transfer::canonical::ToPrivate) posts, leave the memo unprotected:
+    /// anyone relaying such a post can strip its memo or replace it with another ciphertext
+    /// without invalidating the post.
+    ///
+    /// [`TransferPostBody`]: crate::transfer::TransferPostBody
+    /// [`MEMO_ENCODING_VERSION`]: crate::transfer::MEMO_ENCODING_VERSION
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memo: Option<EncryptedMemo<C>>,
 }
 
 impl<C> FullIncomingNote<C>
 where
     C: Configuration<Bool = bool> + ?Sized,
 {
-    /// Builds a new [`FullIncomingNote`] from `address_partition`, `incoming_note` and `light_incoming_note`.
+    /// Builds a new [`FullIncomingNote`] from `address_partition`, `incoming_note` and
+    /// `light_incoming_note` without a memo.
     #[inline]
     pub fn new(
         address_partition: AddressPartition<C>,
@@ -1693,6 +1813,7 @@ where
             address_partition,
             incoming_note,
             light_incoming_note,
+            memo: None,
         }
     }
 }
@@ -2438,6 +2559,16 @@ where
     }
 }
 
+impl<C> utxo::QueryVisibility for Identifier<C>
+where
+    C: BaseConfiguration<Bool = bool>,
+{
+    #[inline]
+    fn is_transparent(&self) -> bool {
+        self.is_transparent
+    }
+}
+
 impl<C> Sample for Identifier<C>
 where
     C: BaseConfiguration<Bool = bool>,
@@ -84,6 +84,32 @@ pub trait Read<D>: Connection {
     ) -> LocalBoxFutureResult<'s, ReadResponse<D>, Self::Error>;
 }
 
+/// Ledger Connection Reorganization
+///
+/// Ledgers can roll back to an earlier state and drop data that clients already read, like during
+/// a chain reorganization. Connections which implement this `trait` report how far back a client
+/// has to go to be consistent with the ledger again.
+pub trait Reorganization<D>: Read<D> {
+    /// Returns the latest checkpoint at or before `checkpoint` which is still consistent with the
+    /// ledger, or `None` if the ledger has not dropped any of the data read up to `checkpoint`.
+    fn fork_point<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Option<Self::Checkpoint>, Self::Error>;
+}
+
+/// Ledger Connection Subscription
+///
+/// Connections which implement this `trait` are notified by the ledger whenever it has new data,
+/// so that clients can read it right away instead of polling.
+pub trait Subscribe<D>: Read<D> {
+    /// Waits until the ledger may have data which was not read yet.
+    ///
+    /// This method is allowed to return even if there is no new data, for example if the
+    /// connection might have missed a notification, so clients should always read afterwards.
+    fn wait_for_update(&mut self) -> LocalBoxFutureResult<(), Self::Error>;
+}
+
 /// Ledger Connection Read Response
 ///
 /// This `struct` is created by the [`read`](Read::read) method on [`Read`].
@@ -101,6 +127,14 @@ pub struct ReadResponse<D> {
     /// ledger to finish the requested [`read`](Read::read).
     pub should_continue: bool,
 
+    /// Remaining Item Count
+    ///
+    /// This is the number of items that the ledger still has to send after this response to
+    /// finish the requested [`read`](Read::read), if the ledger knows it. Clients use it to report
+    /// the progress of a synchronization.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remaining: Option<usize>,
+
     /// Data Payload
     ///
     /// This is the data payload that was returned by the ledger corresponding to the
@@ -28,22 +28,26 @@
 //! [`Ledger`]: ledger::Connection
 
 use crate::{
-    asset::AssetList,
+    asset::{AssetList, CoinSelection},
+    key::AccountIndex,
     transfer::{
-        canonical::{Transaction, TransactionKind},
-        Address, Asset, Configuration, IdentifiedAsset, TransferPost, UtxoAccumulatorModel,
+        canonical::{Transaction, TransactionCheckError, TransactionKind},
+        utxo::protocol::Visibility,
+        Address, Asset, Configuration, IdentifiedAsset, Memo, TransferPost, UtxoAccumulatorModel,
     },
     wallet::{
         balance::{BTreeMapBalanceState, BalanceState},
         ledger::ReadResponse,
         signer::{
-            BalanceUpdate, IdentityRequest, IdentityResponse, SignError, SignRequest, SignResponse,
-            SignWithTransactionDataResponse, SyncData, SyncError, SyncRequest, SyncResponse,
-            TransactionDataRequest, TransactionDataResponse,
+            BalanceUpdate, ConsolidateRequest, ConsolidateResponse, IdentityRequest,
+            IdentityResponse, PlanRequest, RewindError, RewindRequest, SignError, SignRequest,
+            SignResponse, SignWithTransactionDataResponse, SyncData, SyncError, SyncRequest,
+            SyncResponse, TransactionDataRequest, TransactionDataResponse,
+            TransactionHistoryRequest, TransactionHistoryResponse, TransactionPlan,
         },
     },
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use core::{fmt::Debug, hash::Hash, marker::PhantomData, ops::AddAssign};
 use manta_util::ops::ControlFlow;
 
@@ -64,8 +68,8 @@ pub mod test;
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "L: Deserialize<'de>, S::Checkpoint: Deserialize<'de>, S: Deserialize<'de>, B: Deserialize<'de>",
-            serialize = "L: Serialize, S::Checkpoint: Serialize, S: Serialize, B: Serialize",
+            deserialize = "L: Deserialize<'de>, C::AssetId: Deserialize<'de>, S::Checkpoint: Deserialize<'de>, S: Deserialize<'de>, B: Deserialize<'de>",
+            serialize = "L: Serialize, C::AssetId: Serialize, S::Checkpoint: Serialize, S: Serialize, B: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -74,12 +78,13 @@ pub mod test;
 #[derive(derivative::Derivative)]
 #[derivative(
     Clone(bound = "L: Clone, S::Checkpoint: Clone, S: Clone, B: Clone"),
-    Copy(bound = "L: Copy, S::Checkpoint: Copy, S: Copy, B: Copy"),
-    Debug(bound = "L: Debug, S::Checkpoint: Debug, S: Debug, B: Debug"),
+    Debug(bound = "L: Debug, C::AssetId: Debug, S::Checkpoint: Debug, S: Debug, B: Debug"),
     Default(bound = "L: Default, S::Checkpoint: Default, S: Default, B: Default"),
-    Eq(bound = "L: Eq, S::Checkpoint: Eq, S: Eq, B: Eq"),
-    Hash(bound = "L: Hash, S::Checkpoint: Hash, S: Hash, B: Hash"),
-    PartialEq(bound = "L: PartialEq, S::Checkpoint: PartialEq, S: PartialEq, B: PartialEq")
+    Eq(bound = "L: Eq, C::AssetId: Eq, S::Checkpoint: Eq, S: Eq, B: Eq"),
+    Hash(bound = "L: Hash, C::AssetId: Hash, S::Checkpoint: Hash, S: Hash, B: Hash"),
+    PartialEq(
+        bound = "L: PartialEq, C::AssetId: PartialEq, S::Checkpoint: PartialEq, S: PartialEq, B: PartialEq"
+    )
 )]
 pub struct Wallet<
     C,
@@ -95,6 +100,18 @@ pub struct Wallet<
     /// Ledger Connection
     ledger: L,
 
+    /// Account
+    #[cfg_attr(feature = "serde", serde(default))]
+    account: AccountIndex,
+
+    /// Coin Selection Strategy
+    #[cfg_attr(feature = "serde", serde(default))]
+    coin_selection: Option<CoinSelection>,
+
+    /// Non-Fungible Assets
+    #[cfg_attr(feature = "serde", serde(default))]
+    non_fungible: BTreeSet<C::AssetId>,
+
     /// Ledger Checkpoint
     checkpoint: S::Checkpoint,
 
@@ -104,6 +121,10 @@ pub struct Wallet<
     /// Balance State
     assets: B,
 
+    /// Transparent Balance State
+    #[cfg_attr(feature = "serde", serde(default))]
+    transparent_assets: B,
+
     /// Type Parameter Marker
     __: PhantomData<C>,
 }
@@ -118,12 +139,22 @@ where
     /// Builds a new [`Wallet`] without checking if `ledger`, `checkpoint`, `signer`, and `assets`
     /// are properly synchronized.
     #[inline]
-    fn new_unchecked(ledger: L, checkpoint: S::Checkpoint, signer: S, assets: B) -> Self {
+    fn new_unchecked(
+        ledger: L,
+        account: AccountIndex,
+        checkpoint: S::Checkpoint,
+        signer: S,
+        assets: B,
+    ) -> Self {
         Self {
             ledger,
+            account,
+            coin_selection: None,
+            non_fungible: BTreeSet::new(),
             checkpoint,
             signer,
             assets,
+            transparent_assets: Default::default(),
             __: PhantomData,
         }
     }
@@ -141,7 +172,22 @@ where
     /// [`restart`]: Self::restart
     #[inline]
     pub fn new(ledger: L, signer: S) -> Self {
-        Self::new_unchecked(ledger, Default::default(), signer, Default::default())
+        Self::with_account(ledger, signer, Default::default())
+    }
+
+    /// Starts a new [`Wallet`] for `account` from existing `signer` and `ledger` connections.
+    ///
+    /// See [`new`](Self::new) for more on setting up the wallet. Several wallets for different
+    /// accounts can share the same signer, which synchronizes all of its accounts at once.
+    #[inline]
+    pub fn with_account(ledger: L, signer: S, account: AccountIndex) -> Self {
+        Self::new_unchecked(
+            ledger,
+            account,
+            Default::default(),
+            signer,
+            Default::default(),
+        )
     }
 
     /// Returns a mutable reference to the [`Connection`](signer::Connection).
@@ -171,6 +217,7 @@ where
     fn reset_state(&mut self) {
         self.checkpoint = Default::default();
         self.assets = Default::default();
+        self.transparent_assets = Default::default();
     }
 
     /// Returns the current balance associated with this `id`.
@@ -205,12 +252,72 @@ where
         &self.assets
     }
 
+    /// Returns the balance associated with this `id` which is held in transparent UTXOs. This
+    /// balance is already included in [`balance`](Self::balance).
+    #[inline]
+    pub fn transparent_balance(&self, id: &C::AssetId) -> C::AssetValue {
+        self.transparent_assets.balance(id)
+    }
+
+    /// Returns a shared reference to the balance state held in transparent UTXOs by `self`.
+    #[inline]
+    pub fn transparent_assets(&self) -> &B {
+        &self.transparent_assets
+    }
+
+    /// Returns the [`AccountIndex`] of the account managed by `self`.
+    #[inline]
+    pub fn account(&self) -> AccountIndex {
+        self.account
+    }
+
+    /// Returns the [`CoinSelection`] strategy sent along with every signing request of `self`.
+    #[inline]
+    pub fn coin_selection(&self) -> Option<CoinSelection> {
+        self.coin_selection
+    }
+
+    /// Sets the [`CoinSelection`] strategy sent along with every signing request of `self`. If it
+    /// is `None`, the signer uses its default strategy.
+    #[inline]
+    pub fn set_coin_selection(&mut self, coin_selection: Option<CoinSelection>) {
+        self.coin_selection = coin_selection;
+    }
+
+    /// Returns the ids of the assets which `self` treats as non-fungible tokens.
+    ///
+    /// The signer never splits the UTXOs of these assets into change and only spends them to pay
+    /// exactly one unit, and transactions which withdraw any other value of these assets are
+    /// rejected. See [`SignRequest::non_fungible`] for more.
+    #[inline]
+    pub fn non_fungible(&self) -> &BTreeSet<C::AssetId> {
+        &self.non_fungible
+    }
+
+    /// Sets the ids of the assets which `self` treats as non-fungible tokens to `non_fungible`.
+    /// See [`non_fungible`](Self::non_fungible) for more.
+    #[inline]
+    pub fn set_non_fungible(&mut self, non_fungible: BTreeSet<C::AssetId>) {
+        self.non_fungible = non_fungible;
+    }
+
     /// Returns a shared reference to the ledger connection associated to `self`.
     #[inline]
     pub fn ledger(&self) -> &L {
         &self.ledger
     }
 
+    /// Returns a mutable reference to the ledger connection associated to `self`.
+    ///
+    /// # Note
+    ///
+    /// Posts written through this connection are only reflected in the balance state of `self`
+    /// after the next call to [`sync`](Self::sync).
+    #[inline]
+    pub fn ledger_mut(&mut self) -> &mut L {
+        &mut self.ledger
+    }
+
     /// Returns the [`Checkpoint`](ledger::Checkpoint) representing the current state of this
     /// wallet.
     #[inline]
@@ -232,10 +339,21 @@ where
     where
         L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>,
     {
+        self.resume().await?;
+        self.sync().await
+    }
+
+    /// Resets `self` to the last checkpoint and balance state committed by the signer, without
+    /// pulling any data from the ledger. This is how a wallet recovers from an interrupted
+    /// [`sync_with_progress`](Self::sync_with_progress), after which the synchronization can be
+    /// started again from where the signer left off.
+    ///
+    /// The signer commits its state after every step of the synchronization, so as long as that
+    /// state is persisted, at most one ledger read is lost when a client is killed mid-sync.
+    #[inline]
+    pub async fn resume(&mut self) -> Result<(), Error<C, L, S>> {
         self.reset_state();
-        self.load_initial_state().await?;
-        while self.sync_with().await?.is_continue() {}
-        Ok(())
+        self.load_initial_state().await
     }
 
     /// Loads initial checkpoint and balance state from the signer. This method is used by
@@ -243,7 +361,12 @@ where
     /// checkpoint exists.
     #[inline]
     async fn load_initial_state(&mut self) -> Result<(), Error<C, L, S>> {
-        self.signer_sync(Default::default()).await
+        self.signer_sync(SyncRequest {
+            account: self.account,
+            origin_checkpoint: Default::default(),
+            data: Default::default(),
+        })
+        .await
     }
 
     /// Pulls data from the ledger, synchronizing the wallet and balance state. This method loops
@@ -280,29 +403,172 @@ where
     where
         L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>,
     {
-        self.sync_with().await
+        Ok(ControlFlow::should_continue(
+            self.sync_with().await?.should_continue,
+        ))
     }
 
-    /// Pulls data from the ledger, synchronizing the wallet and balance state.
+    /// Pulls data from the ledger, synchronizing the wallet and balance state, and calls
+    /// `progress` with a [`SyncProgress`] report after every [`sync_partial`](Self::sync_partial)
+    /// step. The synchronization is cancelled as soon as `progress` returns
+    /// [`BREAK`](ControlFlow::BREAK).
+    ///
+    /// This method returns [`CONTINUE`](ControlFlow::CONTINUE) if it was cancelled before
+    /// arriving at the current ledger state and [`BREAK`](ControlFlow::BREAK) otherwise, in the
+    /// same way as [`sync_partial`](Self::sync_partial). Every reported checkpoint has already
+    /// been committed by the signer, so a cancelled synchronization can be continued with another
+    /// call to this method, or with [`resume`](Self::resume) if `self` was dropped.
+    ///
+    /// # Failure Conditions
+    ///
+    /// See [`sync`](Self::sync) for the failure conditions of this method.
     #[inline]
-    async fn sync_with(&mut self) -> Result<ControlFlow, Error<C, L, S>>
+    pub async fn sync_with_progress<F>(
+        &mut self,
+        mut progress: F,
+    ) -> Result<ControlFlow, Error<C, L, S>>
+    where
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>,
+        F: FnMut(&SyncProgress<S::Checkpoint>) -> ControlFlow,
+    {
+        let mut processed = 0;
+        loop {
+            let ReadResponse {
+                should_continue,
+                remaining,
+                data,
+            } = self.sync_with().await?;
+            processed += data;
+            let report = SyncProgress {
+                processed,
+                remaining,
+                checkpoint: self.checkpoint.clone(),
+            };
+            if !should_continue {
+                let _ = progress(&report);
+                return Ok(ControlFlow::BREAK);
+            }
+            if progress(&report).is_break() {
+                return Ok(ControlFlow::CONTINUE);
+            }
+        }
+    }
+
+    /// Pulls data from the ledger, synchronizing the wallet and balance state, and returns the
+    /// number of items that were synchronized.
+    #[inline]
+    async fn sync_with(&mut self) -> Result<ReadResponse<usize>, Error<C, L, S>>
     where
         L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>,
     {
         let ReadResponse {
             should_continue,
+            remaining,
             data,
         } = self
             .ledger
             .read(&self.checkpoint)
             .await
             .map_err(Error::LedgerConnectionError)?;
+        let item_count = data.len();
         self.signer_sync(SyncRequest {
+            account: self.account,
             origin_checkpoint: self.checkpoint.clone(),
             data,
         })
         .await?;
-        Ok(ControlFlow::should_continue(should_continue))
+        Ok(ReadResponse {
+            should_continue,
+            remaining,
+            data: item_count,
+        })
+    }
+
+    /// Waits for the ledger to notify that it has new data and then pulls it like
+    /// [`sync`](Self::sync).
+    ///
+    /// # Failure Conditions
+    ///
+    /// This method returns the same errors as [`sync`](Self::sync), along with the ledger
+    /// connection errors raised while waiting for the notification.
+    #[inline]
+    pub async fn sync_on_update(&mut self) -> Result<(), Error<C, L, S>>
+    where
+        L: ledger::Subscribe<SyncData<C>, Checkpoint = S::Checkpoint>,
+    {
+        self.ledger
+            .wait_for_update()
+            .await
+            .map_err(Error::LedgerConnectionError)?;
+        self.sync().await
+    }
+
+    /// Checks whether the ledger dropped data which `self` already synchronized, like after a
+    /// chain reorganization, and if so rewinds the signer and `self` to the latest state that the
+    /// signer retained before the fork point, returning `true`. Call [`sync`](Self::sync)
+    /// afterwards to catch up with the new ledger state.
+    ///
+    /// # Failure Conditions
+    ///
+    /// This method returns [`InconsistencyError::LedgerReorganization`] if the signer did not
+    /// retain any state from before the fork point. See the [`InconsistencyError`] type for how to
+    /// resolve it.
+    #[inline]
+    pub async fn rewind(&mut self) -> Result<bool, Error<C, L, S>>
+    where
+        L: ledger::Reorganization<SyncData<C>, Checkpoint = S::Checkpoint>,
+    {
+        let fork_point = match self
+            .ledger
+            .fork_point(&self.checkpoint)
+            .await
+            .map_err(Error::LedgerConnectionError)?
+        {
+            Some(fork_point) => fork_point,
+            _ => return Ok(false),
+        };
+        match self
+            .signer
+            .rewind(RewindRequest {
+                account: self.account,
+                checkpoint: fork_point,
+            })
+            .await
+            .map_err(Error::SignerConnectionError)?
+        {
+            Ok(response) => {
+                self.apply_sync_response(response)?;
+                Ok(true)
+            }
+            Err(RewindError::MissingCheckpoint) => Err(Error::Inconsistency(
+                InconsistencyError::LedgerReorganization,
+            )),
+        }
+    }
+
+    /// Updates the checkpoint and balance state of `self` with the signer `response`.
+    #[inline]
+    fn apply_sync_response(
+        &mut self,
+        response: SyncResponse<C, S::Checkpoint>,
+    ) -> Result<(), Error<C, L, S>> {
+        match response.balance_update {
+            BalanceUpdate::Partial { deposit, withdraw } => {
+                self.assets.deposit_all(deposit);
+                if !self.assets.withdraw_all(withdraw) {
+                    return Err(Error::Inconsistency(InconsistencyError::WalletBalance));
+                }
+            }
+            BalanceUpdate::Full { assets } => {
+                self.assets.clear();
+                self.assets.deposit_all(assets);
+            }
+        }
+        self.transparent_assets.clear();
+        self.transparent_assets
+            .deposit_all(response.transparent_assets);
+        self.checkpoint = response.checkpoint;
+        Ok(())
     }
 
     /// Performs a synchronization with the signer against the given `request`.
@@ -317,25 +583,7 @@ where
             .await
             .map_err(Error::SignerConnectionError)?
         {
-            Ok(SyncResponse {
-                checkpoint,
-                balance_update,
-            }) => {
-                match balance_update {
-                    BalanceUpdate::Partial { deposit, withdraw } => {
-                        self.assets.deposit_all(deposit);
-                        if !self.assets.withdraw_all(withdraw) {
-                            return Err(Error::Inconsistency(InconsistencyError::WalletBalance));
-                        }
-                    }
-                    BalanceUpdate::Full { assets } => {
-                        self.assets.clear();
-                        self.assets.deposit_all(assets);
-                    }
-                }
-                self.checkpoint = checkpoint;
-                Ok(())
-            }
+            Ok(response) => self.apply_sync_response(response),
             Err(SyncError::InconsistentSynchronization { checkpoint }) => {
                 if checkpoint < self.checkpoint {
                     self.checkpoint = checkpoint;
@@ -359,13 +607,14 @@ where
     /// This method is already called by [`post`](Self::post), but can be used by custom
     /// implementations to perform checks elsewhere.
     #[inline]
-    fn check<'s>(
-        &'s self,
-        transaction: &'s Transaction<C>,
-    ) -> Result<TransactionKind<C>, Asset<C>> {
-        transaction
-            .check(move |a| self.contains(a))
-            .map_err(Clone::clone)
+    fn check(
+        &self,
+        transaction: &Transaction<C>,
+    ) -> Result<TransactionKind<C>, TransactionCheckError<C>> {
+        transaction.check(
+            move |a| self.contains(a),
+            move |id| self.non_fungible.contains(id),
+        )
     }
 
     /// Signs the `transaction` using the signer connection, sending `metadata` for context. This
@@ -377,12 +626,54 @@ where
         transaction: Transaction<C>,
         metadata: Option<S::AssetMetadata>,
     ) -> Result<SignResponse<C>, Error<C, L, S>> {
-        self.check(&transaction)
-            .map_err(Error::InsufficientBalance)?;
+        self.sign_with(transaction, metadata, Default::default())
+            .await
+    }
+
+    /// Signs the `transaction` using the signer connection like [`sign`](Self::sign), minting the
+    /// UTXOs paid out by the `transaction` with the given `options`.
+    #[inline]
+    pub async fn sign_with(
+        &mut self,
+        transaction: Transaction<C>,
+        metadata: Option<S::AssetMetadata>,
+        options: SignOptions<C>,
+    ) -> Result<SignResponse<C>, Error<C, L, S>> {
+        self.check(&transaction)?;
         self.signer
             .sign(SignRequest {
+                account: self.account,
                 transaction,
                 metadata,
+                coin_selection: self.coin_selection,
+                non_fungible: self.non_fungible.clone(),
+                visibility: options.visibility,
+                memo: options.memo,
+            })
+            .await
+            .map_err(Error::SignerConnectionError)?
+            .map_err(Error::SignError)
+    }
+
+    /// Plans the `transaction` using the signer connection without building any proofs, returning
+    /// the assets it would spend, the shapes of the posts it would generate and their estimated
+    /// proving time. This method _does not_ automatically sychronize with the ledger. To do this,
+    /// call the [`sync`](Self::sync) method separately.
+    ///
+    /// Signing the same `transaction` reproduces the plan, unless the wallet selects assets with
+    /// [`CoinSelection::Random`], in which case signing selects them again.
+    #[inline]
+    pub async fn plan(
+        &mut self,
+        transaction: Transaction<C>,
+    ) -> Result<TransactionPlan<C>, Error<C, L, S>> {
+        self.check(&transaction)?;
+        self.signer
+            .plan(PlanRequest {
+                account: self.account,
+                transaction,
+                coin_selection: self.coin_selection,
+                non_fungible: self.non_fungible.clone(),
             })
             .await
             .map_err(Error::SignerConnectionError)?
@@ -443,22 +734,94 @@ where
         transaction: Transaction<C>,
         metadata: Option<S::AssetMetadata>,
     ) -> Result<L::Response, Error<C, L, S>>
+    where
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
+            + ledger::Write<Vec<TransferPost<C>>>,
+    {
+        self.post_with(transaction, metadata, Default::default())
+            .await
+    }
+
+    /// Posts a transaction to the ledger like [`post`](Self::post), minting the UTXOs paid out by
+    /// the `transaction` with the given `options`.
+    #[inline]
+    pub async fn post_with(
+        &mut self,
+        transaction: Transaction<C>,
+        metadata: Option<S::AssetMetadata>,
+        options: SignOptions<C>,
+    ) -> Result<L::Response, Error<C, L, S>>
     where
         L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
             + ledger::Write<Vec<TransferPost<C>>>,
     {
         self.sync().await?;
-        let SignResponse { posts } = self.sign(transaction, metadata).await?;
+        let SignResponse { posts } = self.sign_with(transaction, metadata, options).await?;
         self.ledger
             .write(posts)
             .await
             .map_err(Error::LedgerConnectionError)
     }
 
-    /// Returns the address.
+    /// Merges the UTXOs of `asset_id` owned by the account of `self` into fewer UTXOs, posting at
+    /// most `max_posts` self-transfers to the ledger. This method automatically synchronizes with
+    /// the ledger before consolidating, _but not after_.
+    ///
+    /// This method returns `None` if there were no UTXOs left to merge, so it can be called
+    /// repeatedly during idle periods until it returns `None`. See [`post`](Self::post) for the
+    /// failure conditions.
+    #[inline]
+    pub async fn consolidate(
+        &mut self,
+        asset_id: C::AssetId,
+        max_posts: usize,
+    ) -> Result<Option<L::Response>, Error<C, L, S>>
+    where
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
+            + ledger::Write<Vec<TransferPost<C>>>,
+    {
+        self.sync().await?;
+        let ConsolidateResponse { posts, .. } = self
+            .signer
+            .consolidate(ConsolidateRequest {
+                account: self.account,
+                asset_id,
+                max_posts,
+            })
+            .await
+            .map_err(Error::SignerConnectionError)?
+            .map_err(Error::SignError)?;
+        if posts.is_empty() {
+            return Ok(None);
+        }
+        self.ledger
+            .write(posts)
+            .await
+            .map(Some)
+            .map_err(Error::LedgerConnectionError)
+    }
+
+    /// Returns the address of the account managed by `self`.
     #[inline]
     pub async fn address(&mut self) -> Result<Option<Address<C>>, S::Error> {
-        self.signer.address().await
+        self.signer.account_address(self.account).await
+    }
+
+    /// Returns at most `limit` entries of the transaction history of the account managed by
+    /// `self`, skipping the first `offset` entries.
+    #[inline]
+    pub async fn transaction_history(
+        &mut self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<TransactionHistoryResponse<C, S::Checkpoint>, S::Error> {
+        self.signer
+            .transaction_history(TransactionHistoryRequest {
+                account: self.account,
+                offset,
+                limit,
+            })
+            .await
     }
 
     /// Signs `transaction` and returns the [`TransferPost`]s and the
@@ -472,12 +835,16 @@ where
     where
         TransferPost<C>: Clone,
     {
-        self.check(&transaction)
-            .map_err(Error::InsufficientBalance)?;
+        self.check(&transaction)?;
         self.signer
             .sign_with_transaction_data(SignRequest {
+                account: self.account,
                 transaction,
                 metadata,
+                coin_selection: self.coin_selection,
+                non_fungible: self.non_fungible.clone(),
+                visibility: Default::default(),
+                memo: None,
             })
             .await
             .map_err(Error::SignerConnectionError)?
@@ -485,6 +852,95 @@ where
     }
 }
 
+/// Signing Options
+///
+/// These options control how the UTXOs paid out by a transaction are minted by the
+/// [`sign_with`](Wallet::sign_with) and [`post_with`](Wallet::post_with) methods on [`Wallet`].
+/// The default options mint opaque UTXOs without a memo, like [`sign`](Wallet::sign) and
+/// [`post`](Wallet::post) do.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Memo<C>: Deserialize<'de>",
+            serialize = "Memo<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Memo<C>: Clone"),
+    Copy(bound = "Memo<C>: Copy"),
+    Debug(bound = "Memo<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Memo<C>: Eq"),
+    Hash(bound = "Memo<C>: Hash"),
+    PartialEq(bound = "Memo<C>: PartialEq")
+)]
+pub struct SignOptions<C>
+where
+    C: Configuration,
+{
+    /// Visibility
+    ///
+    /// This is the visibility of the UTXOs paid out by the transaction. Transparent UTXOs reveal
+    /// their asset on the ledger, so they can be audited publicly while remaining in the private
+    /// pool. They are tracked separately by the signer and reported in
+    /// [`transparent_balance`](Wallet::transparent_balance).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visibility: Visibility,
+
+    /// Memo
+    ///
+    /// If it is given, this memo is encrypted for the recipient of each UTXO paid out by the
+    /// transaction, who receives it in the [`SyncResponse`] of the synchronization which finds the
+    /// payment. The memo is only protected against tampering when the post carries an
+    /// authorization signature. See [`SignRequest::memo`] for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memo: Option<Memo<C>>,
+}
+
+/// Synchronization Progress
+///
+/// This `struct` is reported by the [`sync_with_progress`](Wallet::sync_with_progress) method on
+/// [`Wallet`] after every synchronization step. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyncProgress<T> {
+    /// Processed Item Count
+    ///
+    /// This is the number of UTXOs and nullifiers synchronized since the start of the call to
+    /// [`sync_with_progress`](Wallet::sync_with_progress).
+    pub processed: usize,
+
+    /// Remaining Item Count
+    ///
+    /// This is the number of items that the ledger still has to send, if the ledger reports it.
+    /// See [`ReadResponse::remaining`] for more.
+    pub remaining: Option<usize>,
+
+    /// Checkpoint
+    ///
+    /// This is the last checkpoint committed by the signer.
+    pub checkpoint: T,
+}
+
+impl<T> SyncProgress<T> {
+    /// Returns the total number of items to synchronize, if the ledger reports how many items
+    /// are remaining.
+    #[inline]
+    pub fn total(&self) -> Option<usize> {
+        Some(self.processed + self.remaining?)
+    }
+}
+
 /// Inconsistency Error
 ///
 /// This `enum` is the error state for the [`sync`](Wallet::sync) method on [`Wallet`]. See its
@@ -519,6 +975,17 @@ pub enum InconsistencyError {
     /// other errors continue or if there is reason to suspect that the signer or ledger connections
     /// (or their true state) are corrupted, a full recovery is required.
     SignerSynchronization,
+
+    /// Ledger Reorganization Inconsistency
+    ///
+    /// ⚠️  This error causes the wallet system to enter an inconsistent state. ⚠️
+    ///
+    /// This error state arises whenever the ledger drops data older than every state retained by
+    /// the signer, so that [`rewind`](Wallet::rewind) cannot undo it. To resolve this error,
+    /// synchronize the signer again from the beginning of the ledger and perform a wallet reset
+    /// with a call to [`restart`](Wallet::restart). Retaining more signer states makes this error
+    /// less likely for deep reorganizations.
+    LedgerReorganization,
 }
 
 /// Wallet Error
@@ -567,6 +1034,11 @@ where
     /// Insufficient Balance
     InsufficientBalance(Asset<C>),
 
+    /// Partial Non-Fungible Transfer
+    ///
+    /// See [`TransactionCheckError::PartialNonFungibleTransfer`] for more.
+    PartialNonFungibleTransfer(Asset<C>),
+
     /// Inconsistency Error
     ///
     /// See the documentation of [`InconsistencyError`] for more.
@@ -588,6 +1060,23 @@ where
     MissingProofAuthorizationKey,
 }
 
+impl<C, L, S> From<TransactionCheckError<C>> for Error<C, L, S>
+where
+    C: Configuration,
+    L: ledger::Connection,
+    S: signer::Connection<C>,
+{
+    #[inline]
+    fn from(err: TransactionCheckError<C>) -> Self {
+        match err {
+            TransactionCheckError::InsufficientBalance(asset) => Self::InsufficientBalance(asset),
+            TransactionCheckError::PartialNonFungibleTransfer(asset) => {
+                Self::PartialNonFungibleTransfer(asset)
+            }
+        }
+    }
+}
+
 impl<C, L, S> From<InconsistencyError> for Error<C, L, S>
 where
     C: Configuration,
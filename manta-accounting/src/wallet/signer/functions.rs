@@ -17,8 +17,8 @@
 //! Signer Functions
 
 use crate::{
-    asset::AssetMap,
-    key::{Account, DeriveAddress},
+    asset::{AssetMap, CoinSelection, NonFungible},
+    key::{Account, AccountIndex, DeriveAddress},
     transfer::{
         self,
         batch::Join,
@@ -26,30 +26,45 @@ use crate::{
             MultiProvingContext, PrivateTransfer, PrivateTransferShape, Selection, ToPrivate,
             ToPublic, Transaction, TransactionData, TransferShape,
         },
+        internal_pair,
         receiver::ReceiverPost,
         requires_authorization,
-        utxo::{auth::DeriveContext, DeriveDecryptionKey, DeriveSpend, Spend, UtxoReconstruct},
-        Address, Asset, AssociatedData, Authorization, AuthorizationContext, FullParametersRef,
-        IdentifiedAsset, Identifier, IdentityProof, Note, Nullifier, Parameters, PreSender,
-        ProvingContext, Receiver, Sender, Shape, SpendingKey, Transfer, TransferPost, Utxo,
-        UtxoAccumulatorItem, UtxoAccumulatorModel,
+        utxo::{
+            self, auth::DeriveContext, protocol::Visibility, DeriveDecryptionKey, DeriveSpend,
+            NoteMemo, QueryVisibility, Spend, UtxoReconstruct,
+        },
+        Address, Asset, AssociatedData, Authorization, AuthorizationContext, Compiler,
+        DecryptionKey, FullParametersRef, IdentifiedAsset, Identifier, IdentityProof, Memo, Note,
+        Nullifier, Parameters, PreSender, ProvingContext, Receiver, Sender, Shape, SpendingKey,
+        Transfer, TransferPost, Utxo, UtxoAccumulatorItem, UtxoAccumulatorModel,
     },
     wallet::signer::{
-        AccountTable, BalanceUpdate, Checkpoint, Configuration, SignError, SignResponse,
-        SignWithTransactionDataResponse, SignWithTransactionDataResult, SignerParameters, SyncData,
-        SyncError, SyncRequest, SyncResponse,
+        history::{EntryKind, Events, PendingTransaction, TransactionHistory},
+        AccountAssetMap, AccountTable, AuthorizationContextMap, BalanceUpdate, Checkpoint,
+        Configuration, ConsolidateResponse, ConsolidateResult, ParallelSafe, PlanResult, SignError,
+        SignResponse, SignWithTransactionDataResponse, SignWithTransactionDataResult,
+        SignerParameters, SyncData, SyncError, SyncRequest, SyncResponse, TransactionPlan,
     },
 };
-use alloc::{vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use core::time::Duration;
 use manta_crypto::{
     accumulator::{Accumulator, ItemHashFunction, OptimizedAccumulator},
+    constraint::measure::{Measure, Size},
     rand::Rand,
 };
 use manta_util::{
-    array_map, cmp::Independence, into_array_unchecked, iter::IteratorExt, persistence::Rollback,
-    vec::VecExt,
+    array_map, cfg_into_iter, cmp::Independence, into_array_unchecked, iter::IteratorExt,
+    persistence::Rollback, vec::VecExt,
 };
 
+#[cfg(feature = "rayon")]
+use manta_util::rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
 /// Returns the default account for `accounts`.
 #[inline]
 pub fn default_account<C>(accounts: &AccountTable<C>) -> Account<C::Account>
@@ -59,52 +74,51 @@ where
     accounts.get_default()
 }
 
-/// Returns the default spending key for `accounts`.
+/// Returns the spending key for `account`.
 #[inline]
-fn default_spending_key<C>(accounts: &AccountTable<C>, parameters: &C::Parameters) -> SpendingKey<C>
+fn spending_key<C>(account: &Account<C::Account>, parameters: &C::Parameters) -> SpendingKey<C>
 where
     C: Configuration,
 {
     let _ = parameters;
-    accounts.get_default().spending_key()
+    account.spending_key()
 }
 
-/// Returns the default authorization context for `accounts`.
+/// Returns the authorization context for `account`.
 #[inline]
-pub fn default_authorization_context<C>(
-    accounts: &AccountTable<C>,
+pub fn authorization_context<C>(
+    account: &Account<C::Account>,
     parameters: &C::Parameters,
 ) -> AuthorizationContext<C>
 where
     C: Configuration,
 {
-    parameters.derive_context(&default_spending_key::<C>(accounts, parameters))
+    parameters.derive_context(&spending_key::<C>(account, parameters))
 }
 
-/// Returns the authorization for the default spending key of `accounts`.
+/// Returns the default authorization context for `accounts`.
 #[inline]
-fn authorization_for_default_spending_key<C>(
+pub fn default_authorization_context<C>(
     accounts: &AccountTable<C>,
     parameters: &C::Parameters,
-    rng: &mut C::Rng,
-) -> Authorization<C>
+) -> AuthorizationContext<C>
 where
     C: Configuration,
 {
-    Authorization::<C>::from_spending_key(
-        parameters,
-        &default_spending_key::<C>(accounts, parameters),
-        rng,
-    )
+    authorization_context::<C>(&default_account::<C>(accounts), parameters)
 }
 
-/// Returns the address for the default account of `accounts`.
+/// Returns the authorization for the spending key of `account`.
 #[inline]
-fn default_address<C>(accounts: &AccountTable<C>, parameters: &C::Parameters) -> Address<C>
+fn authorization_for_spending_key<C>(
+    account: &Account<C::Account>,
+    parameters: &C::Parameters,
+    rng: &mut C::Rng,
+) -> Authorization<C>
 where
     C: Configuration,
 {
-    accounts.get_default().address(parameters)
+    Authorization::<C>::from_spending_key(parameters, &spending_key::<C>(account, parameters), rng)
 }
 
 /// Hashes `utxo` using the [`UtxoAccumulatorItemHash`](transfer::Configuration::UtxoAccumulatorItemHash)
@@ -119,7 +133,8 @@ where
         .item_hash(utxo, &mut ())
 }
 
-/// Inserts the hash of `utxo` in `utxo_accumulator`.
+/// Inserts the hash of `utxo` in `utxo_accumulator`, recording its deposit along with `memo` in
+/// `events`.
 #[allow(clippy::too_many_arguments)]
 #[inline]
 fn insert_next_item<C>(
@@ -129,8 +144,9 @@ fn insert_next_item<C>(
     parameters: &Parameters<C>,
     utxo: Utxo<C>,
     identified_asset: IdentifiedAsset<C>,
+    memo: Option<Memo<C>>,
     nullifiers: &mut Vec<Nullifier<C>>,
-    deposit: &mut Vec<Asset<C>>,
+    events: &mut Events<C>,
     rng: &mut C::Rng,
 ) where
     C: Configuration,
@@ -142,26 +158,30 @@ fn insert_next_item<C>(
         asset.clone(),
         rng,
     );
+    let item = item_hash::<C>(parameters, &utxo);
     if computed_utxo.is_related(&utxo) {
         if let Some(index) = nullifiers
             .iter()
             .position(move |n| n.is_related(&nullifier))
         {
-            nullifiers.remove(index);
+            let nullifier = nullifiers.remove(index);
+            if !asset.is_zero() {
+                events.transient.push((utxo, nullifier, asset, memo));
+            }
         } else {
-            utxo_accumulator.insert(&item_hash::<C>(parameters, &utxo));
+            utxo_accumulator.insert(&item);
             if !asset.is_zero() {
-                deposit.push(asset.clone());
+                events.deposits.push((utxo, asset.clone(), memo));
             }
             assets.insert(identifier, asset);
             return;
         }
     }
-    utxo_accumulator.insert_nonprovable(&item_hash::<C>(parameters, &utxo));
+    utxo_accumulator.insert_nonprovable(&item);
 }
 
 /// Checks if `asset` matches with `nullifier`, removing it from the `utxo_accumulator` and
-/// inserting it into the `withdraw` set if this is the case.
+/// inserting it into the `withdraws` set if this is the case.
 #[allow(clippy::too_many_arguments)]
 #[inline]
 fn is_asset_unspent<C>(
@@ -171,7 +191,7 @@ fn is_asset_unspent<C>(
     identifier: Identifier<C>,
     asset: Asset<C>,
     nullifiers: &mut Vec<Nullifier<C>>,
-    withdraw: &mut Vec<Asset<C>>,
+    withdraws: &mut Vec<(Nullifier<C>, Asset<C>)>,
     rng: &mut C::Rng,
 ) -> bool
 where
@@ -183,10 +203,10 @@ where
         .iter()
         .position(move |n| n.is_related(&nullifier))
     {
-        nullifiers.remove(index);
+        let nullifier = nullifiers.remove(index);
         utxo_accumulator.remove_proof(&item_hash::<C>(parameters, &utxo));
         if !asset.is_zero() {
-            withdraw.push(asset);
+            withdraws.push((nullifier, asset));
         }
         false
     } else {
@@ -194,81 +214,176 @@ where
     }
 }
 
-/// Updates the internal ledger state, returning the new asset distribution.
+/// Minimum Number of Notes Trial-Decrypted by each Rayon Task
+#[cfg(feature = "rayon")]
+const TRIAL_DECRYPTION_BATCH_SIZE: usize = 64;
+
+/// Trial-decrypts every note in `inserts` with each of the `decryption_keys`, returning the
+/// index of the first key which opens each note along with its contents and its memo, in ledger
+/// order.
+///
+/// With the `rayon` feature enabled, the notes are decrypted in parallel, in batches of at least
+/// `TRIAL_DECRYPTION_BATCH_SIZE` notes.
+#[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
+#[inline]
+fn trial_decrypt<C>(
+    parameters: &Parameters<C>,
+    decryption_keys: &[(AccountIndex, DecryptionKey<C>)],
+    inserts: Vec<(Utxo<C>, Note<C>)>,
+) -> Vec<(
+    Utxo<C>,
+    Option<(AccountIndex, (Identifier<C>, Asset<C>), Option<Memo<C>>)>,
+)>
+where
+    C: Configuration,
+    Asset<C>: ParallelSafe,
+    DecryptionKey<C>: ParallelSafe,
+    Identifier<C>: ParallelSafe,
+    Memo<C>: ParallelSafe,
+    Note<C>: Clone + ParallelSafe,
+    Parameters<C>: ParallelSafe,
+    Utxo<C>: ParallelSafe,
+{
+    cfg_into_iter!(inserts, TRIAL_DECRYPTION_BATCH_SIZE)
+        .map(|(utxo, note)| {
+            let opened = decryption_keys.iter().find_map(|(index, decryption_key)| {
+                parameters
+                    .open_with_check(decryption_key, &utxo, note.clone())
+                    .map(|opened| (*index, opened, parameters.open_memo(decryption_key, &note)))
+            });
+            (utxo, opened)
+        })
+        .collect()
+}
+
+/// Updates the internal ledger state and transaction history for every account in
+/// `authorization_contexts`, returning the new asset distribution of `account`.
 #[allow(clippy::too_many_arguments)]
 #[inline]
-fn sync_with<C, I>(
-    authorization_context: &mut AuthorizationContext<C>,
-    assets: &mut C::AssetMap,
+fn sync_with<C>(
+    authorization_contexts: &mut AuthorizationContextMap<C>,
+    assets: &mut AccountAssetMap<C>,
+    history: &mut TransactionHistory<C>,
     checkpoint: &mut C::Checkpoint,
     utxo_accumulator: &mut C::UtxoAccumulator,
     parameters: &Parameters<C>,
-    inserts: I,
+    inserts: Vec<(Utxo<C>, Note<C>)>,
     mut nullifiers: Vec<Nullifier<C>>,
     is_partial: bool,
+    account: AccountIndex,
     rng: &mut C::Rng,
 ) -> SyncResponse<C, C::Checkpoint>
 where
     C: Configuration,
-    I: Iterator<Item = (Utxo<C>, Note<C>)>,
+    Asset<C>: ParallelSafe,
+    DecryptionKey<C>: ParallelSafe,
+    Identifier<C>: ParallelSafe,
+    Memo<C>: Clone + ParallelSafe,
+    Note<C>: Clone + ParallelSafe,
+    Parameters<C>: ParallelSafe,
+    Utxo<C>: ParallelSafe,
 {
     let nullifier_count = nullifiers.len();
-    let mut deposit = Vec::new();
-    let mut withdraw = Vec::new();
-    let decryption_key = parameters.derive_decryption_key(authorization_context);
-    for (utxo, note) in inserts {
-        if let Some((identifier, asset)) = parameters.open_with_check(&decryption_key, &utxo, note)
-        {
-            insert_next_item::<C>(
-                authorization_context,
+    let observed = inserts.len() + nullifier_count;
+    let mut events = BTreeMap::<AccountIndex, Events<C>>::new();
+    let decryption_keys = authorization_contexts
+        .iter_mut()
+        .map(|(index, authorization_context)| {
+            (
+                *index,
+                parameters.derive_decryption_key(authorization_context),
+            )
+        })
+        .collect::<Vec<_>>();
+    for (utxo, opened) in trial_decrypt::<C>(parameters, &decryption_keys, inserts) {
+        match opened {
+            Some((index, (identifier, asset), memo)) => insert_next_item::<C>(
+                authorization_contexts
+                    .get_mut(&index)
+                    .expect("The decryption key was derived from this authorization context."),
                 utxo_accumulator,
-                assets,
+                assets.entry(index).or_default(),
                 parameters,
                 utxo,
                 transfer::utxo::IdentifiedAsset::new(identifier, asset),
+                memo,
                 &mut nullifiers,
-                &mut deposit,
+                events.entry(index).or_default(),
                 rng,
-            );
-        } else {
-            utxo_accumulator.insert_nonprovable(&item_hash::<C>(parameters, &utxo));
+            ),
+            _ => {
+                utxo_accumulator.insert_nonprovable(&item_hash::<C>(parameters, &utxo));
+            }
+        }
+    }
+    for (index, authorization_context) in authorization_contexts.iter_mut() {
+        let withdraws = &mut events.entry(*index).or_default().withdraws;
+        if let Some(assets) = assets.get_mut(index) {
+            assets.retain(|identifier, assets| {
+                assets.retain(|asset| {
+                    is_asset_unspent::<C>(
+                        authorization_context,
+                        utxo_accumulator,
+                        parameters,
+                        identifier.clone(),
+                        asset.clone(),
+                        &mut nullifiers,
+                        withdraws,
+                        rng,
+                    )
+                });
+                !assets.is_empty()
+            });
         }
     }
-    assets.retain(|identifier, assets| {
-        assets.retain(|asset| {
-            is_asset_unspent::<C>(
-                authorization_context,
-                utxo_accumulator,
-                parameters,
-                identifier.clone(),
-                asset.clone(),
-                &mut nullifiers,
-                &mut withdraw,
-                rng,
-            )
-        });
-        !assets.is_empty()
-    });
     checkpoint.update_from_nullifiers(nullifier_count);
     checkpoint.update_from_utxo_accumulator(utxo_accumulator);
+    let account_events = events.get(&account);
+    let memos = account_events
+        .map(Events::received_memos)
+        .unwrap_or_default();
+    let balance_update = if is_partial {
+        BalanceUpdate::Partial {
+            deposit: account_events
+                .map(Events::deposited_assets)
+                .unwrap_or_default(),
+            withdraw: account_events
+                .map(Events::withdrawn_assets)
+                .unwrap_or_default(),
+        }
+    } else {
+        BalanceUpdate::Full {
+            assets: assets
+                .get(&account)
+                .map(|assets| assets.assets().into())
+                .unwrap_or_default(),
+        }
+    };
+    history.update(events, observed, checkpoint);
     SyncResponse {
         checkpoint: checkpoint.clone(),
-        balance_update: if is_partial {
-            // TODO: Whenever we are doing a full update, don't even build the `deposit` and
-            //       `withdraw` vectors, since we won't be needing them.
-            BalanceUpdate::Partial { deposit, withdraw }
-        } else {
-            BalanceUpdate::Full {
-                assets: assets.assets().into(),
-            }
-        },
+        balance_update,
+        transparent_assets: transparent_assets::<C>(assets, account),
+        memos,
     }
 }
 
+/// Returns the sum of the assets held in transparent UTXOs by `account`.
+#[inline]
+pub fn transparent_assets<C>(assets: &AccountAssetMap<C>, account: AccountIndex) -> Vec<Asset<C>>
+where
+    C: Configuration,
+{
+    assets
+        .get(&account)
+        .map(|assets| assets.assets_where(QueryVisibility::is_transparent).into())
+        .unwrap_or_default()
+}
+
 /// Builds the [`PreSender`] associated to `identifier` and `asset`.
 #[inline]
 fn build_pre_sender<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     parameters: &Parameters<C>,
     identifier: Identifier<C>,
     asset: Asset<C>,
@@ -279,32 +394,34 @@ where
 {
     PreSender::<C>::sample(
         parameters,
-        &mut default_authorization_context::<C>(accounts, parameters),
+        &mut authorization_context::<C>(account, parameters),
         identifier,
         asset,
         rng,
     )
 }
 
-/// Builds the [`Receiver`] associated with `address` and `asset`.
+/// Builds the [`Receiver`] associated with `address` and `asset`, attaching `memo` to its note if
+/// it is given.
 #[inline]
 fn receiver<C>(
     parameters: &Parameters<C>,
     address: Address<C>,
     asset: Asset<C>,
     associated_data: AssociatedData<C>,
+    memo: Option<&Memo<C>>,
     rng: &mut C::Rng,
 ) -> Receiver<C>
 where
     C: Configuration,
 {
-    Receiver::<C>::sample(parameters, address, asset, associated_data, rng)
+    Receiver::<C>::sample_with_memo(parameters, address, asset, associated_data, memo, rng)
 }
 
-/// Builds the [`Receiver`] associated with the default address and `asset`.
+/// Builds the [`Receiver`] associated with the address of `account` and `asset`.
 #[inline]
-fn default_receiver<C>(
-    accounts: &AccountTable<C>,
+fn account_receiver<C>(
+    account: &Account<C::Account>,
     parameters: &Parameters<C>,
     asset: Asset<C>,
     rng: &mut C::Rng,
@@ -312,29 +429,42 @@ fn default_receiver<C>(
 where
     C: Configuration,
 {
-    let default_address = default_address::<C>(accounts, parameters);
-    receiver::<C>(parameters, default_address, asset, Default::default(), rng)
+    receiver::<C>(
+        parameters,
+        account.address(parameters),
+        asset,
+        Default::default(),
+        None,
+        rng,
+    )
 }
 
-/// Selects the pre-senders which collectively own at least `asset`, returning any change.
+/// Selects the pre-senders which collectively own at least `asset` using `coin_selection`,
+/// wrapped in [`NonFungible`] if `non_fungible` contains the id of `asset`, returning any change.
 #[inline]
 fn select<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     assets: &C::AssetMap,
     parameters: &Parameters<C>,
     asset: &Asset<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
     rng: &mut C::Rng,
 ) -> Result<Selection<C>, SignError<C>>
 where
     C: Configuration,
 {
-    let selection = assets.select(asset);
+    let selection = if non_fungible.contains(&asset.id) {
+        assets.select_with(asset, &NonFungible(coin_selection), rng)
+    } else {
+        assets.select_with(asset, &coin_selection, rng)
+    };
     if !asset.is_zero() && selection.is_empty() {
         return Err(SignError::InsufficientBalance(asset.clone()));
     }
     Selection::new(selection, move |k, v| {
         Ok(build_pre_sender::<C>(
-            accounts,
+            account,
             parameters,
             k,
             Asset::<C>::new(asset.id.clone(), v),
@@ -376,7 +506,7 @@ fn build_post<
     const RECEIVERS: usize,
     const SINKS: usize,
 >(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     utxo_accumulator_model: &UtxoAccumulatorModel<C>,
     parameters: &Parameters<C>,
     proving_context: &ProvingContext<C>,
@@ -386,7 +516,7 @@ fn build_post<
 where
     C: Configuration,
 {
-    let spending_key = default_spending_key::<C>(accounts, parameters);
+    let spending_key = spending_key::<C>(account, parameters);
     build_post_inner(
         FullParametersRef::<C>::new(parameters, utxo_accumulator_model),
         proving_context,
@@ -400,7 +530,7 @@ where
 #[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
 #[inline]
 fn next_join<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     parameters: &Parameters<C>,
     asset_id: &C::AssetId,
     total: C::AssetValue,
@@ -411,8 +541,8 @@ where
 {
     Ok(Join::new(
         parameters,
-        &mut default_authorization_context::<C>(accounts, parameters),
-        default_address::<C>(accounts, parameters),
+        &mut authorization_context::<C>(account, parameters),
+        account.address(parameters),
         Asset::<C>::new(asset_id.clone(), total),
         rng,
     ))
@@ -422,7 +552,7 @@ where
 #[allow(clippy::too_many_arguments)]
 #[inline]
 fn prepare_final_pre_senders<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     assets: &C::AssetMap,
     utxo_accumulator: &C::UtxoAccumulator,
     parameters: &Parameters<C>,
@@ -447,7 +577,7 @@ where
     needed_zeroes -= zeroes.len();
     for zero in zeroes {
         let pre_sender = build_pre_sender::<C>(
-            accounts,
+            account,
             parameters,
             zero,
             Asset::<C>::new(asset_id.clone(), Default::default()),
@@ -479,7 +609,7 @@ where
         let identifier = rng.gen();
         senders.push(
             build_pre_sender::<C>(
-                accounts,
+                account,
                 parameters,
                 identifier,
                 Asset::<C>::new(asset_id.clone(), Default::default()),
@@ -494,7 +624,7 @@ where
 /// Builds two virtual [`Sender`]s for `pre_sender`.
 #[inline]
 fn virtual_senders<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     utxo_accumulator_model: &UtxoAccumulatorModel<C>,
     parameters: &Parameters<C>,
     asset_id: &C::AssetId,
@@ -513,7 +643,7 @@ where
     let identifier = rng.gen();
     senders.push(
         build_pre_sender::<C>(
-            accounts,
+            account,
             parameters,
             identifier,
             Asset::<C>::new(asset_id.clone(), Default::default()),
@@ -528,7 +658,7 @@ where
 #[allow(clippy::too_many_arguments)]
 #[inline]
 fn compute_batched_transactions<C>(
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
     parameters: &Parameters<C>,
@@ -553,16 +683,15 @@ where
                     .expect("Unable to upgrade expected UTXO.")
             });
             let (receivers, mut join) = next_join(
-                accounts,
+                account,
                 parameters,
                 asset_id,
                 senders.iter().map(|s| s.asset().value).sum(),
                 rng,
             )?;
-            let authorization =
-                authorization_for_default_spending_key::<C>(accounts, parameters, rng);
+            let authorization = authorization_for_spending_key::<C>(account, parameters, rng);
             posts.push(build_post(
-                accounts,
+                account,
                 utxo_accumulator.model(),
                 parameters,
                 &proving_context.private_transfer,
@@ -577,7 +706,7 @@ where
         pre_senders = joins;
     }
     let final_presenders = prepare_final_pre_senders(
-        accounts,
+        account,
         assets,
         utxo_accumulator,
         parameters,
@@ -589,22 +718,28 @@ where
     Ok(into_array_unchecked(final_presenders))
 }
 
-/// Returns the [`Address`] corresponding to `accounts`.
+/// Returns the [`Address`] corresponding to `account` in `accounts`.
 #[inline]
-pub fn address<C>(parameters: &SignerParameters<C>, accounts: &AccountTable<C>) -> Address<C>
+pub fn address<C>(
+    parameters: &SignerParameters<C>,
+    accounts: &AccountTable<C>,
+    account: AccountIndex,
+) -> Option<Address<C>>
 where
     C: Configuration,
 {
-    let account = default_account::<C>(accounts);
-    account.address(&parameters.parameters)
+    Some(accounts.get(account)?.address(&parameters.parameters))
 }
 
-/// Updates `assets`, `checkpoint` and `utxo_accumulator`, returning the new asset distribution.
+/// Updates `assets`, `history`, `checkpoint` and `utxo_accumulator` for all the accounts in
+/// `authorization_contexts`, returning the new asset distribution of the account in `request`.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sync<C>(
     parameters: &SignerParameters<C>,
-    authorization_context: &mut AuthorizationContext<C>,
-    assets: &mut C::AssetMap,
+    authorization_contexts: &mut AuthorizationContextMap<C>,
+    assets: &mut AccountAssetMap<C>,
+    history: &mut TransactionHistory<C>,
     checkpoint: &mut C::Checkpoint,
     utxo_accumulator: &mut C::UtxoAccumulator,
     mut request: SyncRequest<C, C::Checkpoint>,
@@ -612,6 +747,13 @@ pub fn sync<C>(
 ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>>
 where
     C: Configuration,
+    Asset<C>: ParallelSafe,
+    DecryptionKey<C>: ParallelSafe,
+    Identifier<C>: ParallelSafe,
+    Memo<C>: Clone + ParallelSafe,
+    Note<C>: Clone + ParallelSafe,
+    Parameters<C>: ParallelSafe,
+    Utxo<C>: ParallelSafe,
 {
     // TODO: Do a capacity check on the current UTXO accumulator?
     //
@@ -631,15 +773,17 @@ where
             utxo_note_data,
             nullifier_data,
         } = request.data;
-        let response = sync_with::<C, _>(
-            authorization_context,
+        let response = sync_with::<C>(
+            authorization_contexts,
             assets,
+            history,
             checkpoint,
             utxo_accumulator,
             &parameters.parameters,
-            utxo_note_data.into_iter(),
+            utxo_note_data,
             nullifier_data,
             !has_pruned,
+            request.account,
             rng,
         );
         utxo_accumulator.commit();
@@ -647,24 +791,38 @@ where
     }
 }
 
-/// Signs a withdraw transaction for `asset` sent to `address`.
+/// Signs a withdraw transaction for `asset` sent to `address` with the given `visibility`,
+/// attaching `memo` to the payment if it is given.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 fn sign_withdraw<C>(
     parameters: &SignerParameters<C>,
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
     asset: Asset<C>,
     address: Option<Address<C>>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo<C>>,
     rng: &mut C::Rng,
 ) -> Result<SignResponse<C>, SignError<C>>
 where
     C: Configuration,
 {
-    let selection = select(accounts, assets, &parameters.parameters, &asset, rng)?;
+    let selection = select(
+        account,
+        assets,
+        &parameters.parameters,
+        &asset,
+        coin_selection,
+        non_fungible,
+        rng,
+    )?;
     let mut posts = Vec::new();
     let senders = compute_batched_transactions(
-        accounts,
+        account,
         assets,
         utxo_accumulator,
         &parameters.parameters,
@@ -674,25 +832,25 @@ where
         &mut posts,
         rng,
     )?;
-    let change = default_receiver::<C>(
-        accounts,
+    let change = account_receiver::<C>(
+        account,
         &parameters.parameters,
         Asset::<C>::new(asset.id.clone(), selection.change),
         rng,
     );
-    let authorization =
-        authorization_for_default_spending_key::<C>(accounts, &parameters.parameters, rng);
+    let authorization = authorization_for_spending_key::<C>(account, &parameters.parameters, rng);
     let final_post = match address {
         Some(address) => {
             let receiver = receiver::<C>(
                 &parameters.parameters,
                 address,
                 asset,
-                Default::default(),
+                visibility,
+                memo.as_ref(),
                 rng,
             );
             build_post(
-                accounts,
+                account,
                 utxo_accumulator.model(),
                 &parameters.parameters,
                 &parameters.proving_context.private_transfer,
@@ -701,7 +859,7 @@ where
             )?
         }
         _ => build_post(
-            accounts,
+            account,
             utxo_accumulator.model(),
             &parameters.parameters,
             &parameters.proving_context.to_public,
@@ -713,14 +871,132 @@ where
     Ok(SignResponse::new(posts))
 }
 
-/// Signs the `transaction`, generating transfer posts without releasing resources.
+/// Signs a batch of private transfers paying each asset in `payments` to its address with the
+/// given `visibility` and `memo`, spending the change of each payment in the next payment with
+/// the same asset id.
+///
+/// The outputs of every post are inserted into `utxo_accumulator` in the order in which the
+/// ledger inserts them, so that the witnesses of later posts are built against the roots the
+/// ledger has once the earlier posts are applied.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn sign_batch_private_transfer<C>(
+    parameters: &SignerParameters<C>,
+    account: &Account<C::Account>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    payments: Vec<(Asset<C>, Address<C>)>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo<C>>,
+    rng: &mut C::Rng,
+) -> Result<SignResponse<C>, SignError<C>>
+where
+    C: Configuration,
+{
+    let mut posts = Vec::new();
+    for total in Transaction::<C>::batch_totals(&payments) {
+        let selection = select(
+            account,
+            assets,
+            &parameters.parameters,
+            &total,
+            coin_selection,
+            non_fungible,
+            rng,
+        )?;
+        let mut senders = compute_batched_transactions(
+            account,
+            assets,
+            utxo_accumulator,
+            &parameters.parameters,
+            &parameters.proving_context,
+            &total.id,
+            selection.pre_senders,
+            &mut posts,
+            rng,
+        )?;
+        let group = payments
+            .iter()
+            .filter(|(asset, _)| asset.id == total.id)
+            .collect::<Vec<_>>();
+        let mut changes = Vec::with_capacity(group.len());
+        let mut change = selection.change;
+        for (asset, _) in group.iter().rev() {
+            changes.push(change.clone());
+            change += asset.value.clone();
+        }
+        let mut group = group.into_iter().zip(changes.into_iter().rev()).peekable();
+        while let Some(((asset, address), change)) = group.next() {
+            let (change, pre_sender) = internal_pair::<C, _>(
+                &parameters.parameters,
+                &mut authorization_context::<C>(account, &parameters.parameters),
+                account.address(&parameters.parameters),
+                Asset::<C>::new(total.id.clone(), change),
+                Default::default(),
+                rng,
+            );
+            let receiver = receiver::<C>(
+                &parameters.parameters,
+                address.clone(),
+                asset.clone(),
+                visibility,
+                memo.as_ref(),
+                rng,
+            );
+            let authorization =
+                authorization_for_spending_key::<C>(account, &parameters.parameters, rng);
+            let post = build_post(
+                account,
+                utxo_accumulator.model(),
+                &parameters.parameters,
+                &parameters.proving_context.private_transfer,
+                PrivateTransfer::build(authorization, senders, [change, receiver]),
+                rng,
+            )?;
+            pre_sender.insert_utxo(&parameters.parameters, utxo_accumulator);
+            utxo_accumulator.insert_nonprovable(&item_hash::<C>(
+                &parameters.parameters,
+                &post.body.receiver_posts[1].utxo,
+            ));
+            posts.push(post);
+            if group.peek().is_none() {
+                break;
+            }
+            let identifier = rng.gen();
+            senders = [
+                pre_sender
+                    .try_upgrade(&parameters.parameters, utxo_accumulator)
+                    .expect("Unable to upgrade expected UTXO."),
+                build_pre_sender::<C>(
+                    account,
+                    &parameters.parameters,
+                    identifier,
+                    Asset::<C>::new(total.id.clone(), Default::default()),
+                    rng,
+                )
+                .upgrade_unchecked(Default::default()),
+            ];
+        }
+    }
+    Ok(SignResponse::new(posts))
+}
+
+/// Signs the `transaction` selecting assets with `coin_selection` and minting the UTXOs it pays
+/// out with `visibility` and `memo`, generating transfer posts without releasing resources.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 fn sign_internal<C>(
     parameters: &SignerParameters<C>,
-    accounts: &AccountTable<C>,
+    account: &Account<C::Account>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
     transaction: Transaction<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo<C>>,
     rng: &mut C::Rng,
 ) -> Result<SignResponse<C>, SignError<C>>
 where
@@ -728,10 +1004,16 @@ where
 {
     match transaction {
         Transaction::ToPrivate(asset) => {
-            let receiver =
-                default_receiver::<C>(accounts, &parameters.parameters, asset.clone(), rng);
+            let receiver = receiver::<C>(
+                &parameters.parameters,
+                account.address(&parameters.parameters),
+                asset.clone(),
+                visibility,
+                memo.as_ref(),
+                rng,
+            );
             Ok(SignResponse::new(vec![build_post(
-                accounts,
+                account,
                 utxo_accumulator.model(),
                 &parameters.parameters,
                 &parameters.proving_context.to_private,
@@ -741,50 +1023,348 @@ where
         }
         Transaction::PrivateTransfer(asset, address) => sign_withdraw(
             parameters,
-            accounts,
+            account,
             assets,
             utxo_accumulator,
             asset,
             Some(address),
+            coin_selection,
+            non_fungible,
+            visibility,
+            memo,
             rng,
         ),
         Transaction::ToPublic(asset) => sign_withdraw(
             parameters,
-            accounts,
+            account,
             assets,
             utxo_accumulator,
             asset,
             None,
+            coin_selection,
+            non_fungible,
+            visibility,
+            memo,
+            rng,
+        ),
+        Transaction::BatchPrivateTransfer(payments) => sign_batch_private_transfer(
+            parameters,
+            account,
+            assets,
+            utxo_accumulator,
+            payments,
+            coin_selection,
+            non_fungible,
+            visibility,
+            memo,
             rng,
         ),
     }
 }
 
-/// Signs the `transaction`, generating transfer posts.
+/// Signs the `transaction` spending from `account` with the assets selected by `coin_selection`
+/// and minting the UTXOs it pays out with `visibility` and `memo`, generating transfer posts.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sign<C>(
     parameters: &SignerParameters<C>,
     accounts: &AccountTable<C>,
-    assets: &C::AssetMap,
+    account: AccountIndex,
+    assets: &AccountAssetMap<C>,
     utxo_accumulator: &mut C::UtxoAccumulator,
     transaction: Transaction<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo<C>>,
     rng: &mut C::Rng,
 ) -> Result<SignResponse<C>, SignError<C>>
 where
     C: Configuration,
 {
+    let empty_assets = Default::default();
     let result = sign_internal(
         parameters,
-        accounts,
-        assets,
+        &accounts.get(account).ok_or(SignError::MissingSpendingKey)?,
+        assets.get(&account).unwrap_or(&empty_assets),
         utxo_accumulator,
         transaction,
+        coin_selection,
+        non_fungible,
+        visibility,
+        memo,
         rng,
     )?;
     utxo_accumulator.rollback();
     Ok(result)
 }
 
+/// Signs self-transfers which merge the UTXOs of `asset_id` owned by `account` pairwise, smallest
+/// first, without releasing resources.
+#[inline]
+fn consolidate_internal<C>(
+    parameters: &SignerParameters<C>,
+    account: &Account<C::Account>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    asset_id: C::AssetId,
+    max_posts: usize,
+    rng: &mut C::Rng,
+) -> ConsolidateResult<C>
+where
+    C: Configuration,
+{
+    let balance = Asset::<C>::new(asset_id.clone(), assets.assets().value(&asset_id));
+    let mut pre_senders = select(
+        account,
+        assets,
+        &parameters.parameters,
+        &balance,
+        CoinSelection::SmallestFirst,
+        &BTreeSet::new(),
+        rng,
+    )?
+    .pre_senders;
+    let mut posts = Vec::new();
+    while posts.len() < max_posts && pre_senders.len() >= PrivateTransferShape::SENDERS {
+        let senders = into_array_unchecked(
+            pre_senders
+                .drain(..PrivateTransferShape::SENDERS)
+                .map(|s| {
+                    s.try_upgrade(&parameters.parameters, utxo_accumulator)
+                        .expect("Unable to upgrade expected UTXO.")
+                })
+                .collect::<Vec<_>>(),
+        );
+        let (receivers, join) = next_join(
+            account,
+            &parameters.parameters,
+            &asset_id,
+            senders.iter().map(|s: &Sender<C>| s.asset().value).sum(),
+            rng,
+        )?;
+        let authorization =
+            authorization_for_spending_key::<C>(account, &parameters.parameters, rng);
+        posts.push(build_post(
+            account,
+            utxo_accumulator.model(),
+            &parameters.parameters,
+            &parameters.proving_context.private_transfer,
+            PrivateTransfer::build(authorization, senders, receivers),
+            rng,
+        )?);
+        join.insert_utxos(&parameters.parameters, utxo_accumulator);
+        pre_senders.push(join.pre_sender);
+    }
+    Ok(ConsolidateResponse {
+        posts,
+        utxo_count: pre_senders.len(),
+    })
+}
+
+/// Signs self-transfers which merge the UTXOs of `asset_id` owned by `account` pairwise, smallest
+/// first, using at most `max_posts` posts.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn consolidate<C>(
+    parameters: &SignerParameters<C>,
+    accounts: &AccountTable<C>,
+    account: AccountIndex,
+    assets: &AccountAssetMap<C>,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    asset_id: C::AssetId,
+    max_posts: usize,
+    rng: &mut C::Rng,
+) -> ConsolidateResult<C>
+where
+    C: Configuration,
+{
+    let empty_assets = Default::default();
+    let result = consolidate_internal(
+        parameters,
+        &accounts.get(account).ok_or(SignError::MissingSpendingKey)?,
+        assets.get(&account).unwrap_or(&empty_assets),
+        utxo_accumulator,
+        asset_id,
+        max_posts,
+        rng,
+    );
+    utxo_accumulator.rollback();
+    result
+}
+
+/// Appends to `shapes` the posts which [`compute_batched_transactions`] generates to join
+/// `count` pre-senders down to the senders of a single final post.
+#[inline]
+fn batched_shapes(mut count: usize, shapes: &mut Vec<TransferShape>) {
+    while count > PrivateTransferShape::SENDERS {
+        let joins = count / PrivateTransferShape::SENDERS;
+        shapes.resize(shapes.len() + joins, TransferShape::PrivateTransfer);
+        count = joins + count % PrivateTransferShape::SENDERS;
+    }
+}
+
+/// Selects the assets which pay for `asset` using `coin_selection`, wrapped in [`NonFungible`] if
+/// `non_fungible` contains the id of `asset`, adding them, their change and the posts which join
+/// them to `plan`.
+#[inline]
+fn plan_selection<C>(
+    assets: &C::AssetMap,
+    asset: &Asset<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    plan: &mut TransactionPlan<C>,
+    rng: &mut C::Rng,
+) -> Result<(), SignError<C>>
+where
+    C: Configuration,
+{
+    let selection = if non_fungible.contains(&asset.id) {
+        assets.select_with(asset, &NonFungible(coin_selection), rng)
+    } else {
+        assets.select_with(asset, &coin_selection, rng)
+    };
+    if !asset.is_zero() && selection.is_empty() {
+        return Err(SignError::InsufficientBalance(asset.clone()));
+    }
+    batched_shapes(selection.values.len(), &mut plan.shapes);
+    plan.change
+        .push(Asset::<C>::new(asset.id.clone(), selection.change));
+    plan.selected.extend(
+        selection
+            .values
+            .into_iter()
+            .map(|(key, value)| (key, Asset::<C>::new(asset.id.clone(), value))),
+    );
+    Ok(())
+}
+
+/// Measures the circuit of the canonical transfer with the given `shape`.
+#[inline]
+fn circuit_size<C>(parameters: FullParametersRef<C>, shape: TransferShape) -> Size
+where
+    C: Configuration,
+    Compiler<C>: Measure,
+{
+    match shape {
+        TransferShape::ToPrivate => ToPrivate::<C>::unknown_constraints(parameters).measure(),
+        TransferShape::PrivateTransfer => {
+            PrivateTransfer::<C>::unknown_constraints(parameters).measure()
+        }
+        TransferShape::ToPublic => ToPublic::<C>::unknown_constraints(parameters).measure(),
+    }
+}
+
+/// Plans the `transaction` spending from `account` with the assets selected by `coin_selection`,
+/// without building any proofs.
+///
+/// The proving time is estimated from the circuit sizes and
+/// [`PROVING_THROUGHPUT`](Configuration::PROVING_THROUGHPUT). For every deterministic
+/// `coin_selection`, the posts are planned exactly as [`sign`] would generate them from the same
+/// assets. With [`CoinSelection::Random`], [`sign`] selects the assets again with fresh
+/// randomness, so it may spend other assets and generate a different number of posts than the
+/// plan.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn plan<C>(
+    parameters: &SignerParameters<C>,
+    utxo_accumulator_model: &UtxoAccumulatorModel<C>,
+    account: AccountIndex,
+    assets: &AccountAssetMap<C>,
+    transaction: Transaction<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    rng: &mut C::Rng,
+) -> PlanResult<C>
+where
+    C: Configuration,
+    Compiler<C>: Measure,
+{
+    let empty_assets = Default::default();
+    let assets = assets.get(&account).unwrap_or(&empty_assets);
+    let mut plan = TransactionPlan::default();
+    match transaction {
+        Transaction::ToPrivate(_) => plan.shapes.push(TransferShape::ToPrivate),
+        Transaction::PrivateTransfer(asset, _) => {
+            plan_selection(assets, &asset, coin_selection, non_fungible, &mut plan, rng)?;
+            plan.shapes.push(TransferShape::PrivateTransfer);
+        }
+        Transaction::ToPublic(asset) => {
+            plan_selection(assets, &asset, coin_selection, non_fungible, &mut plan, rng)?;
+            plan.shapes.push(TransferShape::ToPublic);
+        }
+        Transaction::BatchPrivateTransfer(payments) => {
+            for total in Transaction::<C>::batch_totals(&payments) {
+                plan_selection(assets, &total, coin_selection, non_fungible, &mut plan, rng)?;
+                plan.shapes.extend(
+                    payments
+                        .iter()
+                        .filter(|(asset, _)| asset.id == total.id)
+                        .map(|_| TransferShape::PrivateTransfer),
+                );
+            }
+        }
+    }
+    let parameters = FullParametersRef::<C>::new(&parameters.parameters, utxo_accumulator_model);
+    let mut sizes = Vec::<(TransferShape, Size)>::new();
+    for shape in &plan.shapes {
+        let size = match sizes.iter().find(|(measured, _)| measured == shape) {
+            Some((_, size)) => *size,
+            _ => {
+                let size = circuit_size::<C>(parameters, *shape);
+                sizes.push((*shape, size));
+                size
+            }
+        };
+        plan.size += size;
+    }
+    plan.estimated_proving_time = Duration::from_millis(
+        (plan.size.constraint_count.saturating_mul(1000) / C::PROVING_THROUGHPUT.max(1)) as u64,
+    );
+    Ok(plan)
+}
+
+/// Builds the [`PendingTransaction`] for the `transfers` signed by `account` from their `posts`,
+/// using `authorization_context` to find the non-zero assets sent back to `account`.
+#[inline]
+pub fn pending_transaction<C>(
+    parameters: &Parameters<C>,
+    authorization_context: Option<&mut AuthorizationContext<C>>,
+    account: AccountIndex,
+    transfers: Vec<(EntryKind<C>, Asset<C>)>,
+    posts: &[TransferPost<C>],
+) -> PendingTransaction<C>
+where
+    C: Configuration,
+    Note<C>: Clone,
+    Nullifier<C>: Clone,
+    Utxo<C>: Clone,
+{
+    let decryption_key =
+        authorization_context.map(|context| parameters.derive_decryption_key(context));
+    let mut nullifiers = Vec::new();
+    let mut utxos = Vec::new();
+    for post in posts {
+        nullifiers.extend(
+            post.body
+                .sender_posts
+                .iter()
+                .map(|sender_post| sender_post.nullifier.clone()),
+        );
+        if let Some(decryption_key) = &decryption_key {
+            utxos.extend(post.body.receiver_posts.iter().filter_map(|receiver_post| {
+                let (_, asset) = parameters.open_with_check(
+                    decryption_key,
+                    &receiver_post.utxo,
+                    receiver_post.note.clone(),
+                )?;
+                (!asset.is_zero()).then(|| receiver_post.utxo.clone())
+            }));
+        }
+    }
+    PendingTransaction::new(account, transfers, nullifiers, utxos)
+}
+
 /// Generates an [`IdentityProof`] for `identified_asset` by
 /// signing a virtual [`ToPublic`] transaction.
 #[inline]
@@ -794,49 +1374,79 @@ pub fn identity_proof<C>(
     utxo_accumulator_model: &UtxoAccumulatorModel<C>,
     identified_asset: IdentifiedAsset<C>,
     rng: &mut C::Rng,
-) -> Option<IdentityProof<C>>
+) -> Result<IdentityProof<C>, SignError<C>>
 where
     C: Configuration,
 {
+    let account = default_account::<C>(accounts);
     let presender = build_pre_sender::<C>(
-        accounts,
+        &account,
         &parameters.parameters,
         identified_asset.identifier,
         identified_asset.asset.clone(),
         rng,
     );
     let senders = virtual_senders::<C>(
-        accounts,
+        &account,
         utxo_accumulator_model,
         &parameters.parameters,
         &identified_asset.asset.id,
         presender,
         rng,
-    )
-    .ok()?;
-    let change = default_receiver::<C>(
-        accounts,
+    )?;
+    let change = account_receiver::<C>(
+        &account,
         &parameters.parameters,
         Asset::<C>::new(identified_asset.asset.id.clone(), Default::default()),
         rng,
     );
-    let authorization =
-        authorization_for_default_spending_key::<C>(accounts, &parameters.parameters, rng);
+    let authorization = authorization_for_spending_key::<C>(&account, &parameters.parameters, rng);
     let transfer_post = build_post(
-        accounts,
+        &account,
         utxo_accumulator_model,
         &parameters.parameters,
         &parameters.proving_context.to_public,
         ToPublic::build(authorization, senders, [change], identified_asset.asset),
         rng,
+    )?;
+    Ok(IdentityProof { transfer_post })
+}
+
+/// Returns the [`Address`] associated to `authorization_context`.
+#[inline]
+pub fn authorization_context_address<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+) -> Address<C>
+where
+    C: Configuration,
+{
+    utxo::DeriveAddress::derive_address(
+        parameters,
+        &parameters.derive_decryption_key(authorization_context),
     )
-    .ok()?;
-    Some(IdentityProof { transfer_post })
+}
+
+/// Tries to open the `receiver_post` with any of the `decryption_keys`.
+#[inline]
+fn open_receiver_post<C>(
+    parameters: &Parameters<C>,
+    decryption_keys: &[DecryptionKey<C>],
+    receiver_post: ReceiverPost<Parameters<C>>,
+) -> Option<(Identifier<C>, Asset<C>)>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    let ReceiverPost { utxo, note } = receiver_post;
+    decryption_keys
+        .iter()
+        .find_map(|decryption_key| parameters.open_with_check(decryption_key, &utxo, note.clone()))
 }
 
 /// Returns the associated [`TransactionData`] of `post`, namely the [`Asset`] and the
-/// [`Identifier`]. Returns `None` if `post` has an invalid shape, or if `accounts` doesn't own the
-/// underlying assets in `post`.
+/// [`Identifier`]. Returns `None` if `post` has an invalid shape, or if none of the accounts in
+/// `accounts` own the underlying assets in `post`.
 #[inline]
 pub fn transaction_data<C>(
     parameters: &SignerParameters<C>,
@@ -845,24 +1455,66 @@ pub fn transaction_data<C>(
 ) -> Option<TransactionData<C>>
 where
     C: Configuration,
+    Note<C>: Clone,
 {
-    let shape = TransferShape::from_post(&post)?;
     let parameters = &parameters.parameters;
-    let mut authorization_context = default_authorization_context::<C>(accounts, parameters);
-    let decryption_key = parameters.derive_decryption_key(&mut authorization_context);
-    match shape {
+    let decryption_keys = accounts
+        .accounts()
+        .map(|account| {
+            parameters.derive_decryption_key(&mut authorization_context::<C>(&account, parameters))
+        })
+        .collect::<Vec<_>>();
+    transaction_data_with::<C>(parameters, &decryption_keys, post)
+}
+
+/// Returns the associated [`TransactionData`] of `post`, namely the [`Asset`] and the
+/// [`Identifier`]. Returns `None` if `post` has an invalid shape, or if none of the
+/// `authorization_contexts` own the underlying assets in `post`.
+#[inline]
+pub fn authorization_context_transaction_data<C>(
+    parameters: &SignerParameters<C>,
+    authorization_contexts: &mut AuthorizationContextMap<C>,
+    post: TransferPost<C>,
+) -> Option<TransactionData<C>>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    let parameters = &parameters.parameters;
+    let decryption_keys = authorization_contexts
+        .values_mut()
+        .map(|authorization_context| parameters.derive_decryption_key(authorization_context))
+        .collect::<Vec<_>>();
+    transaction_data_with::<C>(parameters, &decryption_keys, post)
+}
+
+/// Returns the associated [`TransactionData`] of `post` by trying to open its receiver posts with
+/// `decryption_keys`.
+#[inline]
+fn transaction_data_with<C>(
+    parameters: &Parameters<C>,
+    decryption_keys: &[DecryptionKey<C>],
+    post: TransferPost<C>,
+) -> Option<TransactionData<C>>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    match TransferShape::from_post(&post)? {
         TransferShape::ToPrivate => {
-            let ReceiverPost { utxo, note } = post.body.receiver_posts.take_first();
-            let (identifier, asset) = parameters.open_with_check(&decryption_key, &utxo, note)?;
+            let (identifier, asset) = open_receiver_post::<C>(
+                parameters,
+                decryption_keys,
+                post.body.receiver_posts.take_first(),
+            )?;
             Some(TransactionData::<C>::ToPrivate(identifier, asset))
         }
         TransferShape::PrivateTransfer => {
             let mut transaction_data = Vec::new();
             let receiver_posts = post.body.receiver_posts;
             for receiver_post in receiver_posts.into_iter() {
-                let ReceiverPost { utxo, note } = receiver_post;
                 if let Some(identified_asset) =
-                    parameters.open_with_check(&decryption_key, &utxo, note)
+                    open_receiver_post::<C>(parameters, decryption_keys, receiver_post)
                 {
                     transaction_data.push(identified_asset);
                 }
@@ -874,35 +1526,117 @@ where
             }
         }
         TransferShape::ToPublic => {
-            let ReceiverPost { utxo, note } = post.body.receiver_posts.take_first();
-            let (identifier, asset) = parameters.open_with_check(&decryption_key, &utxo, note)?;
+            let (identifier, asset) = open_receiver_post::<C>(
+                parameters,
+                decryption_keys,
+                post.body.receiver_posts.take_first(),
+            )?;
             Some(TransactionData::<C>::ToPublic(identifier, asset))
         }
     }
 }
 
-/// Signs the `transaction`, generating transfer posts
-/// and returning their [`TransactionData`].
+/// Returns the memos attached to the receiver posts of `post` which can be opened by one of the
+/// accounts in `accounts`, along with the asset of each receiver post, in post order.
+#[inline]
+pub fn transaction_memos<C>(
+    parameters: &SignerParameters<C>,
+    accounts: &AccountTable<C>,
+    post: TransferPost<C>,
+) -> Vec<(Asset<C>, Memo<C>)>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    let parameters = &parameters.parameters;
+    let decryption_keys = accounts
+        .accounts()
+        .map(|account| {
+            parameters.derive_decryption_key(&mut authorization_context::<C>(&account, parameters))
+        })
+        .collect::<Vec<_>>();
+    transaction_memos_with::<C>(parameters, &decryption_keys, post)
+}
+
+/// Returns the memos attached to the receiver posts of `post` which can be opened by one of the
+/// `authorization_contexts`, along with the asset of each receiver post, in post order.
+#[inline]
+pub fn authorization_context_transaction_memos<C>(
+    parameters: &SignerParameters<C>,
+    authorization_contexts: &mut AuthorizationContextMap<C>,
+    post: TransferPost<C>,
+) -> Vec<(Asset<C>, Memo<C>)>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    let parameters = &parameters.parameters;
+    let decryption_keys = authorization_contexts
+        .values_mut()
+        .map(|authorization_context| parameters.derive_decryption_key(authorization_context))
+        .collect::<Vec<_>>();
+    transaction_memos_with::<C>(parameters, &decryption_keys, post)
+}
+
+/// Returns the memos attached to the receiver posts of `post` by trying to open them with
+/// `decryption_keys`.
+#[inline]
+fn transaction_memos_with<C>(
+    parameters: &Parameters<C>,
+    decryption_keys: &[DecryptionKey<C>],
+    post: TransferPost<C>,
+) -> Vec<(Asset<C>, Memo<C>)>
+where
+    C: Configuration,
+    Note<C>: Clone,
+{
+    post.body
+        .receiver_posts
+        .into_iter()
+        .filter_map(|ReceiverPost { utxo, note }| {
+            decryption_keys.iter().find_map(|decryption_key| {
+                let (_, asset) = parameters.open_with_check(decryption_key, &utxo, note.clone())?;
+                Some((asset, parameters.open_memo(decryption_key, &note)?))
+            })
+        })
+        .collect()
+}
+
+/// Signs the `transaction` spending from `account` with the assets selected by `coin_selection`
+/// and minting the UTXOs it pays out with `visibility` and `memo`, generating transfer posts and
+/// returning their [`TransactionData`].
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sign_with_transaction_data<C>(
     parameters: &SignerParameters<C>,
     accounts: &AccountTable<C>,
-    assets: &C::AssetMap,
+    account: AccountIndex,
+    assets: &AccountAssetMap<C>,
     utxo_accumulator: &mut C::UtxoAccumulator,
     transaction: Transaction<C>,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<C::AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo<C>>,
     rng: &mut C::Rng,
 ) -> SignWithTransactionDataResult<C>
 where
     C: Configuration,
     TransferPost<C>: Clone,
+    Note<C>: Clone,
 {
     Ok(SignWithTransactionDataResponse(
         sign(
             parameters,
             accounts,
+            account,
             assets,
             utxo_accumulator,
             transaction,
+            coin_selection,
+            non_fungible,
+            visibility,
+            memo,
             rng,
         )?
         .posts
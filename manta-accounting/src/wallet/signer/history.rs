@@ -0,0 +1,563 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction History
+//!
+//! The signer keeps a per-account history of the transactions it observes while synchronizing
+//! with the ledger. Transactions signed by the signer itself are first recorded as
+//! [`PendingTransaction`]s and only enter the history once their nullifiers or UTXOs appear on
+//! the ledger. Any other deposit is recorded as [`EntryKind::Received`], along with its memo, and
+//! any other withdraw as [`EntryKind::Sent`] with an unknown counterparty. Pending transactions
+//! which are never observed are dropped after [`PENDING_EXPIRY`] ledger items.
+
+use crate::{
+    key::AccountIndex,
+    transfer::{self, canonical::Transaction, Address, Asset, Memo, Nullifier, Utxo},
+    wallet::signer::Configuration,
+};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use manta_util::cmp::{Independence, IndependenceContext};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Pending Transaction Expiry
+///
+/// Number of ledger items, UTXOs and nullifiers, which the signer can observe after signing a
+/// transaction before it drops the transaction from the pending set if none of its nullifiers or
+/// UTXOs were observed in the meantime.
+pub const PENDING_EXPIRY: usize = 1 << 14;
+
+/// Transaction History Entry Kind
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Address<C>: Deserialize<'de>",
+            serialize = "Address<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Address<C>: Clone"),
+    Debug(bound = "Address<C>: Debug"),
+    Eq(bound = "Address<C>: Eq"),
+    Hash(bound = "Address<C>: Hash"),
+    PartialEq(bound = "Address<C>: PartialEq")
+)]
+pub enum EntryKind<C>
+where
+    C: transfer::Configuration,
+{
+    /// Received Private Asset
+    Received,
+
+    /// Private Transfer Asset to Address
+    ///
+    /// The counterparty address is only known for transfers which were signed by this signer.
+    Sent(Option<Address<C>>),
+
+    /// Convert Private Asset into Public Asset
+    ToPublic,
+
+    /// Convert Public Asset into Private Asset
+    ToPrivate,
+}
+
+impl<C> EntryKind<C>
+where
+    C: transfer::Configuration,
+{
+    /// Returns the [`EntryKind`] and the [`Asset`] of each of the transfers in `transaction`.
+    #[inline]
+    pub fn from_transaction(transaction: &Transaction<C>) -> Vec<(Self, Asset<C>)> {
+        match transaction {
+            Transaction::ToPrivate(asset) => vec![(Self::ToPrivate, asset.clone())],
+            Transaction::PrivateTransfer(asset, address) => {
+                vec![(Self::Sent(Some(address.clone())), asset.clone())]
+            }
+            Transaction::ToPublic(asset) => vec![(Self::ToPublic, asset.clone())],
+            Transaction::BatchPrivateTransfer(payments) => payments
+                .iter()
+                .map(|(asset, address)| (Self::Sent(Some(address.clone())), asset.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Transaction History Entry
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "EntryKind<C>: Deserialize<'de>, Asset<C>: Deserialize<'de>, Memo<C>: Deserialize<'de>, T: Deserialize<'de>",
+            serialize = "EntryKind<C>: Serialize, Asset<C>: Serialize, Memo<C>: Serialize, T: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "EntryKind<C>: Clone, Asset<C>: Clone, Memo<C>: Clone, T: Clone"),
+    Debug(bound = "EntryKind<C>: Debug, Asset<C>: Debug, Memo<C>: Debug, T: Debug"),
+    Eq(bound = "EntryKind<C>: Eq, Asset<C>: Eq, Memo<C>: Eq, T: Eq"),
+    Hash(bound = "EntryKind<C>: Hash, Asset<C>: Hash, Memo<C>: Hash, T: Hash"),
+    PartialEq(
+        bound = "EntryKind<C>: PartialEq, Asset<C>: PartialEq, Memo<C>: PartialEq, T: PartialEq"
+    )
+)]
+pub struct Entry<C, T>
+where
+    C: transfer::Configuration,
+{
+    /// Entry Kind
+    pub kind: EntryKind<C>,
+
+    /// Asset
+    ///
+    /// For transactions signed by this signer, this is the asset in the [`Transaction`]. For any
+    /// other transaction, this is the asset which was deposited to or withdrawn from the account.
+    pub asset: Asset<C>,
+
+    /// Memo
+    ///
+    /// This is the memo attached to the UTXO of a [`Received`](EntryKind::Received) entry, if its
+    /// sender attached one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memo: Option<Memo<C>>,
+
+    /// Checkpoint
+    ///
+    /// This is the checkpoint of the signer right after it observed the transaction on the
+    /// ledger.
+    pub checkpoint: T,
+}
+
+/// Pending Transaction
+///
+/// A transaction signed by the signer which was not observed on the ledger yet, or whose change
+/// was not fully observed yet.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "EntryKind<C>: Deserialize<'de>, Asset<C>: Deserialize<'de>, Nullifier<C>: Deserialize<'de>, Utxo<C>: Deserialize<'de>",
+            serialize = "EntryKind<C>: Serialize, Asset<C>: Serialize, Nullifier<C>: Serialize, Utxo<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "EntryKind<C>: Clone, Asset<C>: Clone, Nullifier<C>: Clone, Utxo<C>: Clone"),
+    Debug(bound = "EntryKind<C>: Debug, Asset<C>: Debug, Nullifier<C>: Debug, Utxo<C>: Debug"),
+    Eq(bound = "EntryKind<C>: Eq, Asset<C>: Eq, Nullifier<C>: Eq, Utxo<C>: Eq"),
+    Hash(bound = "EntryKind<C>: Hash, Asset<C>: Hash, Nullifier<C>: Hash, Utxo<C>: Hash"),
+    PartialEq(
+        bound = "EntryKind<C>: PartialEq, Asset<C>: PartialEq, Nullifier<C>: PartialEq, Utxo<C>: PartialEq"
+    )
+)]
+pub struct PendingTransaction<C>
+where
+    C: transfer::Configuration,
+{
+    /// Account
+    pub account: AccountIndex,
+
+    /// Transfers
+    ///
+    /// Each transfer becomes one history entry once the transaction is confirmed.
+    pub transfers: Vec<(EntryKind<C>, Asset<C>)>,
+
+    /// Nullifiers of the Spent Assets
+    pub nullifiers: Vec<Nullifier<C>>,
+
+    /// UTXOs of the Non-Zero Assets Sent Back to the Account
+    pub utxos: Vec<Utxo<C>>,
+
+    /// Confirmation Flag
+    pub confirmed: bool,
+
+    /// Age
+    ///
+    /// Number of ledger items observed since the transaction was signed while it was not
+    /// confirmed yet. See [`PENDING_EXPIRY`] for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub age: usize,
+}
+
+impl<C> PendingTransaction<C>
+where
+    C: transfer::Configuration,
+{
+    /// Builds a new [`PendingTransaction`] for the `transfers` signed by `account`, which spend
+    /// `nullifiers` and send the non-zero assets in `utxos` back to `account`.
+    ///
+    /// Transactions which only move assets between the UTXOs of `account` have no `transfers`,
+    /// so they never show up in the history.
+    #[inline]
+    pub fn new(
+        account: AccountIndex,
+        transfers: Vec<(EntryKind<C>, Asset<C>)>,
+        nullifiers: Vec<Nullifier<C>>,
+        utxos: Vec<Utxo<C>>,
+    ) -> Self {
+        Self {
+            account,
+            transfers,
+            nullifiers,
+            utxos,
+            confirmed: false,
+            age: 0,
+        }
+    }
+
+    /// Returns `true` if all the nullifiers and UTXOs of `self` have been observed on the ledger.
+    #[inline]
+    fn is_complete(&self) -> bool {
+        self.nullifiers.is_empty() && self.utxos.is_empty()
+    }
+
+    /// Returns `true` if `self` spends `nullifier`.
+    #[inline]
+    fn spends(&self, nullifier: &Nullifier<C>) -> bool {
+        self.nullifiers.iter().any(|n| n.is_related(nullifier))
+    }
+}
+
+/// Synchronization Events
+///
+/// The deposits and withdraws of a single account which were observed during one call to
+/// [`sync`](super::Signer::sync). Zero-valued assets are never included.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone, Memo<C>: Clone, Nullifier<C>: Clone, Utxo<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug, Memo<C>: Debug, Nullifier<C>: Debug, Utxo<C>: Debug"),
+    Default(bound = "")
+)]
+#[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
+pub struct Events<C>
+where
+    C: transfer::Configuration,
+{
+    /// Deposits
+    ///
+    /// Each deposit comes with the memo attached to its UTXO, if any.
+    pub deposits: Vec<(Utxo<C>, Asset<C>, Option<Memo<C>>)>,
+
+    /// Withdraws
+    pub withdraws: Vec<(Nullifier<C>, Asset<C>)>,
+
+    /// Transient Assets
+    ///
+    /// These are assets which were deposited and withdrawn during the same synchronization, so
+    /// they never changed the balance of the account. Each one comes with the memo attached to
+    /// its UTXO, if any.
+    pub transient: Vec<(Utxo<C>, Nullifier<C>, Asset<C>, Option<Memo<C>>)>,
+}
+
+impl<C> Events<C>
+where
+    C: transfer::Configuration,
+{
+    /// Returns the assets deposited to the account.
+    #[inline]
+    pub fn deposited_assets(&self) -> Vec<Asset<C>> {
+        self.deposits
+            .iter()
+            .map(|(_, asset, _)| asset.clone())
+            .collect()
+    }
+
+    /// Returns the assets withdrawn from the account.
+    #[inline]
+    pub fn withdrawn_assets(&self) -> Vec<Asset<C>> {
+        self.withdraws
+            .iter()
+            .map(|(_, asset)| asset.clone())
+            .collect()
+    }
+
+    /// Returns the memos attached to the UTXOs received by the account, along with the asset of
+    /// each UTXO.
+    #[inline]
+    pub fn received_memos(&self) -> Vec<(Asset<C>, Memo<C>)>
+    where
+        Memo<C>: Clone,
+    {
+        self.deposits
+            .iter()
+            .filter_map(|(_, asset, memo)| Some((asset.clone(), memo.clone()?)))
+            .chain(
+                self.transient
+                    .iter()
+                    .filter_map(|(_, _, asset, memo)| Some((asset.clone(), memo.clone()?))),
+            )
+            .collect()
+    }
+}
+
+/// Removes the first element of `items` which is related to `item`, returning `true` if there
+/// was such an element.
+#[inline]
+fn remove_related<T, I>(items: &mut Vec<T>, item: &T) -> bool
+where
+    T: Independence<I>,
+    I: IndependenceContext,
+{
+    match items.iter().position(|i| i.is_related(item)) {
+        Some(index) => {
+            items.remove(index);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Transaction History
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Entry<C, C::Checkpoint>: Deserialize<'de>, PendingTransaction<C>: Deserialize<'de>",
+            serialize = "Entry<C, C::Checkpoint>: Serialize, PendingTransaction<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Entry<C, C::Checkpoint>: Clone, PendingTransaction<C>: Clone"),
+    Debug(bound = "Entry<C, C::Checkpoint>: Debug, PendingTransaction<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Entry<C, C::Checkpoint>: Eq, PendingTransaction<C>: Eq"),
+    Hash(bound = "Entry<C, C::Checkpoint>: Hash, PendingTransaction<C>: Hash"),
+    PartialEq(bound = "Entry<C, C::Checkpoint>: PartialEq, PendingTransaction<C>: PartialEq")
+)]
+pub struct TransactionHistory<C>
+where
+    C: Configuration,
+{
+    /// Entries for each Account
+    entries: BTreeMap<AccountIndex, Vec<Entry<C, C::Checkpoint>>>,
+
+    /// Pending Transactions
+    pending: Vec<PendingTransaction<C>>,
+}
+
+impl<C> TransactionHistory<C>
+where
+    C: Configuration,
+{
+    /// Returns the entries of `account` in the order in which they were observed on the ledger.
+    #[inline]
+    pub fn entries(&self, account: AccountIndex) -> &[Entry<C, C::Checkpoint>] {
+        self.entries
+            .get(&account)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the pending transactions.
+    #[inline]
+    pub fn pending(&self) -> &[PendingTransaction<C>] {
+        &self.pending
+    }
+
+    /// Inserts `pending` into the set of transactions waiting to be observed on the ledger.
+    #[inline]
+    pub fn insert_pending(&mut self, pending: PendingTransaction<C>) {
+        self.pending.push(pending)
+    }
+
+    /// Clears all the entries and pending transactions in `self`.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending.clear();
+    }
+
+    /// Confirms the pending transaction at `index` if it was not confirmed yet.
+    #[inline]
+    fn confirm(&mut self, index: usize, checkpoint: &C::Checkpoint) {
+        let pending = &mut self.pending[index];
+        if !pending.confirmed {
+            pending.confirmed = true;
+            self.entries
+                .entry(pending.account)
+                .or_default()
+                .extend(pending.transfers.iter().map(|(kind, asset)| Entry {
+                    kind: kind.clone(),
+                    asset: asset.clone(),
+                    memo: None,
+                    checkpoint: checkpoint.clone(),
+                }));
+        }
+    }
+
+    /// Updates the history of every account with the `events` which were observed on the ledger
+    /// up to `checkpoint`, while observing `observed`-many new ledger items.
+    ///
+    /// # Implementation Note
+    ///
+    /// A pending transaction is confirmed once any of its nullifiers or UTXOs is observed. It is
+    /// kept around while the rest of them keeps being observed, and dropped after the first
+    /// update in which none of them was observed, since zero-valued senders never show up in the
+    /// `events`. Any other pending transaction spending a nullifier of a confirmed transaction
+    /// can no longer be posted and is dropped as well, and so is any pending transaction which
+    /// was not confirmed within [`PENDING_EXPIRY`] ledger items.
+    ///
+    /// Deposits are matched before withdraws, so that a nullifier spent by two pending
+    /// transactions confirms the one whose UTXOs were observed. Withdraws which do not belong to
+    /// any pending transaction are recorded as a single [`EntryKind::Sent`] entry for each
+    /// account and asset, since the ledger does not say which nullifiers were spent together.
+    #[inline]
+    pub fn update(
+        &mut self,
+        events: BTreeMap<AccountIndex, Events<C>>,
+        observed: usize,
+        checkpoint: &C::Checkpoint,
+    ) {
+        let mut matched = vec![false; self.pending.len()];
+        let mut conflicting = vec![false; self.pending.len()];
+        let mut withdraws = Vec::new();
+        for (account, events) in events {
+            for (utxo, asset, memo) in events.deposits {
+                self.deposit(account, utxo, asset, memo, checkpoint, &mut matched);
+            }
+            for (utxo, nullifier, asset, memo) in events.transient {
+                self.deposit(account, utxo, asset.clone(), memo, checkpoint, &mut matched);
+                withdraws.push((account, nullifier, asset));
+            }
+            withdraws.extend(
+                events
+                    .withdraws
+                    .into_iter()
+                    .map(|(nullifier, asset)| (account, nullifier, asset)),
+            );
+        }
+        let mut foreign = BTreeMap::<AccountIndex, Vec<Asset<C>>>::new();
+        for (account, nullifier, asset) in withdraws {
+            if !self.withdraw(&nullifier, checkpoint, &mut matched, &mut conflicting) {
+                let assets = foreign.entry(account).or_default();
+                match assets.iter_mut().find(|a| a.id == asset.id) {
+                    Some(total) => *total += asset.value,
+                    _ => assets.push(asset),
+                }
+            }
+        }
+        for (account, assets) in foreign {
+            for asset in assets {
+                self.push(account, EntryKind::Sent(None), asset, None, checkpoint);
+            }
+        }
+        let mut index = 0;
+        self.pending.retain_mut(|pending| {
+            if !pending.confirmed && !matched[index] {
+                pending.age = pending.age.saturating_add(observed);
+            }
+            let keep = !conflicting[index]
+                && !pending.is_complete()
+                && (!pending.confirmed || matched[index])
+                && pending.age <= PENDING_EXPIRY;
+            index += 1;
+            keep
+        });
+    }
+
+    /// Records the deposit of `utxo` with `asset` and `memo` to `account`.
+    #[inline]
+    fn deposit(
+        &mut self,
+        account: AccountIndex,
+        utxo: Utxo<C>,
+        asset: Asset<C>,
+        memo: Option<Memo<C>>,
+        checkpoint: &C::Checkpoint,
+        matched: &mut [bool],
+    ) {
+        match (0..matched.len()).find(|i| remove_related(&mut self.pending[*i].utxos, &utxo)) {
+            Some(index) => {
+                self.confirm(index, checkpoint);
+                matched[index] = true;
+            }
+            _ => self.push(account, EntryKind::Received, asset, memo, checkpoint),
+        }
+    }
+
+    /// Records the withdraw of `nullifier` by a pending transaction, returning `false` if no
+    /// pending transaction spends `nullifier`.
+    ///
+    /// If more than one pending transaction spends `nullifier`, the one which was already
+    /// observed on the ledger is preferred, and the other ones are marked as `conflicting`.
+    #[inline]
+    fn withdraw(
+        &mut self,
+        nullifier: &Nullifier<C>,
+        checkpoint: &C::Checkpoint,
+        matched: &mut [bool],
+        conflicting: &mut [bool],
+    ) -> bool {
+        let mut spenders = (0..matched.len()).filter(|i| self.pending[*i].spends(nullifier));
+        let first = match spenders.next() {
+            Some(first) => first,
+            _ => return false,
+        };
+        let index = Some(first)
+            .into_iter()
+            .chain(spenders)
+            .find(|i| matched[*i] || self.pending[*i].confirmed)
+            .unwrap_or(first);
+        remove_related(&mut self.pending[index].nullifiers, nullifier);
+        self.confirm(index, checkpoint);
+        matched[index] = true;
+        for (i, pending) in self.pending.iter().enumerate().take(conflicting.len()) {
+            if i != index && !pending.confirmed && pending.spends(nullifier) {
+                conflicting[i] = true;
+            }
+        }
+        true
+    }
+
+    /// Pushes a new entry to the history of `account`.
+    #[inline]
+    fn push(
+        &mut self,
+        account: AccountIndex,
+        kind: EntryKind<C>,
+        asset: Asset<C>,
+        memo: Option<Memo<C>>,
+        checkpoint: &C::Checkpoint,
+    ) {
+        self.entries.entry(account).or_default().push(Entry {
+            kind,
+            asset,
+            memo,
+            checkpoint: checkpoint.clone(),
+        })
+    }
+}
@@ -26,21 +26,30 @@
 //        internally.
 
 use crate::{
-    asset::AssetMap,
-    key::{self, Account, AccountCollection, DeriveAddresses},
+    asset::{AssetMap, CoinSelection},
+    key::{self, Account, AccountCollection, AccountIndex, DeriveAddresses},
     transfer::{
         self,
-        canonical::{MultiProvingContext, Transaction, TransactionData},
-        Address, Asset, AuthorizationContext, IdentifiedAsset, Identifier, IdentityProof, Note,
-        Nullifier, Parameters, ProofSystemError, SpendingKey, TransferPost, Utxo,
-        UtxoAccumulatorItem, UtxoAccumulatorModel, UtxoMembershipProof,
+        canonical::{MultiProvingContext, Transaction, TransactionData, TransferShape},
+        utxo::protocol::Visibility,
+        Address, Asset, AuthorizationContext, Compiler, DecryptionKey, IdentifiedAsset, Identifier,
+        IdentityProof, Memo, Note, Nullifier, Parameters, ProofSystemError, SpendingKey,
+        TransferPost, Utxo, UtxoAccumulatorItem, UtxoAccumulatorModel, UtxoMembershipProof,
     },
-    wallet::ledger::{self, Data},
+    wallet::{
+        ledger::{self, Data},
+        signer::history::{EntryKind, TransactionHistory},
+    },
+};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
 };
-use alloc::{boxed::Box, vec::Vec};
-use core::{convert::Infallible, fmt::Debug, hash::Hash};
+use core::{convert::Infallible, fmt::Debug, hash::Hash, time::Duration};
 use manta_crypto::{
     accumulator::{Accumulator, ExactSizeAccumulator, ItemHashFunction, OptimizedAccumulator},
+    constraint::measure::{Measure, Size},
     rand::{CryptoRng, FromEntropy, RngCore},
 };
 use manta_util::{future::LocalBoxFutureResult, persistence::Rollback};
@@ -49,6 +58,8 @@ use manta_util::{future::LocalBoxFutureResult, persistence::Rollback};
 use manta_util::serde::{Deserialize, Serialize};
 
 pub mod functions;
+pub mod history;
+pub mod storage;
 
 /// Signer Connection
 pub trait Connection<C>
@@ -82,9 +93,15 @@ where
         request: SignRequest<Self::AssetMetadata, C>,
     ) -> LocalBoxFutureResult<SignResult<C>, Self::Error>;
 
-    /// Returns the [`Address`] corresponding to `self`.
+    /// Returns the [`Address`] of the default account of `self`.
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error>;
 
+    /// Returns the [`Address`] of `account` in `self`.
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error>;
+
     /// Returns the [`TransactionData`] of the [`TransferPost`]s in `request` owned by `self`.
     fn transaction_data(
         &mut self,
@@ -106,6 +123,41 @@ where
     ) -> LocalBoxFutureResult<SignWithTransactionDataResult<C>, Self::Error>
     where
         TransferPost<C>: Clone;
+
+    /// Returns the page of the transaction history of the account selected in `request`.
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse<C, Self::Checkpoint>, Self::Error>;
+
+    /// Signs self-transfers which merge the UTXOs of the asset and account selected in `request`,
+    /// using at most `request.max_posts` posts.
+    ///
+    /// This is meant to be run while the wallet is idle, so that later withdrawals need fewer
+    /// posts to join the UTXOs they spend.
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest<C>,
+    ) -> LocalBoxFutureResult<ConsolidateResult<C>, Self::Error>;
+
+    /// Plans the transaction in `request` without building any proofs, returning the assets it
+    /// would spend, the shapes of the posts it would generate and an estimate of the time needed
+    /// to prove them.
+    ///
+    /// Signing the same transaction reproduces the plan, unless the assets are selected with
+    /// [`CoinSelection::Random`], in which case signing selects them again.
+    fn plan(&mut self, request: PlanRequest<C>)
+        -> LocalBoxFutureResult<PlanResult<C>, Self::Error>;
+
+    /// Rewinds the signer to the latest state it retained at or before the checkpoint in
+    /// `request`, returning the restored checkpoint and the full balance of the selected account.
+    ///
+    /// This is used to recover from ledger reorganizations which dropped data that the signer
+    /// already synchronized.
+    fn rewind(
+        &mut self,
+        request: RewindRequest<Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<RewindResult<C, Self::Checkpoint>, Self::Error>;
 }
 
 /// Signer Synchronization Data
@@ -149,6 +201,23 @@ where
     pub nullifier_data: Vec<Nullifier<C>>,
 }
 
+impl<C> SyncData<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Returns the number of items in `self`, counting both UTXOs and nullifiers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.utxo_note_data.len() + self.nullifier_data.len()
+    }
+
+    /// Returns `true` if `self` has no UTXOs and no nullifiers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.utxo_note_data.is_empty() && self.nullifier_data.is_empty()
+    }
+}
+
 impl<C> Data<C::Checkpoint> for SyncData<C>
 where
     C: Configuration + ?Sized,
@@ -193,6 +262,13 @@ where
     C: transfer::Configuration,
     T: ledger::Checkpoint,
 {
+    /// Account
+    ///
+    /// The signer synchronizes all of its accounts at once but only reports the balance update
+    /// for this account in the [`SyncResponse`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
     /// Origin Checkpoint
     ///
     /// This checkpoint was the one that was used to retrieve the [`data`](Self::data) from the
@@ -236,8 +312,8 @@ where
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "T: Deserialize<'de>, BalanceUpdate<C>: Deserialize<'de>",
-            serialize = "T: Serialize, BalanceUpdate<C>: Serialize",
+            deserialize = "T: Deserialize<'de>, BalanceUpdate<C>: Deserialize<'de>, Asset<C>: Deserialize<'de>, Memo<C>: Deserialize<'de>",
+            serialize = "T: Serialize, BalanceUpdate<C>: Serialize, Asset<C>: Serialize, Memo<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -245,13 +321,14 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "T: Clone, BalanceUpdate<C>: Clone"),
-    Copy(bound = "T: Copy, BalanceUpdate<C>: Copy"),
-    Debug(bound = "T: Debug, BalanceUpdate<C>: Debug"),
+    Clone(bound = "T: Clone, BalanceUpdate<C>: Clone, Asset<C>: Clone, Memo<C>: Clone"),
+    Debug(bound = "T: Debug, BalanceUpdate<C>: Debug, Asset<C>: Debug, Memo<C>: Debug"),
     Default(bound = "T: Default, BalanceUpdate<C>: Default"),
-    Eq(bound = "T: Eq, BalanceUpdate<C>: Eq"),
-    Hash(bound = "T: Hash, BalanceUpdate<C>: Hash"),
-    PartialEq(bound = "T: PartialEq, BalanceUpdate<C>: PartialEq")
+    Eq(bound = "T: Eq, BalanceUpdate<C>: Eq, Asset<C>: Eq, Memo<C>: Eq"),
+    Hash(bound = "T: Hash, BalanceUpdate<C>: Hash, Asset<C>: Hash, Memo<C>: Hash"),
+    PartialEq(
+        bound = "T: PartialEq, BalanceUpdate<C>: PartialEq, Asset<C>: PartialEq, Memo<C>: PartialEq"
+    )
 )]
 pub struct SyncResponse<C, T>
 where
@@ -263,6 +340,22 @@ where
 
     /// Balance Update
     pub balance_update: BalanceUpdate<C>,
+
+    /// Transparent Assets
+    ///
+    /// This is the full balance held in transparent UTXOs by the synchronized account. It is
+    /// already accounted for in the [`balance_update`](Self::balance_update).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transparent_assets: Vec<Asset<C>>,
+
+    /// Memos
+    ///
+    /// These are the memos attached to the UTXOs received by the synchronized account in this
+    /// synchronization step, along with the asset of each UTXO. The memos received by every
+    /// account, including the other accounts synchronized in the same step, are also kept in the
+    /// [`Received`](history::EntryKind::Received) entries of their transaction history.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memos: Vec<(Asset<C>, Memo<C>)>,
 }
 
 /// Transaction Data Request
@@ -422,6 +515,50 @@ where
 /// Synchronization Result
 pub type SyncResult<C, T> = Result<SyncResponse<C, T>, SyncError<T>>;
 
+/// Signer Rewind Request
+///
+/// This `struct` is used by the [`rewind`](Connection::rewind) method on [`Connection`].
+/// See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RewindRequest<T> {
+    /// Account
+    ///
+    /// This is the account whose balance is returned after rewinding.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
+    /// Fork Point
+    ///
+    /// The signer rewinds to a state at or before this checkpoint.
+    pub checkpoint: T,
+}
+
+/// Signer Rewind Error
+///
+/// This `enum` is the error state for the [`rewind`](Connection::rewind) method on
+/// [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RewindError {
+    /// Missing Checkpoint
+    ///
+    /// The signer retained no state at or before the requested checkpoint, so it has to be
+    /// synchronized again from the beginning of the ledger.
+    MissingCheckpoint,
+}
+
+/// Rewind Result
+pub type RewindResult<C, T> = Result<SyncResponse<C, T>, RewindError>;
+
 /// Signer Signing Request
 ///
 /// This `struct` is used by the [`sign`](Connection::sign) method on [`Connection`].
@@ -431,8 +568,8 @@ pub type SyncResult<C, T> = Result<SyncResponse<C, T>, SyncError<T>>;
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "Transaction<C>: Deserialize<'de>, A: Deserialize<'de>",
-            serialize = "Transaction<C>: Serialize, A: Serialize"
+            deserialize = "Transaction<C>: Deserialize<'de>, A: Deserialize<'de>, C::AssetId: Deserialize<'de>, Memo<C>: Deserialize<'de>",
+            serialize = "Transaction<C>: Serialize, A: Serialize, C::AssetId: Serialize, Memo<C>: Serialize"
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -440,21 +577,84 @@ pub type SyncResult<C, T> = Result<SyncResponse<C, T>, SyncError<T>>;
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "Transaction<C>: Clone, A: Clone"),
-    Debug(bound = "Transaction<C>: Debug, A: Debug"),
-    Eq(bound = "Transaction<C>: Eq, A: Eq"),
-    Hash(bound = "Transaction<C>: Hash, A: Hash"),
-    PartialEq(bound = "Transaction<C>: PartialEq, A: PartialEq")
+    Clone(bound = "Transaction<C>: Clone, A: Clone, Memo<C>: Clone"),
+    Debug(bound = "Transaction<C>: Debug, A: Debug, C::AssetId: Debug, Memo<C>: Debug"),
+    Eq(bound = "Transaction<C>: Eq, A: Eq, C::AssetId: Eq, Memo<C>: Eq"),
+    Hash(bound = "Transaction<C>: Hash, A: Hash, C::AssetId: Hash, Memo<C>: Hash"),
+    PartialEq(
+        bound = "Transaction<C>: PartialEq, A: PartialEq, C::AssetId: PartialEq, Memo<C>: PartialEq"
+    )
 )]
 pub struct SignRequest<A, C>
 where
     C: transfer::Configuration,
 {
+    /// Account
+    ///
+    /// This is the account which owns the assets spent in the [`transaction`](Self::transaction).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
     /// Transaction Data
     pub transaction: Transaction<C>,
 
     /// Asset Metadata
     pub metadata: Option<A>,
+
+    /// Coin Selection Strategy
+    ///
+    /// This is the strategy used to select the assets spent in the
+    /// [`transaction`](Self::transaction). If it is `None`, the signer uses its
+    /// [default strategy](Configuration::COIN_SELECTION).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub coin_selection: Option<CoinSelection>,
+
+    /// Non-Fungible Assets
+    ///
+    /// These are the ids of the assets which are treated as non-fungible tokens. The UTXOs of
+    /// these assets are selected with the [`NonFungible`](crate::asset::NonFungible) strategy, so
+    /// they are never selected together with other UTXOs and never split into change. Every
+    /// other asset is fungible, whatever its value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub non_fungible: BTreeSet<C::AssetId>,
+
+    /// Visibility
+    ///
+    /// This is the visibility of the UTXOs paid out by the [`transaction`](Self::transaction).
+    /// Change UTXOs returned to the [`account`](Self::account) are always opaque.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visibility: Visibility,
+
+    /// Memo
+    ///
+    /// If it is given, this memo is encrypted for the recipient of each UTXO paid out by the
+    /// [`transaction`](Self::transaction). Change UTXOs never carry a memo. The memo of a
+    /// [`ToPrivate`](Transaction::ToPrivate) transaction is not signed, so it can be stripped or
+    /// replaced before the post reaches the ledger. See
+    /// [`FullIncomingNote::memo`](crate::transfer::utxo::protocol::FullIncomingNote::memo) for
+    /// more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memo: Option<Memo<C>>,
+}
+
+impl<A, C> SignRequest<A, C>
+where
+    C: transfer::Configuration,
+{
+    /// Builds a new [`SignRequest`] for `transaction` spending from the default account, without
+    /// asset metadata and with the default value of every other option.
+    #[inline]
+    pub fn new(transaction: Transaction<C>) -> Self {
+        Self {
+            account: Default::default(),
+            transaction,
+            metadata: None,
+            coin_selection: None,
+            non_fungible: BTreeSet::new(),
+            visibility: Default::default(),
+            memo: None,
+        }
+    }
 }
 
 /// Signer Signing Response
@@ -489,6 +689,206 @@ where
     pub posts: Vec<TransferPost<C>>,
 }
 
+/// Signer Consolidation Request
+///
+/// This `struct` is used by the [`consolidate`](Connection::consolidate) method on
+/// [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C::AssetId: Deserialize<'de>",
+            serialize = "C::AssetId: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C::AssetId: Clone"),
+    Copy(bound = "C::AssetId: Copy"),
+    Debug(bound = "C::AssetId: Debug"),
+    Eq(bound = "C::AssetId: Eq"),
+    Hash(bound = "C::AssetId: Hash"),
+    PartialEq(bound = "C::AssetId: PartialEq")
+)]
+pub struct ConsolidateRequest<C>
+where
+    C: transfer::Configuration,
+{
+    /// Account
+    ///
+    /// This is the account which owns the UTXOs to consolidate.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
+    /// Asset Id
+    pub asset_id: C::AssetId,
+
+    /// Maximum Number of Posts
+    pub max_posts: usize,
+}
+
+/// Signer Consolidation Response
+///
+/// This `struct` is created by the [`consolidate`](Connection::consolidate) method on
+/// [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TransferPost<C>: Deserialize<'de>",
+            serialize = "TransferPost<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "TransferPost<C>: Clone"),
+    Debug(bound = "TransferPost<C>: Debug"),
+    Eq(bound = "TransferPost<C>: Eq"),
+    Hash(bound = "TransferPost<C>: Hash"),
+    PartialEq(bound = "TransferPost<C>: PartialEq")
+)]
+pub struct ConsolidateResponse<C>
+where
+    C: transfer::Configuration,
+{
+    /// Transfer Posts
+    pub posts: Vec<TransferPost<C>>,
+
+    /// Projected UTXO Count
+    ///
+    /// This is the number of non-zero UTXOs of the asset which the account will own once all of
+    /// the [`posts`](Self::posts) are on the ledger.
+    pub utxo_count: usize,
+}
+
+/// Signer Plan Request
+///
+/// This `struct` is used by the [`plan`](Connection::plan) method on [`Connection`].
+/// See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Transaction<C>: Deserialize<'de>, C::AssetId: Deserialize<'de>",
+            serialize = "Transaction<C>: Serialize, C::AssetId: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Transaction<C>: Clone"),
+    Debug(bound = "Transaction<C>: Debug, C::AssetId: Debug"),
+    Eq(bound = "Transaction<C>: Eq, C::AssetId: Eq"),
+    Hash(bound = "Transaction<C>: Hash, C::AssetId: Hash"),
+    PartialEq(bound = "Transaction<C>: PartialEq, C::AssetId: PartialEq")
+)]
+pub struct PlanRequest<C>
+where
+    C: transfer::Configuration,
+{
+    /// Account
+    ///
+    /// This is the account which owns the assets spent in the [`transaction`](Self::transaction).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
+    /// Transaction Data
+    pub transaction: Transaction<C>,
+
+    /// Coin Selection Strategy
+    ///
+    /// See [`SignRequest::coin_selection`] for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub coin_selection: Option<CoinSelection>,
+
+    /// Non-Fungible Assets
+    ///
+    /// See [`SignRequest::non_fungible`] for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub non_fungible: BTreeSet<C::AssetId>,
+}
+
+/// Transaction Plan
+///
+/// This `struct` is created by the [`plan`](Connection::plan) method on [`Connection`].
+/// See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>, Identifier<C>: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize, Identifier<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone, Identifier<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug, Identifier<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Asset<C>: Eq, Identifier<C>: Eq"),
+    Hash(bound = "Asset<C>: Hash, Identifier<C>: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq, Identifier<C>: PartialEq")
+)]
+pub struct TransactionPlan<C>
+where
+    C: transfer::Configuration,
+{
+    /// Selected UTXOs
+    ///
+    /// These are the [`Identifier`]s and [`Asset`]s of the UTXOs spent by the transaction.
+    pub selected: Vec<(Identifier<C>, Asset<C>)>,
+
+    /// Post Shapes
+    ///
+    /// This is the [`TransferShape`] of each post in the order the signer would generate them,
+    /// including the posts which join the [`selected`](Self::selected) UTXOs.
+    pub shapes: Vec<TransferShape>,
+
+    /// Change
+    ///
+    /// This is the change returned to the account for each asset id spent by the transaction.
+    pub change: Vec<Asset<C>>,
+
+    /// Circuit Size
+    ///
+    /// This is the sum of the sizes of the circuits of every post in [`shapes`](Self::shapes).
+    pub size: Size,
+
+    /// Estimated Proving Time
+    pub estimated_proving_time: Duration,
+}
+
+impl<C> TransactionPlan<C>
+where
+    C: transfer::Configuration,
+{
+    /// Returns the number of proofs needed to sign the transaction.
+    #[inline]
+    pub fn proof_count(&self) -> usize {
+        self.shapes.len()
+    }
+}
+
+/// Plan Result
+///
+/// This is the return type of [`plan`](Connection::plan).
+pub type PlanResult<C> = Result<TransactionPlan<C>, SignError<C>>;
+
 /// Identity Request
 #[cfg_attr(
     feature = "serde",
@@ -549,6 +949,68 @@ where
     }
 }
 
+/// Transaction History Request
+///
+/// This `struct` is used by the [`transaction_history`](Connection::transaction_history) method
+/// on [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TransactionHistoryRequest {
+    /// Account
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: AccountIndex,
+
+    /// Number of Entries to Skip
+    pub offset: usize,
+
+    /// Maximum Number of Entries to Return
+    pub limit: usize,
+}
+
+/// Transaction History Response
+///
+/// This `struct` is created by the [`transaction_history`](Connection::transaction_history)
+/// method on [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "history::Entry<C, T>: Deserialize<'de>",
+            serialize = "history::Entry<C, T>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "history::Entry<C, T>: Clone"),
+    Debug(bound = "history::Entry<C, T>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "history::Entry<C, T>: Eq"),
+    Hash(bound = "history::Entry<C, T>: Hash"),
+    PartialEq(bound = "history::Entry<C, T>: PartialEq")
+)]
+pub struct TransactionHistoryResponse<C, T>
+where
+    C: transfer::Configuration,
+{
+    /// History Entries
+    ///
+    /// The entries are sorted in the order in which they were observed on the ledger, starting
+    /// from the oldest one, so that the offsets of the entries do not change as the history
+    /// grows.
+    pub entries: Vec<history::Entry<C, T>>,
+
+    /// Total Number of Entries in the History of the Account
+    pub total: usize,
+}
+
 impl<C> SignResponse<C>
 where
     C: transfer::Configuration,
@@ -597,11 +1059,19 @@ where
 
     /// Missing Spending Key
     MissingSpendingKey,
+
+    /// No Spending Authority
+    ///
+    /// This error is returned when a view-only [`Signer`] is asked to spend assets.
+    NoSpendingAuthority,
 }
 
 /// Signing Result
 pub type SignResult<C> = Result<SignResponse<C>, SignError<C>>;
 
+/// Consolidation Result
+pub type ConsolidateResult<C> = Result<ConsolidateResponse<C>, SignError<C>>;
+
 /// Signing with Transaction Data Error
 pub type SignWithTransactionDataResult<C> =
     Result<SignWithTransactionDataResponse<C>, SignError<C>>;
@@ -645,7 +1115,7 @@ where
 }
 
 /// Signer Configuration
-pub trait Configuration: transfer::Configuration {
+pub trait Configuration: transfer::Configuration<AssociatedData = Visibility> {
     /// Checkpoint Type
     type Checkpoint: Checkpoint<
         Self,
@@ -672,11 +1142,53 @@ pub trait Configuration: transfer::Configuration {
 
     /// Random Number Generator Type
     type Rng: CryptoRng + FromEntropy + RngCore;
+
+    /// Default Coin Selection Strategy
+    ///
+    /// This strategy is used to select the assets spent by a transaction whenever its
+    /// [`SignRequest`] does not choose one.
+    const COIN_SELECTION: CoinSelection = CoinSelection::Greedy;
+
+    /// Proving Throughput
+    ///
+    /// This is the estimated number of constraints proven per second, used to estimate proving
+    /// times in [`TransactionPlan`]s.
+    const PROVING_THROUGHPUT: usize = 100_000;
 }
 
 /// Account Table Type
 pub type AccountTable<C> = key::AccountTable<<C as Configuration>::Account>;
 
+/// Account Asset Map Type
+///
+/// Each account in the [`AccountTable`] keeps its own [`AssetMap`](Configuration::AssetMap).
+pub type AccountAssetMap<C> = BTreeMap<AccountIndex, <C as Configuration>::AssetMap>;
+
+/// Authorization Context Map Type
+pub type AuthorizationContextMap<C> = BTreeMap<AccountIndex, AuthorizationContext<C>>;
+
+/// Parallel Trial Decryption Bound
+///
+/// With the `rayon` feature enabled, [`sync`](Signer::sync) trial-decrypts the incoming notes on
+/// several threads, so the data shared between them must be [`Send`] and [`Sync`]. Without the
+/// feature, every type implements this `trait`.
+#[cfg(feature = "rayon")]
+pub trait ParallelSafe: Send + Sync {}
+
+#[cfg(feature = "rayon")]
+impl<T> ParallelSafe for T where T: Send + Sync + ?Sized {}
+
+/// Parallel Trial Decryption Bound
+///
+/// With the `rayon` feature enabled, [`sync`](Signer::sync) trial-decrypts the incoming notes on
+/// several threads, so the data shared between them must be [`Send`] and [`Sync`]. Without the
+/// feature, every type implements this `trait`.
+#[cfg(not(feature = "rayon"))]
+pub trait ParallelSafe {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T> ParallelSafe for T where T: ?Sized {}
+
 /// Signer Parameters
 #[cfg_attr(
     feature = "serde",
@@ -731,16 +1243,18 @@ where
         bound(
             deserialize = r"
                 AccountTable<C>: Deserialize<'de>,
-                AuthorizationContext<C>: Deserialize<'de>,
+                AuthorizationContextMap<C>: Deserialize<'de>,
                 C::UtxoAccumulator: Deserialize<'de>,
-                C::AssetMap: Deserialize<'de>,
+                AccountAssetMap<C>: Deserialize<'de>,
+                TransactionHistory<C>: Deserialize<'de>,
                 C::Checkpoint: Deserialize<'de>
             ",
             serialize = r"
                 AccountTable<C>: Serialize,
-                AuthorizationContext<C>: Serialize,
+                AuthorizationContextMap<C>: Serialize,
                 C::UtxoAccumulator: Serialize,
-                C::AssetMap: Serialize,
+                AccountAssetMap<C>: Serialize,
+                TransactionHistory<C>: Serialize,
                 C::Checkpoint: Serialize
             ",
         ),
@@ -752,41 +1266,46 @@ where
 #[derivative(
     Debug(bound = r"
         AccountTable<C>: Debug,
-        AuthorizationContext<C>: Debug,
+        AuthorizationContextMap<C>: Debug,
         C::UtxoAccumulator: Debug,
-        C::AssetMap: Debug,
+        AccountAssetMap<C>: Debug,
+        TransactionHistory<C>: Debug,
         C::Checkpoint: Debug,
         C::Rng: Debug
     "),
     Default(bound = r"
         AccountTable<C>: Default,
-        AuthorizationContext<C>: Default,
+        AuthorizationContextMap<C>: Default,
         C::UtxoAccumulator: Default,
-        C::AssetMap: Default,
+        AccountAssetMap<C>: Default,
+        TransactionHistory<C>: Default,
         C::Checkpoint: Default,
         C::Rng: Default
     "),
     Eq(bound = r"
         AccountTable<C>: Eq,
-        AuthorizationContext<C>: Eq,
+        AuthorizationContextMap<C>: Eq,
         C::UtxoAccumulator: Eq,
-        C::AssetMap: Eq,
+        AccountAssetMap<C>: Eq,
+        TransactionHistory<C>: Eq,
         C::Checkpoint: Eq,
         C::Rng: Eq
     "),
     Hash(bound = r"
         AccountTable<C>: Hash,
-        AuthorizationContext<C>: Hash,
+        AuthorizationContextMap<C>: Hash,
         C::UtxoAccumulator: Hash,
-        C::AssetMap: Hash,
+        AccountAssetMap<C>: Hash,
+        TransactionHistory<C>: Hash,
         C::Checkpoint: Hash,
         C::Rng: Hash
     "),
     PartialEq(bound = r"
         AccountTable<C>: PartialEq,
-        AuthorizationContext<C>: PartialEq,
+        AuthorizationContextMap<C>: PartialEq,
         C::UtxoAccumulator: PartialEq,
-        C::AssetMap: PartialEq,
+        AccountAssetMap<C>: PartialEq,
+        TransactionHistory<C>: PartialEq,
         C::Checkpoint: PartialEq,
         C::Rng: PartialEq
     ")
@@ -799,19 +1318,22 @@ where
     ///
     /// # Implementation Note
     ///
-    /// For now, we only use the default account, and the rest of the storage data is related to
-    /// this account. Eventually, we want to have a global `utxo_accumulator` for all accounts and
-    /// a local `assets` map for each account.
+    /// All the accounts share a global `utxo_accumulator` and each account has a local `assets`
+    /// map and its own authorization context.
     accounts: Option<AccountTable<C>>,
 
-    /// Authorization Context
-    authorization_context: Option<AuthorizationContext<C>>,
+    /// Authorization Contexts
+    authorization_contexts: AuthorizationContextMap<C>,
 
     /// UTXO Accumulator
     utxo_accumulator: C::UtxoAccumulator,
 
     /// Asset Distribution
-    assets: C::AssetMap,
+    assets: AccountAssetMap<C>,
+
+    /// Transaction History
+    #[cfg_attr(feature = "serde", serde(default))]
+    history: TransactionHistory<C>,
 
     /// Current Checkpoint
     checkpoint: C::Checkpoint,
@@ -823,6 +1345,20 @@ where
     /// from local entropy whenever the [`SignerState`] is deserialized.
     #[cfg_attr(feature = "serde", serde(skip, default = "FromEntropy::from_entropy"))]
     rng: C::Rng,
+
+    /// Retained States
+    ///
+    /// Copies of the state after the latest synchronizations, oldest first, which
+    /// [`rewind`](Signer::rewind) restores after a ledger reorganization. They are kept in memory
+    /// only and are not saved along with the rest of the state.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    retained: Vec<StorageState<C>>,
+
+    /// Retention Limit
+    ///
+    /// Maximum number of states kept in [`retained`](Self::retained).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    retention_limit: usize,
 }
 
 impl<C> SignerState<C>
@@ -831,14 +1367,21 @@ where
 {
     /// Builds a new [`SignerState`] from `utxo_accumulator`, `assets`, and `rng`.
     #[inline]
-    fn build(utxo_accumulator: C::UtxoAccumulator, assets: C::AssetMap, rng: C::Rng) -> Self {
+    fn build(
+        utxo_accumulator: C::UtxoAccumulator,
+        assets: AccountAssetMap<C>,
+        rng: C::Rng,
+    ) -> Self {
         Self {
             accounts: None,
-            authorization_context: None,
+            authorization_contexts: Default::default(),
             checkpoint: C::Checkpoint::from_utxo_accumulator(&utxo_accumulator),
             utxo_accumulator,
             assets,
+            history: Default::default(),
             rng,
+            retained: Default::default(),
+            retention_limit: 0,
         }
     }
 
@@ -864,16 +1407,21 @@ where
         self.accounts = None
     }
 
-    /// Loads `authorization_context` to `self`.
+    /// Loads `authorization_context` for `account` to `self`.
     #[inline]
-    pub fn load_authorization_context(&mut self, authorization_context: AuthorizationContext<C>) {
-        self.authorization_context = Some(authorization_context)
+    pub fn load_authorization_context(
+        &mut self,
+        account: AccountIndex,
+        authorization_context: AuthorizationContext<C>,
+    ) {
+        self.authorization_contexts
+            .insert(account, authorization_context);
     }
 
-    /// Drops `self.authorization_context`.
+    /// Drops `self.authorization_contexts`.
     #[inline]
     pub fn drop_authorization_context(&mut self) {
-        self.authorization_context = None
+        self.authorization_contexts.clear()
     }
 
     /// Returns the [`AccountTable`].
@@ -882,10 +1430,45 @@ where
         &self.accounts
     }
 
-    /// Returns the [`AuthorizationContext`].
+    /// Returns `true` if `self` has authorization contexts to synchronize with but no accounts
+    /// to spend from.
+    #[inline]
+    pub fn is_view_only(&self) -> bool {
+        self.accounts.is_none() && !self.authorization_contexts.is_empty()
+    }
+
+    /// Returns the [`SignError`] for signing without an [`AccountTable`].
     #[inline]
-    pub fn authorization_context(&self) -> &Option<AuthorizationContext<C>> {
-        &self.authorization_context
+    fn missing_accounts_error(&self) -> SignError<C> {
+        if self.is_view_only() {
+            SignError::NoSpendingAuthority
+        } else {
+            SignError::MissingSpendingKey
+        }
+    }
+
+    /// Returns the [`AuthorizationContext`] for `account`.
+    #[inline]
+    pub fn authorization_context(&self, account: AccountIndex) -> Option<&AuthorizationContext<C>> {
+        self.authorization_contexts.get(&account)
+    }
+
+    /// Returns the [`AuthorizationContextMap`].
+    #[inline]
+    pub fn authorization_contexts(&self) -> &AuthorizationContextMap<C> {
+        &self.authorization_contexts
+    }
+
+    /// Returns the [`AccountAssetMap`].
+    #[inline]
+    pub fn assets(&self) -> &AccountAssetMap<C> {
+        &self.assets
+    }
+
+    /// Returns the [`TransactionHistory`].
+    #[inline]
+    pub fn history(&self) -> &TransactionHistory<C> {
+        &self.history
     }
 
     /// Returns the default account for `self`.
@@ -893,15 +1476,22 @@ where
     pub fn default_account(&self) -> Option<Account<C::Account>> {
         Some(self.accounts.as_ref()?.get_default())
     }
+
+    /// Returns the account for `account` in `self`.
+    #[inline]
+    pub fn account(&self, account: AccountIndex) -> Option<Account<C::Account>> {
+        self.accounts.as_ref()?.get(account)
+    }
 }
 
 impl<C> Clone for SignerState<C>
 where
     C: Configuration,
     AccountTable<C>: Clone,
-    AuthorizationContext<C>: Clone,
+    AuthorizationContextMap<C>: Clone,
     C::UtxoAccumulator: Clone,
-    C::AssetMap: Clone,
+    AccountAssetMap<C>: Clone,
+    TransactionHistory<C>: Clone,
 {
     #[inline]
     fn clone(&self) -> Self {
@@ -913,10 +1503,10 @@ where
         if self.accounts.is_some() {
             signer_state.load_accounts(self.accounts.as_ref().unwrap().clone());
         }
-        if self.authorization_context.is_some() {
-            signer_state
-                .load_authorization_context(self.authorization_context.as_ref().unwrap().clone());
-        }
+        signer_state.authorization_contexts = self.authorization_contexts.clone();
+        signer_state.history = self.history.clone();
+        signer_state.retained = self.retained.clone();
+        signer_state.retention_limit = self.retention_limit;
         signer_state
     }
 }
@@ -969,7 +1559,7 @@ where
         parameters: Parameters<C>,
         proving_context: MultiProvingContext<C>,
         utxo_accumulator: C::UtxoAccumulator,
-        assets: C::AssetMap,
+        assets: AccountAssetMap<C>,
         rng: C::Rng,
     ) -> Self {
         Self::from_parts(
@@ -998,6 +1588,33 @@ where
         )
     }
 
+    /// Builds a new view-only [`Signer`] for the default account from `authorization_context`.
+    ///
+    /// # Implementation Note
+    ///
+    /// The [`AuthorizationContext`] is the smallest amount of key material from which the signer
+    /// can recognize both incoming and spent assets, since nullifiers are derived from its proof
+    /// authorization key. It cannot authorize any spending, so [`sign`](Self::sign) and
+    /// [`identity_proof`](Self::identity_proof) return [`SignError::NoSpendingAuthority`].
+    #[inline]
+    pub fn new_view_only(
+        parameters: Parameters<C>,
+        proving_context: MultiProvingContext<C>,
+        utxo_accumulator: C::UtxoAccumulator,
+        authorization_context: AuthorizationContext<C>,
+        rng: C::Rng,
+    ) -> Self {
+        let mut signer = Self::new(parameters, proving_context, utxo_accumulator, rng);
+        signer.load_authorization_context(Default::default(), authorization_context);
+        signer
+    }
+
+    /// Returns `true` if `self` can synchronize with the ledger but cannot spend.
+    #[inline]
+    pub fn is_view_only(&self) -> bool {
+        self.state.is_view_only()
+    }
+
     /// Returns a shared reference to the signer parameters.
     #[inline]
     pub fn parameters(&self) -> &SignerParameters<C> {
@@ -1022,27 +1639,60 @@ where
         self.state.drop_accounts()
     }
 
-    /// Loads `authorization_context` to `self`.
+    /// Loads `authorization_context` for `account` to `self`.
     #[inline]
-    pub fn load_authorization_context(&mut self, authorization_context: AuthorizationContext<C>) {
-        self.state.load_authorization_context(authorization_context)
+    pub fn load_authorization_context(
+        &mut self,
+        account: AccountIndex,
+        authorization_context: AuthorizationContext<C>,
+    ) {
+        self.state
+            .load_authorization_context(account, authorization_context)
     }
 
-    /// Updates `self.state.authorization_context` from `self.state.accounts`, if possible.
+    /// Updates `self.state.authorization_contexts` from `self.state.accounts`, if possible.
     #[inline]
     pub fn update_authorization_context(&mut self) -> bool {
         match self.state.accounts() {
             Some(accounts) => {
-                self.load_authorization_context(functions::default_authorization_context::<C>(
-                    accounts,
-                    &self.parameters.parameters,
-                ));
+                self.state.authorization_contexts = accounts
+                    .accounts()
+                    .map(|account| {
+                        (
+                            account.index(),
+                            functions::authorization_context::<C>(
+                                &account,
+                                &self.parameters.parameters,
+                            ),
+                        )
+                    })
+                    .collect();
                 true
             }
             None => false,
         }
     }
 
+    /// Creates a new account in `self.state.accounts`, loading its authorization context and
+    /// returning its [`AccountIndex`], if the accounts are loaded.
+    ///
+    /// # Implementation Note
+    ///
+    /// Since the new account has no balance yet, the [`Signer`] does not need to be re-synchronized
+    /// with the ledger from scratch after this call. However, any assets sent to the new account
+    /// before its creation will only be discovered after a full resynchronization.
+    #[inline]
+    pub fn create_account(&mut self) -> Option<AccountIndex> {
+        let accounts = self.state.accounts.as_mut()?;
+        let index = accounts.create_account();
+        let account = accounts.get(index)?;
+        self.load_authorization_context(
+            index,
+            functions::authorization_context::<C>(&account, &self.parameters.parameters),
+        );
+        Some(index)
+    }
+
     /// Drops `self.state.authorization_context`
     #[inline]
     pub fn drop_authorization_context(&mut self) {
@@ -1050,23 +1700,132 @@ where
     }
 
     /// Updates the internal ledger state, returning the new asset distribution.
+    ///
+    /// After every successful synchronization, a copy of the new state is retained for
+    /// [`rewind`](Self::rewind), up to the number set with
+    /// [`set_retained_checkpoints`](Self::set_retained_checkpoints).
     #[inline]
     pub fn sync(
         &mut self,
         request: SyncRequest<C, C::Checkpoint>,
-    ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
-        functions::sync(
+    ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>>
+    where
+        Asset<C>: ParallelSafe,
+        DecryptionKey<C>: ParallelSafe,
+        Identifier<C>: ParallelSafe,
+        Memo<C>: Clone + ParallelSafe,
+        Note<C>: Clone + ParallelSafe,
+        Parameters<C>: ParallelSafe,
+        Utxo<C>: ParallelSafe,
+        C::UtxoAccumulator: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
+    {
+        if self.state.authorization_contexts.is_empty() {
+            return Err(SyncError::MissingProofAuthorizationKey);
+        }
+        let response = functions::sync(
             &self.parameters,
-            self.state
-                .authorization_context
-                .as_mut()
-                .ok_or(SyncError::MissingProofAuthorizationKey)?,
+            &mut self.state.authorization_contexts,
             &mut self.state.assets,
+            &mut self.state.history,
             &mut self.state.checkpoint,
             &mut self.state.utxo_accumulator,
             request,
             &mut self.state.rng,
-        )
+        )?;
+        self.retain_state();
+        Ok(response)
+    }
+
+    /// Returns the maximum number of synchronized states retained for [`rewind`](Self::rewind).
+    #[inline]
+    pub fn retained_checkpoints(&self) -> usize {
+        self.state.retention_limit
+    }
+
+    /// Sets the maximum number of synchronized states retained for [`rewind`](Self::rewind) to
+    /// `count`, dropping the oldest retained states beyond it.
+    ///
+    /// Every retained state holds a copy of the UTXO accumulator, so this trades memory for the
+    /// depth of the ledger reorganizations `self` can recover from without synchronizing again
+    /// from the beginning. No states are retained by default.
+    #[inline]
+    pub fn set_retained_checkpoints(&mut self, count: usize) {
+        self.state.retention_limit = count;
+        let excess = self.state.retained.len().saturating_sub(count);
+        self.state.retained.drain(..excess);
+    }
+
+    /// Retains a copy of the current state, replacing the latest retained state if it has the
+    /// same checkpoint.
+    #[inline]
+    fn retain_state(&mut self)
+    where
+        C::UtxoAccumulator: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
+    {
+        if self.state.retention_limit == 0 {
+            return;
+        }
+        if matches!(
+            self.state.retained.last(),
+            Some(state) if state.checkpoint == self.state.checkpoint
+        ) {
+            self.state.retained.pop();
+        }
+        let state = StorageState::from_signer(self);
+        self.state.retained.push(state);
+        let excess = self
+            .state
+            .retained
+            .len()
+            .saturating_sub(self.state.retention_limit);
+        self.state.retained.drain(..excess);
+    }
+
+    /// Rewinds `self` to the latest retained state at or before `request.checkpoint`, dropping
+    /// the retained states after it, and returns the restored checkpoint along with the full
+    /// balance of `request.account`. If `self` is not ahead of `request.checkpoint`, its state is
+    /// left as it is.
+    #[inline]
+    pub fn rewind(
+        &mut self,
+        request: RewindRequest<C::Checkpoint>,
+    ) -> RewindResult<C, C::Checkpoint>
+    where
+        C::UtxoAccumulator: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
+    {
+        if request.checkpoint < self.state.checkpoint {
+            let index = self
+                .state
+                .retained
+                .iter()
+                .rposition(|state| state.checkpoint <= request.checkpoint)
+                .ok_or(RewindError::MissingCheckpoint)?;
+            self.state.retained.truncate(index + 1);
+            let state = self.state.retained[index].clone();
+            state.update_signer(self);
+        }
+        Ok(SyncResponse {
+            checkpoint: self.state.checkpoint.clone(),
+            balance_update: BalanceUpdate::Full {
+                assets: self
+                    .state
+                    .assets
+                    .get(&request.account)
+                    .map(|assets| assets.assets().into())
+                    .unwrap_or_default(),
+            },
+            transparent_assets: functions::transparent_assets::<C>(
+                &self.state.assets,
+                request.account,
+            ),
+            memos: Vec::new(),
+        })
     }
 
     /// Generates an [`IdentityProof`] for `identified_asset` by
@@ -1075,30 +1834,78 @@ where
     pub fn identity_proof(
         &mut self,
         identified_asset: IdentifiedAsset<C>,
-    ) -> Option<IdentityProof<C>> {
+    ) -> Result<IdentityProof<C>, SignError<C>> {
         functions::identity_proof(
             &self.parameters,
-            self.state.accounts.as_ref()?,
+            self.state
+                .accounts
+                .as_ref()
+                .ok_or_else(|| self.state.missing_accounts_error())?,
             self.state.utxo_accumulator.model(),
             identified_asset,
             &mut self.state.rng,
         )
     }
 
-    /// Signs the `transaction`, generating transfer posts.
+    /// Records the `transfers` signed by `account` with `posts` as pending in the transaction
+    /// history.
+    #[inline]
+    fn insert_pending_transaction(
+        &mut self,
+        account: AccountIndex,
+        transfers: Vec<(EntryKind<C>, Asset<C>)>,
+        posts: &[TransferPost<C>],
+    ) where
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+        Utxo<C>: Clone,
+    {
+        let pending = functions::pending_transaction(
+            &self.parameters.parameters,
+            self.state.authorization_contexts.get_mut(&account),
+            account,
+            transfers,
+            posts,
+        );
+        self.state.history.insert_pending(pending);
+    }
+
+    /// Signs the transaction in `request`, generating transfer posts. See [`SignRequest`] for the
+    /// options which control how the spent assets are selected and how the UTXOs paid out by the
+    /// transaction are minted. The asset metadata of the `request` is ignored.
+    ///
+    /// The transaction is recorded as pending in the [`TransactionHistory`] of the account of the
+    /// `request` until its posts are observed on the ledger during [`sync`](Self::sync).
     #[inline]
-    pub fn sign(&mut self, transaction: Transaction<C>) -> Result<SignResponse<C>, SignError<C>> {
-        functions::sign(
+    pub fn sign(
+        &mut self,
+        request: SignRequest<C::AssetMetadata, C>,
+    ) -> Result<SignResponse<C>, SignError<C>>
+    where
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+        Utxo<C>: Clone,
+    {
+        let account = request.account;
+        let transfers = EntryKind::from_transaction(&request.transaction);
+        let response = functions::sign(
             &self.parameters,
             self.state
                 .accounts
                 .as_ref()
-                .ok_or(SignError::MissingSpendingKey)?,
+                .ok_or_else(|| self.state.missing_accounts_error())?,
+            account,
             &self.state.assets,
             &mut self.state.utxo_accumulator,
-            transaction,
+            request.transaction,
+            request.coin_selection.unwrap_or(C::COIN_SELECTION),
+            &request.non_fungible,
+            request.visibility,
+            request.memo,
             &mut self.state.rng,
-        )
+        )?;
+        self.insert_pending_transaction(account, transfers, &response.posts);
+        Ok(response)
     }
 
     /// Returns a vector with the [`IdentityProof`] corresponding to each [`IdentifiedAsset`] in `identified_assets`.
@@ -1110,35 +1917,68 @@ where
         IdentityResponse(
             identified_assets
                 .into_iter()
-                .map(|identified_asset| self.identity_proof(identified_asset))
+                .map(|identified_asset| self.identity_proof(identified_asset).ok())
                 .collect(),
         )
     }
 
-    /// Returns the [`Address`] corresponding to `self`.
+    /// Returns the [`Address`] corresponding to `account` in `self`.
     #[inline]
-    pub fn address(&mut self) -> Option<Address<C>> {
-        Some(functions::address(
-            &self.parameters,
-            self.state.accounts.as_ref()?,
-        ))
+    pub fn address(&mut self, account: AccountIndex) -> Option<Address<C>> {
+        match self.state.accounts.as_ref() {
+            Some(accounts) => functions::address(&self.parameters, accounts, account),
+            _ => Some(functions::authorization_context_address::<C>(
+                &self.parameters.parameters,
+                self.state.authorization_contexts.get_mut(&account)?,
+            )),
+        }
     }
 
     /// Returns the associated [`TransactionData`] of `post`, namely the [`Asset`] and the
     /// [`Identifier`]. Returns `None` if `post` has an invalid shape, or if `self` doesn't own the
     /// underlying assets in `post`.
     #[inline]
-    pub fn transaction_data(&self, post: TransferPost<C>) -> Option<TransactionData<C>> {
-        functions::transaction_data(&self.parameters, self.state.accounts.as_ref()?, post)
+    pub fn transaction_data(&mut self, post: TransferPost<C>) -> Option<TransactionData<C>>
+    where
+        Note<C>: Clone,
+    {
+        match self.state.accounts.as_ref() {
+            Some(accounts) => functions::transaction_data(&self.parameters, accounts, post),
+            _ => functions::authorization_context_transaction_data(
+                &self.parameters,
+                &mut self.state.authorization_contexts,
+                post,
+            ),
+        }
+    }
+
+    /// Returns the memos attached to the UTXOs of `post` owned by `self`, along with the asset of
+    /// each UTXO. This complements the [`TransactionData`] of `post`, which does not carry memos.
+    #[inline]
+    pub fn transaction_memos(&mut self, post: TransferPost<C>) -> Vec<(Asset<C>, Memo<C>)>
+    where
+        Note<C>: Clone,
+    {
+        match self.state.accounts.as_ref() {
+            Some(accounts) => functions::transaction_memos(&self.parameters, accounts, post),
+            _ => functions::authorization_context_transaction_memos(
+                &self.parameters,
+                &mut self.state.authorization_contexts,
+                post,
+            ),
+        }
     }
 
     /// Returns a vector with the [`TransactionData`] of each well-formed [`TransferPost`] owned by
     /// `self`.
     #[inline]
     pub fn batched_transaction_data(
-        &self,
+        &mut self,
         posts: Vec<TransferPost<C>>,
-    ) -> TransactionDataResponse<C> {
+    ) -> TransactionDataResponse<C>
+    where
+        Note<C>: Clone,
+    {
         TransactionDataResponse(
             posts
                 .into_iter()
@@ -1147,32 +1987,148 @@ where
         )
     }
 
-    /// Signs the `transaction`, generating transfer posts and returning their associated [`TransactionData`].
+    /// Signs the transaction in `request`, generating transfer posts and returning their
+    /// associated [`TransactionData`]. See [`sign`](Self::sign) for more.
     #[inline]
     pub fn sign_with_transaction_data(
         &mut self,
-        transaction: Transaction<C>,
+        request: SignRequest<C::AssetMetadata, C>,
     ) -> Result<SignWithTransactionDataResponse<C>, SignError<C>>
     where
         TransferPost<C>: Clone,
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+        Utxo<C>: Clone,
     {
-        functions::sign_with_transaction_data(
+        let account = request.account;
+        let transfers = EntryKind::from_transaction(&request.transaction);
+        let response = functions::sign_with_transaction_data(
             &self.parameters,
             self.state
                 .accounts
                 .as_ref()
-                .ok_or(SignError::MissingSpendingKey)?,
+                .ok_or_else(|| self.state.missing_accounts_error())?,
+            account,
             &self.state.assets,
             &mut self.state.utxo_accumulator,
+            request.transaction,
+            request.coin_selection.unwrap_or(C::COIN_SELECTION),
+            &request.non_fungible,
+            request.visibility,
+            request.memo,
+            &mut self.state.rng,
+        )?;
+        let posts = response
+            .0
+            .iter()
+            .map(|(post, _)| post.clone())
+            .collect::<Vec<_>>();
+        self.insert_pending_transaction(account, transfers, &posts);
+        Ok(response)
+    }
+
+    /// Signs self-transfers which merge the UTXOs of `asset_id` owned by `account`, using at most
+    /// `max_posts` posts. The UTXOs are merged pairwise, smallest first, and each post reduces
+    /// the number of non-zero UTXOs by one.
+    ///
+    /// The consolidation is recorded as pending in the [`TransactionHistory`] so that it does not
+    /// show up as a set of unrelated deposits and withdraws once it is observed on the ledger.
+    #[inline]
+    pub fn consolidate(
+        &mut self,
+        account: AccountIndex,
+        asset_id: C::AssetId,
+        max_posts: usize,
+    ) -> ConsolidateResult<C>
+    where
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+        Utxo<C>: Clone,
+    {
+        let response = functions::consolidate(
+            &self.parameters,
+            self.state
+                .accounts
+                .as_ref()
+                .ok_or_else(|| self.state.missing_accounts_error())?,
+            account,
+            &self.state.assets,
+            &mut self.state.utxo_accumulator,
+            asset_id,
+            max_posts,
+            &mut self.state.rng,
+        )?;
+        self.insert_pending_transaction(account, Vec::new(), &response.posts);
+        Ok(response)
+    }
+
+    /// Plans the `transaction` spending from `account` without building any proofs. See
+    /// [`SignRequest`] for the use of `coin_selection` and `non_fungible`.
+    ///
+    /// Signing the same `transaction` from the same assets reproduces the plan, unless the assets
+    /// are selected with [`CoinSelection::Random`], in which case signing selects them again.
+    ///
+    /// Planning only reads the assets of `account`, so it also works on view-only signers.
+    #[inline]
+    pub fn plan(
+        &mut self,
+        account: AccountIndex,
+        transaction: Transaction<C>,
+        coin_selection: Option<CoinSelection>,
+        non_fungible: &BTreeSet<C::AssetId>,
+    ) -> PlanResult<C>
+    where
+        Compiler<C>: Measure,
+    {
+        functions::plan(
+            &self.parameters,
+            self.state.utxo_accumulator.model(),
+            account,
+            &self.state.assets,
             transaction,
+            coin_selection.unwrap_or(C::COIN_SELECTION),
+            non_fungible,
             &mut self.state.rng,
         )
     }
+
+    /// Returns the page of the transaction history of the account selected in `request`.
+    #[inline]
+    pub fn transaction_history(
+        &self,
+        request: TransactionHistoryRequest,
+    ) -> TransactionHistoryResponse<C, C::Checkpoint>
+    where
+        Memo<C>: Clone,
+    {
+        let entries = self.state.history.entries(request.account);
+        TransactionHistoryResponse {
+            entries: entries
+                .iter()
+                .skip(request.offset)
+                .take(request.limit)
+                .cloned()
+                .collect(),
+            total: entries.len(),
+        }
+    }
 }
 
 impl<C> Connection<C> for Signer<C>
 where
     C: Configuration,
+    Asset<C>: ParallelSafe,
+    Compiler<C>: Measure,
+    DecryptionKey<C>: ParallelSafe,
+    Identifier<C>: ParallelSafe,
+    Memo<C>: Clone + ParallelSafe,
+    Note<C>: Clone + ParallelSafe,
+    Nullifier<C>: Clone,
+    Parameters<C>: ParallelSafe,
+    Utxo<C>: Clone + ParallelSafe,
+    C::UtxoAccumulator: Clone,
+    AccountAssetMap<C>: Clone,
+    TransactionHistory<C>: Clone,
 {
     type AssetMetadata = C::AssetMetadata;
     type Checkpoint = C::Checkpoint;
@@ -1191,12 +2147,20 @@ where
         &mut self,
         request: SignRequest<Self::AssetMetadata, C>,
     ) -> LocalBoxFutureResult<SignResult<C>, Self::Error> {
-        Box::pin(async move { Ok(self.sign(request.transaction)) })
+        Box::pin(async move { Ok(self.sign(request)) })
     }
 
     #[inline]
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error> {
-        Box::pin(async move { Ok(self.address()) })
+        Box::pin(async move { Ok(self.address(Default::default())) })
+    }
+
+    #[inline]
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error> {
+        Box::pin(async move { Ok(self.address(account)) })
     }
 
     #[inline]
@@ -1223,22 +2187,69 @@ where
     where
         TransferPost<C>: Clone,
     {
-        Box::pin(async move { Ok(self.sign_with_transaction_data(request.transaction)) })
+        Box::pin(async move { Ok(self.sign_with_transaction_data(request)) })
+    }
+
+    #[inline]
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse<C, C::Checkpoint>, Self::Error> {
+        Box::pin(async move { Ok(Signer::transaction_history(self, request)) })
+    }
+
+    #[inline]
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest<C>,
+    ) -> LocalBoxFutureResult<ConsolidateResult<C>, Self::Error> {
+        Box::pin(async move {
+            Ok(Signer::consolidate(
+                self,
+                request.account,
+                request.asset_id,
+                request.max_posts,
+            ))
+        })
+    }
+
+    #[inline]
+    fn plan(
+        &mut self,
+        request: PlanRequest<C>,
+    ) -> LocalBoxFutureResult<PlanResult<C>, Self::Error> {
+        Box::pin(async move {
+            Ok(Signer::plan(
+                self,
+                request.account,
+                request.transaction,
+                request.coin_selection,
+                &request.non_fungible,
+            ))
+        })
+    }
+
+    #[inline]
+    fn rewind(
+        &mut self,
+        request: RewindRequest<Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<RewindResult<C, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move { Ok(Signer::rewind(self, request)) })
     }
 }
 
 /// Storage State
 ///
 /// This struct stores the [`Checkpoint`],
-/// [`UtxoAccumulator`](Configuration::UtxoAccumulator) and [`AssetMap`] of
-/// a [`SignerState`].
+/// [`UtxoAccumulator`](Configuration::UtxoAccumulator), [`AssetMap`] and
+/// [`TransactionHistory`] of a [`SignerState`].
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "C::Checkpoint: Deserialize<'de>, C::UtxoAccumulator: Deserialize<'de>, C::AssetMap: Deserialize<'de>",
-            serialize = "C::Checkpoint: Serialize, C::UtxoAccumulator: Serialize, C::AssetMap: Serialize",
+            deserialize = "C::Checkpoint: Deserialize<'de>, C::UtxoAccumulator: Deserialize<'de>, AccountAssetMap<C>: Deserialize<'de>, TransactionHistory<C>: Deserialize<'de>",
+            serialize = "C::Checkpoint: Serialize, C::UtxoAccumulator: Serialize, AccountAssetMap<C>: Serialize, TransactionHistory<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -1246,12 +2257,20 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "C::Checkpoint: Clone, C::UtxoAccumulator: Clone, C::AssetMap: Clone"),
-    Debug(bound = "C::Checkpoint: Debug, C::UtxoAccumulator: Debug, C::AssetMap: Debug"),
-    Eq(bound = "C::Checkpoint: Eq, C::UtxoAccumulator: Eq, C::AssetMap: Eq"),
-    Hash(bound = "C::Checkpoint: Hash, C::UtxoAccumulator: Hash, C::AssetMap: Hash"),
+    Clone(
+        bound = "C::Checkpoint: Clone, C::UtxoAccumulator: Clone, AccountAssetMap<C>: Clone, TransactionHistory<C>: Clone"
+    ),
+    Debug(
+        bound = "C::Checkpoint: Debug, C::UtxoAccumulator: Debug, AccountAssetMap<C>: Debug, TransactionHistory<C>: Debug"
+    ),
+    Eq(
+        bound = "C::Checkpoint: Eq, C::UtxoAccumulator: Eq, AccountAssetMap<C>: Eq, TransactionHistory<C>: Eq"
+    ),
+    Hash(
+        bound = "C::Checkpoint: Hash, C::UtxoAccumulator: Hash, AccountAssetMap<C>: Hash, TransactionHistory<C>: Hash"
+    ),
     PartialEq(
-        bound = "C::Checkpoint: PartialEq, C::UtxoAccumulator: PartialEq, C::AssetMap: PartialEq"
+        bound = "C::Checkpoint: PartialEq, C::UtxoAccumulator: PartialEq, AccountAssetMap<C>: PartialEq, TransactionHistory<C>: PartialEq"
     )
 )]
 pub struct StorageState<C>
@@ -1265,7 +2284,11 @@ where
     utxo_accumulator: C::UtxoAccumulator,
 
     /// Assets
-    assets: C::AssetMap,
+    assets: AccountAssetMap<C>,
+
+    /// Transaction History
+    #[cfg_attr(feature = "serde", serde(default))]
+    history: TransactionHistory<C>,
 }
 
 impl<C> StorageState<C>
@@ -1280,6 +2303,7 @@ where
             checkpoint: Checkpoint::from_utxo_accumulator(&utxo_accumulator),
             utxo_accumulator,
             assets: Default::default(),
+            history: Default::default(),
         }
     }
 
@@ -1288,11 +2312,13 @@ where
     pub fn update_from_signer(&mut self, signer: &Signer<C>)
     where
         C::UtxoAccumulator: Clone,
-        C::AssetMap: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
     {
         self.checkpoint = signer.state.checkpoint.clone();
         self.utxo_accumulator = signer.state.utxo_accumulator.clone();
         self.assets = signer.state.assets.clone();
+        self.history = signer.state.history.clone();
     }
 
     /// Builds a new [`StorageState`] from `signer`.
@@ -1300,12 +2326,14 @@ where
     pub fn from_signer(signer: &Signer<C>) -> Self
     where
         C::UtxoAccumulator: Clone,
-        C::AssetMap: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
     {
         Self {
             checkpoint: signer.state.checkpoint.clone(),
             utxo_accumulator: signer.state.utxo_accumulator.clone(),
             assets: signer.state.assets.clone(),
+            history: signer.state.history.clone(),
         }
     }
 
@@ -1314,11 +2342,13 @@ where
     pub fn update_signer(&self, signer: &mut Signer<C>)
     where
         C::UtxoAccumulator: Clone,
-        C::AssetMap: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
     {
         signer.state.checkpoint = self.checkpoint.clone();
         signer.state.utxo_accumulator = self.utxo_accumulator.clone();
         signer.state.assets = self.assets.clone();
+        signer.state.history = self.history.clone();
     }
 
     /// Initializes a [`Signer`] from `self`, `accounts`, `parameters` and `proving_context`.
@@ -1330,7 +2360,8 @@ where
     ) -> Signer<C>
     where
         C::UtxoAccumulator: Clone,
-        C::AssetMap: Clone,
+        AccountAssetMap<C>: Clone,
+        TransactionHistory<C>: Clone,
     {
         let mut signer = Signer::new(
             parameters,
@@ -0,0 +1,454 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer Persistent Storage
+//!
+//! This module defines the [`Backend`] abstraction for persisting the [`StorageState`] of a
+//! [`Signer`] and the [`PersistentSigner`] which writes a snapshot to its backend after every
+//! successful call which changes the state of the signer. See `FileBackend` for an implementation
+//! on top of the encrypted file system in `manta_accounting::fs`.
+
+use crate::{
+    key::AccountIndex,
+    transfer::{
+        Address, Asset, Compiler, DecryptionKey, Identifier, Memo, Note, Nullifier, Parameters,
+        TransferPost, Utxo,
+    },
+    wallet::signer::{
+        AccountAssetMap, Configuration, Connection, ConsolidateRequest, ConsolidateResult,
+        IdentityRequest, IdentityResponse, ParallelSafe, PlanRequest, PlanResult, RewindRequest,
+        RewindResult, SignRequest, SignResult, SignWithTransactionDataResult, Signer, StorageState,
+        SyncRequest, SyncResult, TransactionDataRequest, TransactionDataResponse,
+        TransactionHistory, TransactionHistoryRequest, TransactionHistoryResponse,
+    },
+};
+use alloc::boxed::Box;
+use manta_crypto::constraint::measure::Measure;
+use manta_util::future::LocalBoxFutureResult;
+
+#[cfg(all(feature = "fs", feature = "serde", feature = "std"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "fs", feature = "serde", feature = "std")))
+)]
+pub use file::{FileBackend, FileBackendError};
+
+/// Storage Backend
+pub trait Backend<C>
+where
+    C: Configuration,
+{
+    /// Error Type
+    type Error;
+
+    /// Loads the last [`StorageState`] saved to `self`, returning `None` if no state has been
+    /// saved yet.
+    fn load(&mut self) -> Result<Option<StorageState<C>>, Self::Error>;
+
+    /// Saves `state` to `self`, replacing the previously saved state.
+    ///
+    /// # Crash Consistency
+    ///
+    /// Implementations should write `state` atomically, so that a crash during a call to this
+    /// method leaves either the previous state or `state` visible to [`load`](Self::load).
+    fn save(&mut self, state: &StorageState<C>) -> Result<(), Self::Error>;
+}
+
+/// Persistent Signer
+///
+/// This signer saves a snapshot of its [`StorageState`] to its [`Backend`] after every successful
+/// synchronization, signature, consolidation and rewind, and restores the last snapshot when it is
+/// built.
+pub struct PersistentSigner<C, B>
+where
+    C: Configuration,
+    B: Backend<C>,
+{
+    /// Signer
+    signer: Signer<C>,
+
+    /// Storage Backend
+    backend: B,
+}
+
+impl<C, B> PersistentSigner<C, B>
+where
+    C: Configuration,
+    B: Backend<C>,
+    C::UtxoAccumulator: Clone,
+    AccountAssetMap<C>: Clone,
+    TransactionHistory<C>: Clone,
+{
+    /// Builds a new [`PersistentSigner`] from `signer` and `backend`, restoring the state of
+    /// `signer` from the last snapshot saved in `backend` if there is one.
+    #[inline]
+    pub fn new(mut signer: Signer<C>, mut backend: B) -> Result<Self, B::Error> {
+        if let Some(state) = backend.load()? {
+            state.update_signer(&mut signer);
+        }
+        Ok(Self { signer, backend })
+    }
+
+    /// Returns a shared reference to the underlying signer.
+    #[inline]
+    pub fn signer(&self) -> &Signer<C> {
+        &self.signer
+    }
+
+    /// Returns a shared reference to the storage backend.
+    #[inline]
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Saves a snapshot of the current state of the signer to the storage backend.
+    #[inline]
+    pub fn save(&mut self) -> Result<(), B::Error> {
+        self.backend.save(&StorageState::from_signer(&self.signer))
+    }
+
+    /// Returns the underlying signer and storage backend, dropping `self`.
+    #[inline]
+    pub fn into_inner(self) -> (Signer<C>, B) {
+        (self.signer, self.backend)
+    }
+}
+
+impl<C, B> Connection<C> for PersistentSigner<C, B>
+where
+    C: Configuration,
+    B: Backend<C>,
+    Asset<C>: ParallelSafe,
+    Compiler<C>: Measure,
+    DecryptionKey<C>: ParallelSafe,
+    Identifier<C>: ParallelSafe,
+    Memo<C>: Clone + ParallelSafe,
+    Note<C>: Clone + ParallelSafe,
+    Nullifier<C>: Clone,
+    Parameters<C>: ParallelSafe,
+    Utxo<C>: Clone + ParallelSafe,
+    C::UtxoAccumulator: Clone,
+    AccountAssetMap<C>: Clone,
+    TransactionHistory<C>: Clone,
+{
+    type AssetMetadata = C::AssetMetadata;
+    type Checkpoint = C::Checkpoint;
+    type Error = B::Error;
+
+    #[inline]
+    fn sync(
+        &mut self,
+        request: SyncRequest<C, C::Checkpoint>,
+    ) -> LocalBoxFutureResult<SyncResult<C, C::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let result = self.signer.sync(request);
+            if result.is_ok() {
+                self.save()?;
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn sign(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<SignResult<C>, Self::Error> {
+        Box::pin(async move {
+            let result = self.signer.sign(request);
+            if result.is_ok() {
+                self.save()?;
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn address(&mut self) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error> {
+        Box::pin(async move { Ok(self.signer.address(Default::default())) })
+    }
+
+    #[inline]
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error> {
+        Box::pin(async move { Ok(self.signer.address(account)) })
+    }
+
+    #[inline]
+    fn transaction_data(
+        &mut self,
+        request: TransactionDataRequest<C>,
+    ) -> LocalBoxFutureResult<TransactionDataResponse<C>, Self::Error> {
+        Box::pin(async move { Ok(self.signer.batched_transaction_data(request.0)) })
+    }
+
+    #[inline]
+    fn identity_proof(
+        &mut self,
+        request: IdentityRequest<C>,
+    ) -> LocalBoxFutureResult<IdentityResponse<C>, Self::Error> {
+        Box::pin(async move { Ok(self.signer.batched_identity_proof(request.0)) })
+    }
+
+    #[inline]
+    fn sign_with_transaction_data(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<SignWithTransactionDataResult<C>, Self::Error>
+    where
+        TransferPost<C>: Clone,
+    {
+        Box::pin(async move {
+            let result = self.signer.sign_with_transaction_data(request);
+            if result.is_ok() {
+                self.save()?;
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse<C, C::Checkpoint>, Self::Error> {
+        Box::pin(async move { Ok(self.signer.transaction_history(request)) })
+    }
+
+    #[inline]
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest<C>,
+    ) -> LocalBoxFutureResult<ConsolidateResult<C>, Self::Error> {
+        Box::pin(async move {
+            let result =
+                self.signer
+                    .consolidate(request.account, request.asset_id, request.max_posts);
+            if result.is_ok() {
+                self.save()?;
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn plan(
+        &mut self,
+        request: PlanRequest<C>,
+    ) -> LocalBoxFutureResult<PlanResult<C>, Self::Error> {
+        Box::pin(async move {
+            Ok(self.signer.plan(
+                request.account,
+                request.transaction,
+                request.coin_selection,
+                &request.non_fungible,
+            ))
+        })
+    }
+
+    #[inline]
+    fn rewind(
+        &mut self,
+        request: RewindRequest<Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<RewindResult<C, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let result = self.signer.rewind(request);
+            if result.is_ok() {
+                self.save()?;
+            }
+            Ok(result)
+        })
+    }
+}
+
+/// Encrypted File Backend
+#[cfg(all(feature = "fs", feature = "serde", feature = "std"))]
+mod file {
+    use super::*;
+    use crate::fs::{File, LoadError, SaveError};
+    use core::{
+        fmt::{self, Debug, Display},
+        marker::PhantomData,
+    };
+    use manta_util::serde::{de::DeserializeOwned, Serialize};
+    use std::{
+        fs,
+        io::Error as IoError,
+        path::{Path, PathBuf},
+    };
+
+    /// File Backend Error
+    #[derive(derivative::Derivative)]
+    #[derivative(Debug(bound = "F::Error: Debug"))]
+    pub enum FileBackendError<F>
+    where
+        F: File,
+    {
+        /// I/O Error
+        ///
+        /// This error is returned when the snapshot file or its directory could not be flushed, or
+        /// when the snapshot file could not be moved into place or cleaned up.
+        Io(IoError),
+
+        /// Save Error
+        Save(SaveError<F>),
+
+        /// Corrupted Snapshot
+        ///
+        /// This error is returned when the snapshot file exists but could not be decrypted or
+        /// deserialized, either because it was modified or because the password is wrong.
+        Corrupted(LoadError<F>),
+
+        /// Unsupported Snapshot Version
+        UnsupportedVersion(u16),
+    }
+
+    impl<F> Display for FileBackendError<F>
+    where
+        F: File,
+        F::Error: Display,
+    {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Io(err) => write!(f, "File I/O Error: {err}"),
+                Self::Save(err) => write!(f, "Snapshot Saving Error: {err}"),
+                Self::Corrupted(err) => write!(f, "Corrupted Snapshot: {err}"),
+                Self::UnsupportedVersion(version) => {
+                    write!(f, "Unsupported Snapshot Version: {version}")
+                }
+            }
+        }
+    }
+
+    impl<F> std::error::Error for FileBackendError<F>
+    where
+        F: File,
+        F::Error: Debug + Display,
+    {
+    }
+
+    impl<F> From<IoError> for FileBackendError<F>
+    where
+        F: File,
+    {
+        #[inline]
+        fn from(err: IoError) -> Self {
+            Self::Io(err)
+        }
+    }
+
+    /// Encrypted File Backend
+    ///
+    /// This backend stores the snapshot encrypted under a password at a fixed path. Snapshots are
+    /// first written to a temporary file next to the target path, flushed to disk and then renamed
+    /// over the previous snapshot, so the file at the target path is always a complete snapshot.
+    /// On Unix, the parent directory is flushed after the rename as well, so that the new
+    /// snapshot survives a crash right after [`save`](Backend::save) returns.
+    pub struct FileBackend<F>
+    where
+        F: File<Path = Path>,
+    {
+        /// Snapshot Path
+        path: PathBuf,
+
+        /// Encryption Password
+        password: Box<[u8]>,
+
+        /// Type Parameter Marker
+        __: PhantomData<F>,
+    }
+
+    impl<F> FileBackend<F>
+    where
+        F: File<Path = Path>,
+    {
+        /// Snapshot Format Version
+        pub const VERSION: u16 = 1;
+
+        /// Builds a new [`FileBackend`] storing snapshots at `path` encrypted with `password`.
+        #[inline]
+        pub fn new<P>(path: P, password: &[u8]) -> Self
+        where
+            P: AsRef<Path>,
+        {
+            Self {
+                path: path.as_ref().to_owned(),
+                password: password.into(),
+                __: PhantomData,
+            }
+        }
+
+        /// Returns the path of the snapshot file.
+        #[inline]
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Returns the path of the temporary file used while saving a snapshot.
+        #[inline]
+        pub fn temporary_path(&self) -> PathBuf {
+            let mut path = self.path.clone().into_os_string();
+            path.push(".tmp");
+            path.into()
+        }
+    }
+
+    impl<C, F> Backend<C> for FileBackend<F>
+    where
+        C: Configuration,
+        F: File<Path = Path>,
+        F::Error: Debug + Display,
+        StorageState<C>: DeserializeOwned + Serialize,
+    {
+        type Error = FileBackendError<F>;
+
+        #[inline]
+        fn load(&mut self) -> Result<Option<StorageState<C>>, Self::Error> {
+            let temporary_path = self.temporary_path();
+            if temporary_path.exists() {
+                fs::remove_file(temporary_path)?;
+            }
+            if !self.path.exists() {
+                return Ok(None);
+            }
+            let (version, state) = F::load::<_, (u16, StorageState<C>)>(&self.path, &self.password)
+                .map_err(FileBackendError::Corrupted)?;
+            if version != Self::VERSION {
+                return Err(FileBackendError::UnsupportedVersion(version));
+            }
+            Ok(Some(state))
+        }
+
+        #[inline]
+        fn save(&mut self, state: &StorageState<C>) -> Result<(), Self::Error> {
+            let temporary_path = self.temporary_path();
+            F::save(&temporary_path, &self.password, (Self::VERSION, state))
+                .map_err(FileBackendError::Save)?;
+            fs::File::open(&temporary_path)?.sync_all()?;
+            fs::rename(temporary_path, &self.path)?;
+            #[cfg(unix)]
+            fs::File::open(match self.path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            })?
+            .sync_all()?;
+            Ok(())
+        }
+    }
+}
@@ -21,19 +21,29 @@
 
 use crate::{
     asset::AssetList,
-    transfer::{canonical::Transaction, Address, Asset, Configuration, TransferPost},
+    transfer::{
+        canonical::Transaction, receiver::ReceiverPostError, sender::SenderPostError, Address,
+        Asset, Configuration, InvalidAuthorizationSignature, Nullifier, TransferPost,
+        TransferPostError, UtxoAccumulatorOutput,
+    },
     wallet::{
         ledger,
         signer::{self, SyncData},
         BalanceState, Error, Wallet,
     },
 };
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec::Vec};
 use core::{fmt::Debug, future::Future, hash::Hash, marker::PhantomData, ops::AddAssign};
 use futures::StreamExt;
 use indexmap::IndexSet;
-use manta_crypto::rand::{CryptoRng, Distribution, Rand, RngCore, Sample, SampleUniform};
-use manta_util::{future::LocalBoxFuture, iter::Iterable, num::CheckedSub};
+use manta_crypto::rand::{
+    CryptoRng, Distribution, Rand, RngCore, Sample, SampleUniform, SeedableRng,
+};
+use manta_util::{
+    future::{LocalBoxFuture, LocalBoxFutureResult},
+    iter::Iterable,
+    num::CheckedSub,
+};
 use parking_lot::Mutex;
 use statrs::{distribution::Categorical, StatsError};
 
@@ -87,6 +97,18 @@ where
 
     /// Restart Wallet
     Restart,
+
+    /// Adversarial Post
+    ///
+    /// Posts built from `transaction` and tampered with according to `attack`, which the ledger
+    /// should reject. See [`Attack`] for more.
+    Attack {
+        /// Attack
+        attack: Attack,
+
+        /// Transaction Data
+        transaction: Transaction<C>,
+    },
 }
 
 impl<C> Action<C>
@@ -130,6 +152,15 @@ where
         Self::self_post(is_maximal, Transaction::ToPublic(asset))
     }
 
+    /// Generates an [`Attack`](Self::Attack) of type `attack` on `transaction`.
+    #[inline]
+    pub fn attack(attack: Attack, transaction: Transaction<C>) -> Self {
+        Self::Attack {
+            attack,
+            transaction,
+        }
+    }
+
     /// Computes the [`ActionType`] for a [`Post`](Self::Post) type with the `is_self`,
     /// `is_maximal`, and `transaction` parameters.
     #[inline]
@@ -146,6 +177,8 @@ where
             (true, _, false, PrivateTransfer { .. }) => ActionType::SelfTransfer,
             (false, _, true, PrivateTransfer { .. }) => ActionType::PrivateTransferZero,
             (false, _, false, PrivateTransfer { .. }) => ActionType::PrivateTransfer,
+            (_, _, true, BatchPrivateTransfer { .. }) => ActionType::PrivateTransferZero,
+            (_, _, false, BatchPrivateTransfer { .. }) => ActionType::PrivateTransfer,
             (_, true, _, ToPublic { .. }) => ActionType::FlushToPublic,
             (_, false, true, ToPublic { .. }) => ActionType::ToPublicZero,
             (_, false, false, ToPublic { .. }) => ActionType::ToPublic,
@@ -163,6 +196,7 @@ where
                 transaction,
             } => Self::as_post_type(*is_self, *is_maximal, transaction),
             Self::Restart => ActionType::Restart,
+            Self::Attack { attack, .. } => attack.as_type(),
         }
     }
 }
@@ -194,7 +228,7 @@ pub type MaybeAction<C, L, S> = Result<Action<C>, ActionLabelledError<C, L, S>>;
     derive(Deserialize, Serialize),
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ActionType {
     /// No Action
     Skip,
@@ -228,6 +262,18 @@ pub enum ActionType {
 
     /// Restart Wallet Action
     Restart,
+
+    /// Replay Post Attack
+    ReplayPost,
+
+    /// Double Spend Attack
+    DoubleSpend,
+
+    /// Stale Root Attack
+    StaleRoot,
+
+    /// Tamper Signature Attack
+    TamperSignature,
 }
 
 impl ActionType {
@@ -241,6 +287,57 @@ impl ActionType {
     }
 }
 
+/// Adversarial Actions
+///
+/// Every attack builds posts which a correct ledger rejects with a known [`TransferPostError`],
+/// which the simulation checks with the [`ValidationOracle`] of the ledger.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Attack {
+    /// Replay Post
+    ///
+    /// Posts a zero-valued [`ToPrivate`](Transaction::ToPrivate) and then posts it again, which
+    /// should be rejected because its UTXO is already registered.
+    ReplayPost,
+
+    /// Double Spend
+    ///
+    /// Signs the same spending transaction twice from the same wallet state, as two wallets
+    /// sharing a spending key would, posts the first one and then the second one, which should be
+    /// rejected because its nullifier is already spent.
+    DoubleSpend,
+
+    /// Stale Root
+    ///
+    /// Signs a spending transaction without posting it and posts it once the UTXO accumulator
+    /// output it was built against is no longer accepted by the ledger, which should be rejected
+    /// for that output.
+    StaleRoot,
+
+    /// Tamper Signature
+    ///
+    /// Replaces the authorization signature of a spending post with the signature of another
+    /// post, which should be rejected as a bad signature.
+    TamperSignature,
+}
+
+impl Attack {
+    /// Converts `self` into its corresponding [`ActionType`].
+    #[inline]
+    pub fn as_type(self) -> ActionType {
+        match self {
+            Self::ReplayPost => ActionType::ReplayPost,
+            Self::DoubleSpend => ActionType::DoubleSpend,
+            Self::StaleRoot => ActionType::StaleRoot,
+            Self::TamperSignature => ActionType::TamperSignature,
+        }
+    }
+}
+
 /// Action Distribution Probability Mass Function
 #[cfg_attr(
     feature = "serde",
@@ -281,6 +378,18 @@ pub struct ActionDistributionPMF<T = u64> {
 
     /// Restart Wallet Action Weight
     pub restart: T,
+
+    /// Replay Post Attack Weight
+    pub replay_post: T,
+
+    /// Double Spend Attack Weight
+    pub double_spend: T,
+
+    /// Stale Root Attack Weight
+    pub stale_root: T,
+
+    /// Tamper Signature Attack Weight
+    pub tamper_signature: T,
 }
 
 impl Default for ActionDistributionPMF {
@@ -298,6 +407,10 @@ impl Default for ActionDistributionPMF {
             self_transfer_zero: 1,
             flush_to_public: 1,
             restart: 4,
+            replay_post: 0,
+            double_spend: 0,
+            stale_root: 0,
+            tamper_signature: 0,
         }
     }
 }
@@ -335,6 +448,10 @@ impl TryFrom<ActionDistributionPMF> for ActionDistribution {
                 pmf.self_transfer_zero as f64,
                 pmf.flush_to_public as f64,
                 pmf.restart as f64,
+                pmf.replay_post as f64,
+                pmf.double_spend as f64,
+                pmf.stale_root as f64,
+                pmf.tamper_signature as f64,
             ])?,
         })
     }
@@ -358,6 +475,10 @@ impl Distribution<ActionType> for ActionDistribution {
             8 => ActionType::SelfTransferZero,
             9 => ActionType::FlushToPublic,
             10 => ActionType::Restart,
+            11 => ActionType::ReplayPost,
+            12 => ActionType::DoubleSpend,
+            13 => ActionType::StaleRoot,
+            14 => ActionType::TamperSignature,
             _ => unreachable!(),
         }
     }
@@ -382,6 +503,50 @@ where
     fn public_balances(&self) -> LocalBoxFuture<Option<AssetList<C::AssetId, C::AssetValue>>>;
 }
 
+/// Validation Oracle
+///
+/// Ledger connection which reports why the ledger rejects posts, used by the simulation to check
+/// that adversarial posts are rejected for the expected reason.
+pub trait ValidationOracle<C>: ledger::Connection
+where
+    C: Configuration,
+{
+    /// Account Identifier Type
+    type AccountId;
+
+    /// Sender Ledger Error Type
+    type SenderError;
+
+    /// Receiver Ledger Error Type
+    type ReceiverError;
+
+    /// Transfer Ledger Error Type
+    type TransferError;
+
+    /// Validates and applies `posts` to the ledger in order, returning the error of the first post
+    /// which is not valid. The posts before it stay applied.
+    fn try_write(
+        &mut self,
+        posts: Vec<TransferPost<C>>,
+    ) -> LocalBoxFutureResult<Result<(), ValidationError<C, Self>>, Self::Error>;
+
+    /// Returns `true` if the ledger currently accepts senders built against `output`, or `None` if
+    /// the ledger could not be reached.
+    fn has_matching_utxo_accumulator_output<'s>(
+        &'s self,
+        output: &'s UtxoAccumulatorOutput<C>,
+    ) -> LocalBoxFuture<'s, Option<bool>>;
+}
+
+/// [`ValidationOracle`] Error Type
+pub type ValidationError<C, L> = TransferPostError<
+    C,
+    <L as ValidationOracle<C>>::AccountId,
+    <L as ValidationOracle<C>>::SenderError,
+    <L as ValidationOracle<C>>::ReceiverError,
+    <L as ValidationOracle<C>>::TransferError,
+>;
+
 /// Ledger Alias Trait
 ///
 /// This `trait` is used as an alias for the [`Read`](ledger::Read) and [`Write`](ledger::Write)
@@ -403,11 +568,11 @@ where
 /// Actor
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "Wallet<C, L, S, B>: Clone"),
-    Debug(bound = "Wallet<C, L, S, B>: Debug"),
+    Clone(bound = "Wallet<C, L, S, B>: Clone, TransferPost<C>: Clone"),
+    Debug(bound = "Wallet<C, L, S, B>: Debug, TransferPost<C>: Debug"),
     Default(bound = "Wallet<C, L, S, B>: Default"),
-    Eq(bound = "Wallet<C, L, S, B>: Eq"),
-    PartialEq(bound = "Wallet<C, L, S, B>: PartialEq")
+    Eq(bound = "Wallet<C, L, S, B>: Eq, TransferPost<C>: Eq"),
+    PartialEq(bound = "Wallet<C, L, S, B>: PartialEq, TransferPost<C>: PartialEq")
 )]
 pub struct Actor<C, L, S, B>
 where
@@ -424,6 +589,9 @@ where
 
     /// Actor Lifetime
     pub lifetime: usize,
+
+    /// Posts Withheld for a [`StaleRoot`](Attack::StaleRoot) Attack
+    withheld: Option<Vec<TransferPost<C>>>,
 }
 
 impl<C, L, S, B> Actor<C, L, S, B>
@@ -444,6 +612,7 @@ where
             wallet,
             distribution,
             lifetime,
+            withheld: None,
         }
     }
 
@@ -499,6 +668,156 @@ where
         self.wallet.post(transaction, metadata).await
     }
 
+    /// Signs `transaction` without posting it, returning the posts built by the signer.
+    #[inline]
+    async fn sign(
+        &mut self,
+        transaction: Transaction<C>,
+    ) -> Result<Vec<TransferPost<C>>, Error<C, L, S>> {
+        Ok(self.wallet.sign(transaction, None).await?.posts)
+    }
+
+    /// Writes `posts` to the ledger, returning `true` if the ledger accepted all of them.
+    #[inline]
+    async fn write(&mut self, posts: Vec<TransferPost<C>>) -> Result<bool, Error<C, L, S>> {
+        self.wallet
+            .ledger_mut()
+            .write(posts)
+            .await
+            .map_err(Error::LedgerConnectionError)
+    }
+
+    /// Writes `posts` to the ledger, returning the error of the first post the ledger rejected.
+    #[inline]
+    async fn try_write(
+        &mut self,
+        posts: Vec<TransferPost<C>>,
+    ) -> Result<Result<(), ValidationError<C, L>>, Error<C, L, S>>
+    where
+        L: ValidationOracle<C>,
+    {
+        self.wallet
+            .ledger_mut()
+            .try_write(posts)
+            .await
+            .map_err(Error::LedgerConnectionError)
+    }
+
+    /// Performs `attack` on `transaction`, returning `false` if the ledger rejected the honest
+    /// posts the attack is built on, in which case the attack is not performed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the ledger accepts the adversarial posts or rejects them with another
+    /// error than the one expected for `attack`.
+    #[inline]
+    async fn attack(
+        &mut self,
+        attack: Attack,
+        transaction: Transaction<C>,
+    ) -> Result<bool, Error<C, L, S>>
+    where
+        L: ValidationOracle<C>,
+        Nullifier<C>: PartialEq,
+        TransferPost<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        ValidationError<C, L>: Debug,
+    {
+        self.sync().await?;
+        match attack {
+            Attack::ReplayPost => {
+                let posts = self.sign(transaction).await?;
+                if !self.write(posts.clone()).await? {
+                    return Ok(false);
+                }
+                match self.try_write(posts).await? {
+                    Err(TransferPostError::Receiver(ReceiverPostError::AssetRegistered)) => {}
+                    result => panic!("The ledger accepted a replayed post: {result:?}."),
+                }
+            }
+            Attack::DoubleSpend => {
+                let spend = self.sign(transaction.clone()).await?;
+                let double_spend = self.sign(transaction).await?;
+                let output = match double_spend
+                    .first()
+                    .and_then(|post| post.body.sender_posts.first())
+                {
+                    Some(sender)
+                        if spend
+                            .iter()
+                            .flat_map(|post| &post.body.sender_posts)
+                            .any(|spent| spent.nullifier == sender.nullifier) =>
+                    {
+                        sender.utxo_accumulator_output.clone()
+                    }
+                    _ => return Ok(true),
+                };
+                if !self.write(spend).await? {
+                    return Ok(false);
+                }
+                match self.try_write(double_spend).await? {
+                    Err(TransferPostError::Sender(SenderPostError::AssetSpent)) => {}
+                    Err(TransferPostError::Sender(
+                        SenderPostError::InvalidUtxoAccumulatorOutput,
+                    )) if self
+                        .wallet
+                        .ledger()
+                        .has_matching_utxo_accumulator_output(&output)
+                        .await
+                        == Some(false) => {}
+                    result => panic!("The ledger accepted a double spend: {result:?}."),
+                }
+            }
+            Attack::StaleRoot => match self.withheld.take() {
+                Some(posts) => {
+                    let output = &posts[0].body.sender_posts[0].utxo_accumulator_output;
+                    match self
+                        .wallet
+                        .ledger()
+                        .has_matching_utxo_accumulator_output(output)
+                        .await
+                    {
+                        Some(false) => match self.try_write(posts).await? {
+                            Err(TransferPostError::Sender(
+                                SenderPostError::InvalidUtxoAccumulatorOutput,
+                            )) => {}
+                            result => {
+                                panic!("The ledger accepted a post with a stale root: {result:?}.")
+                            }
+                        },
+                        _ => self.withheld = Some(posts),
+                    }
+                }
+                _ => {
+                    let posts = self.sign(transaction).await?;
+                    if matches!(posts.first(), Some(post) if !post.body.sender_posts.is_empty()) {
+                        self.withheld = Some(posts);
+                    }
+                }
+            },
+            Attack::TamperSignature => {
+                let mut posts = self.sign(transaction.clone()).await?;
+                let mut other = self.sign(transaction).await?;
+                match (posts.first_mut(), other.first_mut()) {
+                    (Some(post), Some(other))
+                        if post.authorization_signature.is_some()
+                            && other.authorization_signature.is_some() =>
+                    {
+                        post.authorization_signature = other.authorization_signature.take();
+                    }
+                    _ => return Ok(true),
+                }
+                match self.try_write(posts).await? {
+                    Err(TransferPostError::InvalidAuthorizationSignature(
+                        InvalidAuthorizationSignature::BadSignature,
+                    )) => {}
+                    result => panic!("The ledger accepted a tampered signature: {result:?}."),
+                }
+            }
+        }
+        Ok(true)
+    }
+
     /// Returns the [`Address`].
     #[inline]
     pub async fn address(&mut self) -> Result<Option<Address<C>>, S::Error> {
@@ -731,6 +1050,50 @@ where
             .unwrap_or(Action::Skip))
     }
 
+    /// Samples a [`ReplayPost`] against `self` using `rng` to select the `AssetId`, returning a
+    /// [`Skip`] if [`ReplayPost`] is impossible.
+    ///
+    /// [`ReplayPost`]: ActionType::ReplayPost
+    /// [`Skip`]: ActionType::Skip
+    #[inline]
+    async fn sample_replay_post<R>(&mut self, rng: &mut R) -> MaybeAction<C, L, S>
+    where
+        L: PublicBalanceOracle<C>,
+        R: RngCore + ?Sized,
+    {
+        match self.public_balances().await {
+            Ok(Some(assets)) => match rng.select_item(assets) {
+                Some(asset) => Ok(Action::attack(
+                    Attack::ReplayPost,
+                    Transaction::ToPrivate(Asset::<C>::zero(asset.id)),
+                )),
+                _ => Ok(Action::Skip),
+            },
+            Ok(_) => Ok(Action::Skip),
+            Err(err) => Err(ActionType::ReplayPost.label(err)),
+        }
+    }
+
+    /// Samples a spending `attack` on the private balance of a random `AssetId` or a [`Skip`] if
+    /// the private balance is empty.
+    ///
+    /// [`Skip`]: ActionType::Skip
+    #[inline]
+    async fn sample_spending_attack<R>(
+        &mut self,
+        attack: Attack,
+        rng: &mut R,
+    ) -> MaybeAction<C, L, S>
+    where
+        R: RngCore + ?Sized,
+    {
+        Ok(self
+            .sample_asset(attack.as_type(), rng)
+            .await?
+            .map(|asset| Action::attack(attack, Transaction::ToPublic(asset)))
+            .unwrap_or(Action::Skip))
+    }
+
     /// Computes the current balance state of the wallet, performs a wallet restart, and then checks
     /// that the balance state has the same or more funds than before the restart.
     #[inline]
@@ -753,6 +1116,234 @@ where
 pub type Event<C, L, S> =
     ActionLabelled<Result<<L as ledger::Write<Vec<TransferPost<C>>>>::Response, Error<C, L, S>>>;
 
+/// Recorded Simulation Event
+///
+/// This is the serializable form of an [`Event`] which keeps the debug representation of the error
+/// instead of the error itself.
+pub type RecordedEvent = ActionLabelled<Result<bool, String>>;
+
+/// Converts `event` into its [`RecordedEvent`] form.
+#[inline]
+fn record_event<C, L, S>(event: &Event<C, L, S>) -> RecordedEvent
+where
+    C: Configuration,
+    L: Ledger<C>,
+    S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+    Error<C, L, S>: Debug,
+{
+    ActionLabelled {
+        action: event.action,
+        value: match &event.value {
+            Ok(response) => Ok(*response),
+            Err(err) => Err(format!("{err:?}")),
+        },
+    }
+}
+
+/// Action Log Record
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Action<C>: Deserialize<'de>",
+            serialize = "Action<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Action<C>: Clone"),
+    Debug(bound = "Action<C>: Debug"),
+    Eq(bound = "Action<C>: Eq"),
+    Hash(bound = "Action<C>: Hash"),
+    PartialEq(bound = "Action<C>: PartialEq")
+)]
+pub struct Record<C>
+where
+    C: Configuration,
+{
+    /// Actor Index
+    pub actor: usize,
+
+    /// Actor Seed
+    ///
+    /// This is the seed of the random number generator the actor sampled its actions from.
+    pub seed: u64,
+
+    /// Step Index of the Actor
+    pub step: usize,
+
+    /// Action
+    ///
+    /// This is `None` whenever sampling the action failed, in which case the error is stored in
+    /// the `event`.
+    pub action: Option<Action<C>>,
+
+    /// Event
+    pub event: RecordedEvent,
+}
+
+/// Action Log
+///
+/// The log of a simulation run by [`Config::record`] which can be re-executed with
+/// [`Config::replay`]. The records are stored in the order in which their events were emitted.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Record<C>: Deserialize<'de>",
+            serialize = "Record<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Record<C>: Clone"),
+    Debug(bound = "Record<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Record<C>: Eq"),
+    Hash(bound = "Record<C>: Hash"),
+    PartialEq(bound = "Record<C>: PartialEq")
+)]
+pub struct ActionLog<C>
+where
+    C: Configuration,
+{
+    /// Simulation Seed
+    ///
+    /// The actor seeds are sampled from a random number generator seeded with this value.
+    pub seed: u64,
+
+    /// Records
+    pub records: Vec<Record<C>>,
+}
+
+/// Replay Divergence
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Record<C>: Deserialize<'de>",
+            serialize = "Record<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Record<C>: Clone"),
+    Debug(bound = "Record<C>: Debug"),
+    Eq(bound = "Record<C>: Eq"),
+    Hash(bound = "Record<C>: Hash"),
+    PartialEq(bound = "Record<C>: PartialEq")
+)]
+pub struct Divergence<C>
+where
+    C: Configuration,
+{
+    /// Index of the Record in the [`ActionLog`]
+    pub index: usize,
+
+    /// Recorded Entry
+    pub record: Record<C>,
+
+    /// Event Emitted by the Replay
+    pub event: RecordedEvent,
+}
+
+/// Replay Report
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Divergence<C>: Deserialize<'de>",
+            serialize = "Divergence<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Divergence<C>: Clone"),
+    Debug(bound = "Divergence<C>: Debug"),
+    Eq(bound = "Divergence<C>: Eq"),
+    Hash(bound = "Divergence<C>: Hash"),
+    PartialEq(bound = "Divergence<C>: PartialEq")
+)]
+pub struct ReplayReport<C>
+where
+    C: Configuration,
+{
+    /// First Divergence from the [`ActionLog`], if any
+    pub divergence: Option<Divergence<C>>,
+
+    /// Flag set to `true` whenever the funds before and after the replay match
+    pub balances_match: bool,
+}
+
+/// Recording Simulation
+///
+/// This `struct` wraps a [`Simulation`] attaching to every event the action it was produced from.
+struct Recorder<C, L, S, B>(Simulation<C, L, S, B>)
+where
+    C: Configuration,
+    L: Ledger<C>,
+    S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+    B: BalanceState<C::AssetId, C::AssetValue>;
+
+impl<C, L, S, B> sim::ActionSimulation for Recorder<C, L, S, B>
+where
+    C: Configuration,
+    C::AssetValue: SampleUniform,
+    L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
+    S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+    B: BalanceState<C::AssetId, C::AssetValue>,
+    Action<C>: Clone,
+    Address<C>: Clone + Eq + Hash,
+    Nullifier<C>: PartialEq,
+    TransferPost<C>: Clone,
+    UtxoAccumulatorOutput<C>: Clone,
+    ValidationError<C, L>: Debug,
+{
+    type Actor = Actor<C, L, S, B>;
+    type Action = MaybeAction<C, L, S>;
+    type Event = (Option<Action<C>>, Event<C, L, S>);
+
+    #[inline]
+    fn sample<'s, R>(
+        &'s self,
+        actor: &'s mut Self::Actor,
+        rng: &'s mut R,
+    ) -> LocalBoxFuture<'s, Option<Self::Action>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        self.0.sample(actor, rng)
+    }
+
+    #[inline]
+    fn act<'s>(
+        &'s self,
+        actor: &'s mut Self::Actor,
+        action: Self::Action,
+    ) -> LocalBoxFuture<'s, Self::Event> {
+        Box::pin(async move {
+            let recorded = action.as_ref().ok().cloned();
+            (recorded, self.0.act(actor, action).await)
+        })
+    }
+}
+
 /// Address Database
 pub type AddressDatabase<C> = IndexSet<Address<C>>;
 
@@ -808,10 +1399,14 @@ impl<C, L, S, B> sim::ActionSimulation for Simulation<C, L, S, B>
 where
     C: Configuration,
     C::AssetValue: SampleUniform,
-    L: Ledger<C> + PublicBalanceOracle<C>,
+    L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
     S: signer::Connection<C, Checkpoint = L::Checkpoint>,
     B: BalanceState<C::AssetId, C::AssetValue>,
     Address<C>: Clone + Eq + Hash,
+    Nullifier<C>: PartialEq,
+    TransferPost<C>: Clone,
+    UtxoAccumulatorOutput<C>: Clone,
+    ValidationError<C, L>: Debug,
 {
     type Actor = Actor<C, L, S, B>;
     type Action = MaybeAction<C, L, S>;
@@ -863,6 +1458,16 @@ where
                 }
                 ActionType::FlushToPublic => actor.flush_to_public(rng).await,
                 ActionType::Restart => Ok(Action::Restart),
+                ActionType::ReplayPost => actor.sample_replay_post(rng).await,
+                ActionType::DoubleSpend => {
+                    actor.sample_spending_attack(Attack::DoubleSpend, rng).await
+                }
+                ActionType::StaleRoot => actor.sample_spending_attack(Attack::StaleRoot, rng).await,
+                ActionType::TamperSignature => {
+                    actor
+                        .sample_spending_attack(Attack::TamperSignature, rng)
+                        .await
+                }
             })
         })
     }
@@ -908,6 +1513,13 @@ where
                         action: ActionType::Restart,
                         value: actor.restart().await,
                     },
+                    Action::Attack {
+                        attack,
+                        transaction,
+                    } => Event {
+                        action: attack.as_type(),
+                        value: actor.attack(attack, transaction).await,
+                    },
                 },
                 Err(err) => Event {
                     action: err.action,
@@ -948,6 +1560,89 @@ where
     Ok(balances)
 }
 
+/// Actor Balances
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "AssetList<C::AssetId, C::AssetValue>: Deserialize<'de>",
+            serialize = "AssetList<C::AssetId, C::AssetValue>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "AssetList<C::AssetId, C::AssetValue>: Clone"),
+    Debug(bound = "AssetList<C::AssetId, C::AssetValue>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "AssetList<C::AssetId, C::AssetValue>: Eq"),
+    Hash(bound = "AssetList<C::AssetId, C::AssetValue>: Hash"),
+    PartialEq(bound = "AssetList<C::AssetId, C::AssetValue>: PartialEq")
+)]
+pub struct ActorBalances<C>
+where
+    C: Configuration,
+{
+    /// Public Balances
+    pub public: AssetList<C::AssetId, C::AssetValue>,
+
+    /// Private Balances
+    pub private: AssetList<C::AssetId, C::AssetValue>,
+}
+
+/// Simulation Summary
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "AssetList<C::AssetId, C::AssetValue>: Deserialize<'de>",
+            serialize = "AssetList<C::AssetId, C::AssetValue>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "AssetList<C::AssetId, C::AssetValue>: Clone"),
+    Debug(bound = "AssetList<C::AssetId, C::AssetValue>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "AssetList<C::AssetId, C::AssetValue>: Eq"),
+    Hash(bound = "AssetList<C::AssetId, C::AssetValue>: Hash"),
+    PartialEq(bound = "AssetList<C::AssetId, C::AssetValue>: PartialEq")
+)]
+pub struct Summary<C>
+where
+    C: Configuration,
+{
+    /// Total Funds before the Simulation
+    pub initial_balances: AssetList<C::AssetId, C::AssetValue>,
+
+    /// Total Funds after the Simulation
+    pub final_balances: AssetList<C::AssetId, C::AssetValue>,
+
+    /// Balances of each Actor after the Simulation
+    pub actors: Vec<ActorBalances<C>>,
+}
+
+impl<C> Summary<C>
+where
+    C: Configuration,
+{
+    /// Returns `true` if the total funds before and after the simulation match.
+    #[inline]
+    pub fn balances_match(&self) -> bool
+    where
+        AssetList<C::AssetId, C::AssetValue>: PartialEq,
+    {
+        self.initial_balances == self.final_balances
+    }
+}
+
 /// Simulation Configuration
 #[cfg_attr(
     feature = "serde",
@@ -967,30 +1662,22 @@ pub struct Config {
 }
 
 impl Config {
-    /// Runs the simulation on the configuration defined in `self`, sending events to the
-    /// `event_subscriber`.
+    /// Builds the actors for the configuration defined in `self` along with the [`Simulation`]
+    /// which knows all of their addresses.
     #[inline]
-    pub async fn run<C, L, S, B, R, GL, GS, F, ES, ESFut>(
+    async fn setup<C, L, S, B, GL, GS>(
         &self,
         mut ledger: GL,
         mut signer: GS,
-        rng: F,
-        mut event_subscriber: ES,
-    ) -> Result<bool, Error<C, L, S>>
+    ) -> (Simulation<C, L, S, B>, Vec<Actor<C, L, S, B>>)
     where
         C: Configuration,
-        C::AssetValue: AddAssign + SampleUniform,
-        for<'v> &'v C::AssetValue: CheckedSub<Output = C::AssetValue>,
-        L: Ledger<C> + PublicBalanceOracle<C>,
+        L: Ledger<C>,
         S: signer::Connection<C, Checkpoint = L::Checkpoint>,
         S::Error: Debug,
         B: BalanceState<C::AssetId, C::AssetValue>,
-        R: CryptoRng + RngCore,
         GL: FnMut(usize) -> L,
         GS: FnMut(usize) -> S,
-        F: FnMut(usize) -> R,
-        ES: Copy + FnMut(&sim::Event<sim::ActionSim<Simulation<C, L, S, B>>>) -> ESFut,
-        ESFut: Future<Output = ()>,
         Address<C>: Clone + Eq + Hash,
     {
         let action_distribution = ActionDistribution::try_from(self.action_distribution)
@@ -1014,6 +1701,77 @@ impl Config {
                 .expect("Missing spending key");
             simulation.addresses.lock().insert(address);
         }
+        (simulation, actors)
+    }
+
+    /// Runs the simulation on the configuration defined in `self`, sending events to the
+    /// `event_subscriber`.
+    #[inline]
+    pub async fn run<C, L, S, B, R, GL, GS, F, ES, ESFut>(
+        &self,
+        ledger: GL,
+        signer: GS,
+        rng: F,
+        event_subscriber: ES,
+    ) -> Result<bool, Error<C, L, S>>
+    where
+        C: Configuration,
+        C::AssetValue: AddAssign + SampleUniform,
+        for<'v> &'v C::AssetValue: CheckedSub<Output = C::AssetValue>,
+        L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
+        S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+        S::Error: Debug,
+        B: BalanceState<C::AssetId, C::AssetValue>,
+        R: CryptoRng + RngCore,
+        GL: FnMut(usize) -> L,
+        GS: FnMut(usize) -> S,
+        F: FnMut(usize) -> R,
+        ES: Copy + FnMut(&sim::Event<sim::ActionSim<Simulation<C, L, S, B>>>) -> ESFut,
+        ESFut: Future<Output = ()>,
+        Address<C>: Clone + Eq + Hash,
+        Nullifier<C>: PartialEq,
+        TransferPost<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        ValidationError<C, L>: Debug,
+    {
+        Ok(self
+            .run_with_summary(ledger, signer, rng, event_subscriber)
+            .await?
+            .balances_match())
+    }
+
+    /// Runs the simulation on the configuration defined in `self` like [`run`](Self::run),
+    /// returning the total funds before and after the simulation along with the final balances
+    /// of every actor.
+    #[inline]
+    pub async fn run_with_summary<C, L, S, B, R, GL, GS, F, ES, ESFut>(
+        &self,
+        ledger: GL,
+        signer: GS,
+        rng: F,
+        mut event_subscriber: ES,
+    ) -> Result<Summary<C>, Error<C, L, S>>
+    where
+        C: Configuration,
+        C::AssetValue: AddAssign + SampleUniform,
+        for<'v> &'v C::AssetValue: CheckedSub<Output = C::AssetValue>,
+        L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
+        S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+        S::Error: Debug,
+        B: BalanceState<C::AssetId, C::AssetValue>,
+        R: CryptoRng + RngCore,
+        GL: FnMut(usize) -> L,
+        GS: FnMut(usize) -> S,
+        F: FnMut(usize) -> R,
+        ES: Copy + FnMut(&sim::Event<sim::ActionSim<Simulation<C, L, S, B>>>) -> ESFut,
+        ESFut: Future<Output = ()>,
+        Address<C>: Clone + Eq + Hash,
+        Nullifier<C>: PartialEq,
+        TransferPost<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        ValidationError<C, L>: Debug,
+    {
+        let (simulation, actors) = self.setup(ledger, signer).await;
         let mut simulator = sim::Simulator::new(sim::ActionSim(simulation), actors);
         let initial_balances =
             measure_balances(simulator.actors.iter_mut().map(|actor| &mut actor.wallet)).await?;
@@ -1023,8 +1781,176 @@ impl Config {
                 event_subscriber(&event).await;
             })
             .await;
+        let mut final_balances = AssetList::new();
+        let mut actors = Vec::with_capacity(simulator.actors.len());
+        for actor in simulator.actors.iter_mut() {
+            actor.sync().await?;
+            let balances = ActorBalances {
+                public: actor
+                    .wallet
+                    .ledger()
+                    .public_balances()
+                    .await
+                    .unwrap_or_default(),
+                private: AssetList::from_iter(
+                    actor
+                        .wallet
+                        .assets()
+                        .convert_iter()
+                        .map(|(id, value)| Asset::<C>::new(id.clone(), value.clone())),
+                ),
+            };
+            final_balances.deposit_all(balances.public.clone());
+            final_balances.deposit_all(balances.private.clone());
+            actors.push(balances);
+        }
+        Ok(Summary {
+            initial_balances,
+            final_balances,
+            actors,
+        })
+    }
+
+    /// Runs the simulation on the configuration defined in `self` like [`run`](Self::run),
+    /// recording every action and event into an [`ActionLog`] and sending each [`Record`] to the
+    /// `event_subscriber`.
+    ///
+    /// Every actor samples its actions from a random number generator of type `R` seeded by the
+    /// log, where the actor seeds are sampled from an `R` seeded with `seed`. The flag returned
+    /// along with the log is set to `true` whenever the funds before and after the simulation
+    /// match.
+    #[inline]
+    pub async fn record<C, L, S, B, R, GL, GS, ES, ESFut>(
+        &self,
+        ledger: GL,
+        signer: GS,
+        seed: u64,
+        mut event_subscriber: ES,
+    ) -> Result<(bool, ActionLog<C>), Error<C, L, S>>
+    where
+        C: Configuration,
+        C::AssetValue: AddAssign + SampleUniform,
+        for<'v> &'v C::AssetValue: CheckedSub<Output = C::AssetValue>,
+        L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
+        S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+        S::Error: Debug,
+        B: BalanceState<C::AssetId, C::AssetValue>,
+        R: CryptoRng + RngCore + SeedableRng,
+        GL: FnMut(usize) -> L,
+        GS: FnMut(usize) -> S,
+        ES: FnMut(&Record<C>) -> ESFut,
+        ESFut: Future<Output = ()>,
+        Action<C>: Clone,
+        Address<C>: Clone + Eq + Hash,
+        Error<C, L, S>: Debug,
+        Nullifier<C>: PartialEq,
+        TransferPost<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        ValidationError<C, L>: Debug,
+    {
+        let (simulation, actors) = self.setup::<_, _, _, B, _, _>(ledger, signer).await;
+        let mut seeds = R::seed_from_u64(seed);
+        let seeds = (0..self.actor_count)
+            .map(|_| seeds.next_u64())
+            .collect::<Vec<_>>();
+        let mut simulator = sim::Simulator::new(sim::ActionSim(Recorder(simulation)), actors);
+        let initial_balances =
+            measure_balances(simulator.actors.iter_mut().map(|actor| &mut actor.wallet)).await?;
+        let records = Mutex::new(Vec::new());
+        simulator
+            .run(|i| R::seed_from_u64(seeds[i]))
+            .for_each_concurrent(None, |event| {
+                let (action, value) = event.event;
+                let record = Record {
+                    actor: event.actor,
+                    seed: seeds[event.actor],
+                    step: event.step,
+                    action,
+                    event: record_event(&value),
+                };
+                let future = event_subscriber(&record);
+                records.lock().push(record);
+                future
+            })
+            .await;
         let final_balances =
             measure_balances(simulator.actors.iter_mut().map(|actor| &mut actor.wallet)).await?;
-        Ok(initial_balances == final_balances)
+        Ok((
+            initial_balances == final_balances,
+            ActionLog {
+                seed,
+                records: records.into_inner(),
+            },
+        ))
+    }
+
+    /// Re-executes the actions in `log` one after the other against the actors built from `ledger`
+    /// and `signer`, stopping at the first event which differs from the recorded one.
+    ///
+    /// Records whose action could not be sampled are replayed as a synchronization of their
+    /// actor. For the replay to be faithful, `ledger` and `signer` must build the same initial
+    /// state as the recorded run, i.e. a fresh ledger with the same public balances and signers
+    /// with the same keys.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `log` contains a record for an actor outside of the configuration
+    /// defined in `self`.
+    #[inline]
+    pub async fn replay<C, L, S, B, GL, GS>(
+        &self,
+        ledger: GL,
+        signer: GS,
+        log: &ActionLog<C>,
+    ) -> Result<ReplayReport<C>, Error<C, L, S>>
+    where
+        C: Configuration,
+        C::AssetValue: AddAssign + SampleUniform,
+        for<'v> &'v C::AssetValue: CheckedSub<Output = C::AssetValue>,
+        L: Ledger<C> + PublicBalanceOracle<C> + ValidationOracle<C>,
+        S: signer::Connection<C, Checkpoint = L::Checkpoint>,
+        S::Error: Debug,
+        B: BalanceState<C::AssetId, C::AssetValue>,
+        GL: FnMut(usize) -> L,
+        GS: FnMut(usize) -> S,
+        Action<C>: Clone,
+        Address<C>: Clone + Eq + Hash,
+        Error<C, L, S>: Debug,
+        Nullifier<C>: PartialEq,
+        TransferPost<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        ValidationError<C, L>: Debug,
+    {
+        let (simulation, mut actors) = self.setup::<_, _, _, B, _, _>(ledger, signer).await;
+        let initial_balances =
+            measure_balances(actors.iter_mut().map(|actor| &mut actor.wallet)).await?;
+        let mut divergence = None;
+        for (index, record) in log.records.iter().enumerate() {
+            let actor = &mut actors[record.actor];
+            let event = match &record.action {
+                Some(action) => {
+                    sim::ActionSimulation::act(&simulation, actor, Ok(action.clone())).await
+                }
+                _ => Event {
+                    action: record.event.action,
+                    value: actor.sync().await.map(|_| true),
+                },
+            };
+            let event = record_event(&event);
+            if event != record.event {
+                divergence = Some(Divergence {
+                    index,
+                    record: record.clone(),
+                    event,
+                });
+                break;
+            }
+        }
+        let final_balances =
+            measure_balances(actors.iter_mut().map(|actor| &mut actor.wallet)).await?;
+        Ok(ReplayReport {
+            divergence,
+            balances_match: initial_balances == final_balances,
+        })
     }
 }
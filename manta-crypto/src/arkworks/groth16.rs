@@ -19,7 +19,8 @@
 use crate::{
     arkworks::{
         constraint::R1CS,
-        ec::PairingEngine,
+        ec::{AffineCurve, PairingEngine, ProjectiveCurve},
+        ff::{Field, PrimeField, Zero},
         relations::r1cs::SynthesisError,
         serialize::{
             ArkReader, ArkWriter, CanonicalDeserialize, CanonicalSerialize, HasDeserialization,
@@ -607,6 +608,49 @@ where
     ) -> Result<bool, Self::Error> {
         ArkGroth16::verify_with_processed_vk(&context.0, input, &proof.0).map_err(|_| Error)
     }
+
+    /// Verifies all of the `proofs` at once by checking a random linear combination of their
+    /// pairing equations, which only requires a single final exponentiation.
+    #[inline]
+    fn verify_batch<R>(
+        context: &Self::VerifyingContext,
+        proofs: &[(Self::Input, &Self::Proof)],
+        rng: &mut R,
+    ) -> Result<bool, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        match proofs {
+            [] => return Ok(true),
+            [(input, proof)] => return Self::verify(context, input, proof),
+            _ => {}
+        }
+        let mut pairs = Vec::with_capacity(proofs.len() + 2);
+        let mut inputs_sum = E::G1Projective::zero();
+        let mut c_sum = E::G1Projective::zero();
+        let mut r_sum = E::Fr::zero();
+        for (input, proof) in proofs {
+            let r = E::Fr::from((u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64()));
+            let r_repr = r.into_repr();
+            inputs_sum += ark_groth16::prepare_inputs(&context.0, input)
+                .map_err(|_| Error)?
+                .mul(r_repr);
+            c_sum += proof.0.c.mul(r_repr);
+            pairs.push((proof.0.a.mul(r_repr).into_affine().into(), proof.0.b.into()));
+            r_sum += r;
+        }
+        pairs.push((
+            inputs_sum.into_affine().into(),
+            context.0.gamma_g2_neg_pc.clone(),
+        ));
+        pairs.push((
+            c_sum.into_affine().into(),
+            context.0.delta_g2_neg_pc.clone(),
+        ));
+        Ok(E::final_exponentiation(&E::miller_loop(pairs.iter()))
+            .map(|value| value == context.0.alpha_g1_beta_g2.pow(r_sum.into_repr()))
+            .unwrap_or(false))
+    }
 }
 
 /// Implements [`Input`] over [`Groth16`] for `$type` that can convert to a field element.
@@ -100,6 +100,34 @@ pub trait ProofSystem {
         input: &Self::Input,
         proof: &Self::Proof,
     ) -> Result<bool, Self::Error>;
+
+    /// Verifies that every proof in `proofs`, generated from this proof system, is valid against
+    /// its paired input, returning `false` if any of them is invalid.
+    ///
+    /// # Implementation Note
+    ///
+    /// The default implementation verifies each proof one at a time with [`verify`]. Proof
+    /// systems which can amortize verification over many proofs should override this method,
+    /// sampling any randomness they need from `rng`.
+    ///
+    /// [`verify`]: Self::verify
+    #[inline]
+    fn verify_batch<R>(
+        context: &Self::VerifyingContext,
+        proofs: &[(Self::Input, &Self::Proof)],
+        rng: &mut R,
+    ) -> Result<bool, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let _ = rng;
+        for (input, proof) in proofs {
+            if !Self::verify(context, input, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
 
 /// Proof System Input
@@ -0,0 +1,170 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Simulation Ledger Server
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use core::time::Duration;
+use manta_pay::{
+    config::utxo::AssetValue,
+    parameters::load_parameters,
+    simulation::{
+        ledger::{block::produce_blocks, http::server::Server, FeeSchedule, Ledger},
+        Simulation,
+    },
+};
+use std::path::PathBuf;
+use tokio::net::TcpListener;
+
+/// Simulation Ledger Server
+///
+/// Serves a simulation ledger over HTTP, optionally restoring it from a snapshot at startup,
+/// saving a new snapshot periodically and producing blocks from its mempool.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// HTTP Server Address
+    #[clap(long, default_value = "127.0.0.1:29988")]
+    address: String,
+
+    /// WebSocket Address to Serve Ledger Events at
+    #[clap(long)]
+    events_address: Option<String>,
+
+    /// Snapshot Path, loaded at startup if it exists and saved to periodically
+    #[clap(long)]
+    snapshot: Option<PathBuf>,
+
+    /// Number of Seconds between Snapshots
+    #[clap(long, default_value_t = 60)]
+    persist_interval: u64,
+
+    /// Number of Milliseconds between Blocks, which include the Posts Submitted to the Mempool
+    #[clap(long)]
+    block_time: Option<u64>,
+
+    /// Maximum Number of Receivers and Nullifiers per Pull
+    #[clap(long)]
+    page_size: Option<usize>,
+
+    /// Number of Public Accounts to Fund when Starting without a Snapshot
+    #[clap(long, default_value_t = 0)]
+    accounts: usize,
+
+    /// Number of Asset Ids to Fund each Public Account with
+    #[clap(long, default_value_t = 1)]
+    asset_ids: usize,
+
+    /// Starting Public Balance of each Account and Asset Id
+    #[clap(long, default_value_t = 0)]
+    starting_balance: AssetValue,
+
+    /// Fee Charged to the Source Account of each ToPrivate Transfer
+    #[clap(long)]
+    to_private_fee: Option<AssetValue>,
+
+    /// Fee Charged to the Sink Account of each ToPublic Transfer
+    #[clap(long)]
+    to_public_fee: Option<AssetValue>,
+
+    /// Minimum Nonzero Public Balance an Account can be Left With
+    #[clap(long)]
+    existential_deposit: Option<AssetValue>,
+}
+
+/// Runs the simulation ledger server.
+pub fn main() {
+    let arguments = Arguments::parse();
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Unable to load parameters");
+    let mut ledger = match &arguments.snapshot {
+        Some(path) if path.exists() => {
+            Ledger::load(path, utxo_accumulator_model, verifying_context, parameters)
+                .unwrap_or_else(|err| {
+                    Arguments::command()
+                        .error(
+                            ErrorKind::Io,
+                            format_args!("Unable to load the ledger snapshot: {err}"),
+                        )
+                        .exit()
+                })
+        }
+        _ => {
+            let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+            Simulation {
+                actor_count: arguments.accounts,
+                actor_lifetime: 0,
+                asset_id_count: arguments.asset_ids,
+                starting_balance: arguments.starting_balance,
+                attack_weight: 0,
+            }
+            .setup(&mut ledger);
+            ledger
+        }
+    };
+    if let Some(page_size) = arguments.page_size {
+        ledger.set_page_size(page_size);
+    }
+    let fee_schedule = ledger.fee_schedule();
+    ledger.set_fee_schedule(FeeSchedule {
+        to_private: arguments.to_private_fee.unwrap_or(fee_schedule.to_private),
+        to_public: arguments.to_public_fee.unwrap_or(fee_schedule.to_public),
+    });
+    if let Some(existential_deposit) = arguments.existential_deposit {
+        ledger.set_existential_deposit(existential_deposit);
+    }
+    let server = Server::new(ledger);
+    match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime.block_on(async {
+            if let Some(block_time) = arguments.block_time {
+                tokio::spawn(produce_blocks(
+                    server.ledger().clone(),
+                    Duration::from_millis(block_time),
+                ));
+            }
+            if let Some(events_address) = arguments.events_address {
+                let listener = TcpListener::bind(&events_address)
+                    .await
+                    .expect("Unable to bind the ledger events address.");
+                println!("Serving the simulation ledger events at {events_address}");
+                tokio::spawn(server.clone().serve_events(listener));
+            }
+            println!("Serving the simulation ledger at {}", arguments.address);
+            let result = match arguments.snapshot {
+                Some(path) => {
+                    server
+                        .serve_with_snapshots(
+                            arguments.address,
+                            path,
+                            Duration::from_secs(arguments.persist_interval),
+                        )
+                        .await
+                }
+                _ => server.serve(arguments.address).await,
+            };
+            result.expect("Unable to serve the simulation ledger.");
+        }),
+        Err(err) => Arguments::command()
+            .error(
+                ErrorKind::Io,
+                format_args!("Unable to start `tokio` runtime: {err}"),
+            )
+            .exit(),
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Reference Signer Server
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use manta_crypto::rand::OsRng;
+use manta_pay::{
+    key::Mnemonic,
+    parameters::load_parameters,
+    signer::{
+        client::network::{Network, NetworkSpecific},
+        functions::{accounts_from_mnemonic, new_signer_from_model},
+        server::Server,
+    },
+};
+use std::path::PathBuf;
+use tokio::net::TcpListener;
+
+/// Reference Signer Server
+///
+/// Hosts one signer for every network, all derived from the same mnemonic, over HTTP and
+/// optionally over WebSocket.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// HTTP Server Address
+    #[clap(long, default_value = "127.0.0.1:29987")]
+    http: String,
+
+    /// WebSocket Server Address
+    #[clap(long)]
+    websocket: Option<String>,
+
+    /// WebSocket Network: one of `dolphin`, `calamari` or `manta`
+    #[clap(long, default_value = "dolphin")]
+    network: String,
+
+    /// Mnemonic Phrase, sampled at random if missing
+    #[clap(long)]
+    mnemonic: Option<String>,
+
+    /// Parameter Directory, defaults to a directory inside the system temporary directory
+    #[clap(long)]
+    parameters: Option<PathBuf>,
+}
+
+impl Arguments {
+    /// Parses the WebSocket network from the arguments.
+    #[inline]
+    fn network(&self) -> Network {
+        match self.network.to_lowercase().as_str() {
+            "dolphin" => Network::Dolphin,
+            "calamari" => Network::Calamari,
+            "manta" => Network::Manta,
+            _ => Self::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format_args!("Unknown network: {}", self.network),
+                )
+                .exit(),
+        }
+    }
+
+    /// Parses the mnemonic from the arguments, sampling one if it was not given.
+    #[inline]
+    fn mnemonic(&self) -> Mnemonic {
+        match &self.mnemonic {
+            Some(phrase) => Mnemonic::new(phrase).unwrap_or_else(|err| {
+                Self::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format_args!("Invalid mnemonic: {err:?}"),
+                    )
+                    .exit()
+            }),
+            _ => {
+                let mnemonic = Mnemonic::sample(&mut OsRng);
+                println!("Sampled Mnemonic: {}", mnemonic.as_ref());
+                mnemonic
+            }
+        }
+    }
+}
+
+/// Runs the reference signer server.
+pub fn main() {
+    let arguments = Arguments::parse();
+    let network = arguments.network();
+    let mnemonic = arguments.mnemonic();
+    let directory = arguments
+        .parameters
+        .clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("manta-signer-server"));
+    if let Err(err) = std::fs::create_dir_all(&directory) {
+        Arguments::command()
+            .error(
+                ErrorKind::Io,
+                format_args!("Unable to create the parameter directory: {err}"),
+            )
+            .exit()
+    }
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(&directory).expect("Unable to load parameters");
+    let signer = || {
+        let mut signer = new_signer_from_model(
+            parameters.clone(),
+            proving_context.clone(),
+            &utxo_accumulator_model,
+        );
+        signer.load_accounts(accounts_from_mnemonic(mnemonic.clone()));
+        signer.update_authorization_context();
+        Some(signer)
+    };
+    match tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .build()
+    {
+        Ok(runtime) => runtime.block_on(async {
+            let server = Server::new(NetworkSpecific {
+                dolphin: signer(),
+                calamari: signer(),
+                manta: signer(),
+            });
+            if let Some(address) = &arguments.websocket {
+                let listener = TcpListener::bind(address)
+                    .await
+                    .expect("Unable to bind the WebSocket server address.");
+                println!("Serving {network} signer over WebSocket at {address}");
+                tokio::spawn(server.clone().serve_websocket(network, listener));
+            }
+            println!("Serving signers over HTTP at {}", arguments.http);
+            server
+                .serve(arguments.http.clone())
+                .await
+                .expect("Unable to serve the HTTP signer server.");
+        }),
+        Err(err) => Arguments::command()
+            .error(
+                ErrorKind::Io,
+                format_args!("Unable to start `tokio` runtime: {err}"),
+            )
+            .exit(),
+    }
+}
@@ -17,13 +17,62 @@
 //! Manta Pay Simulation
 
 use clap::{error::ErrorKind, CommandFactory, Parser};
-use manta_crypto::rand::OsRng;
-use manta_pay::{parameters::load_parameters, simulation::Simulation};
+use manta_crypto::rand::{OsRng, Rand};
+use manta_pay::{
+    parameters::load_parameters,
+    simulation::{ActionLog, Simulation},
+};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+
+/// Manta Pay Simulation
+///
+/// Runs the simulation, optionally recording every action to a log which can be replayed later to
+/// reproduce the run, or writing a JSON report of the run.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Simulation Configuration
+    #[clap(flatten)]
+    simulation: Simulation,
+
+    /// Path to Record the Action Log to
+    #[clap(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Seed for the Recorded Run, sampled at random if not given
+    #[clap(long, requires = "record")]
+    seed: Option<u64>,
+
+    /// Path of an Action Log to Replay
+    #[clap(long, conflicts_with = "report")]
+    replay: Option<PathBuf>,
+
+    /// Path to Write the JSON Report of the Run to
+    #[clap(long, conflicts_with = "record")]
+    report: Option<PathBuf>,
+}
 
 /// Runs the Manta Pay simulation.
 pub fn main() {
-    let simulation = Simulation::parse();
+    let arguments = Arguments::parse();
+    let simulation = arguments.simulation;
     let mut rng = OsRng;
+    let replay_log = arguments.replay.as_ref().map(|path| {
+        File::open(path)
+            .map_err(serde_json::Error::io)
+            .and_then(|file| serde_json::from_reader::<_, ActionLog>(BufReader::new(file)))
+            .unwrap_or_else(|err| {
+                Arguments::command()
+                    .error(
+                        ErrorKind::Io,
+                        format_args!("Unable to load action log {}: {err}", path.display()),
+                    )
+                    .exit()
+            })
+    });
     let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
     let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
         load_parameters(directory.path()).expect("Unable to load parameters");
@@ -32,17 +81,77 @@ pub fn main() {
         .build()
     {
         Ok(runtime) => runtime.block_on(async {
-            simulation
-                .run(
-                    &parameters,
-                    &utxo_accumulator_model,
-                    &proving_context,
-                    verifying_context,
-                    &mut rng,
-                )
-                .await
+            if let Some(log) = replay_log {
+                let report = simulation
+                    .replay(
+                        &parameters,
+                        &utxo_accumulator_model,
+                        &proving_context,
+                        verifying_context,
+                        &log,
+                    )
+                    .await;
+                if let Some(divergence) = report.divergence {
+                    panic!(
+                        "ERROR: Replay diverged at record {}. Recorded: {:?}. Replayed: {:?}.",
+                        divergence.index, divergence.record, divergence.event
+                    );
+                }
+                assert!(
+                    report.balances_match,
+                    "ERROR: Replay balance mismatch. Funds before and after the replay do not match."
+                );
+            } else if let Some(path) = arguments.report {
+                let report = simulation
+                    .report(
+                        &parameters,
+                        &utxo_accumulator_model,
+                        &proving_context,
+                        verifying_context,
+                        &mut rng,
+                    )
+                    .await;
+                File::create(&path)
+                    .map_err(serde_json::Error::io)
+                    .and_then(|file| serde_json::to_writer_pretty(BufWriter::new(file), &report))
+                    .expect("Unable to save the report.");
+                assert!(
+                    report.balances_match,
+                    "ERROR: Simulation balance mismatch. Funds before and after the simulation do not match."
+                );
+            } else if let Some(path) = arguments.record {
+                let seed = arguments.seed.unwrap_or_else(|| rng.gen());
+                let (balances_match, log) = simulation
+                    .record(
+                        &parameters,
+                        &utxo_accumulator_model,
+                        &proving_context,
+                        verifying_context,
+                        seed,
+                    )
+                    .await;
+                File::create(&path)
+                    .map_err(serde_json::Error::io)
+                    .and_then(|file| serde_json::to_writer(BufWriter::new(file), &log))
+                    .expect("Unable to save the action log.");
+                assert!(
+                    balances_match,
+                    "ERROR: Simulation balance mismatch. Funds before and after the simulation do not match. Replay the log at {} with seed {seed} to reproduce it.",
+                    path.display()
+                );
+            } else {
+                simulation
+                    .run(
+                        &parameters,
+                        &utxo_accumulator_model,
+                        &proving_context,
+                        verifying_context,
+                        &mut rng,
+                    )
+                    .await
+            }
         }),
-        Err(err) => Simulation::command()
+        Err(err) => Arguments::command()
             .error(
                 ErrorKind::Io,
                 format_args!("Unable to start `tokio` runtime: {err}"),
@@ -0,0 +1,358 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checksummed Address Encoding
+//!
+//! Addresses are encoded with [Bech32m] using the [`address_prefix`] of their [`Network`] as the
+//! human-readable part. The first data symbol is the address format [`VERSION`], followed by the
+//! bytes of the receiving key. The Bech32m checksum detects any error affecting up to four
+//! characters, and the prefix prevents an address of one network from being used on another.
+//!
+//! [Bech32m]: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+//! [`address_prefix`]: Network::address_prefix
+
+use crate::{config::Address, signer::client::network::Network};
+use alloc::{string::String, vec::Vec};
+use core::iter;
+use manta_util::codec::Encode;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Address Format Version
+pub const VERSION: u8 = 1;
+
+/// Bech32 Character Set
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Bech32m Checksum Constant
+const BECH32M_CONSTANT: u32 = 0x2bc830a3;
+
+/// Separator between the Prefix and the Data
+const SEPARATOR: char = '1';
+
+/// Checksum Length
+const CHECKSUM_LENGTH: usize = 6;
+
+/// Maximum Encoded Length
+const MAX_LENGTH: usize = 90;
+
+/// Address Decoding Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DecodeError {
+    /// Invalid Length
+    ///
+    /// The string is longer than 90 characters, or it is too short to hold a prefix, a version
+    /// and a checksum.
+    InvalidLength,
+
+    /// Mixed Case
+    ///
+    /// The string contains both lowercase and uppercase characters.
+    MixedCase,
+
+    /// Missing Separator
+    MissingSeparator,
+
+    /// Invalid Character
+    InvalidCharacter(char),
+
+    /// Invalid Checksum
+    ///
+    /// The string was mistyped or corrupted.
+    InvalidChecksum,
+
+    /// Unknown Network
+    ///
+    /// The prefix of the string does not belong to any [`Network`].
+    UnknownNetwork,
+
+    /// Wrong Network
+    ///
+    /// The address is well-formed but belongs to a different [`Network`] than the expected one.
+    WrongNetwork {
+        /// Expected Network
+        expected: Network,
+
+        /// Network of the Address
+        found: Network,
+    },
+
+    /// Unsupported Version
+    UnsupportedVersion(u8),
+
+    /// Invalid Padding
+    InvalidPadding,
+
+    /// Invalid Receiving Key
+    InvalidKey,
+}
+
+/// Computes the Bech32 checksum polynomial over `values`.
+#[inline]
+fn polymod<I>(values: I) -> u32
+where
+    I: IntoIterator<Item = u8>,
+{
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ u32::from(value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// Expands `prefix` into the values it contributes to the checksum.
+#[inline]
+fn expand_prefix(prefix: &str) -> impl Iterator<Item = u8> + '_ {
+    prefix
+        .bytes()
+        .map(|byte| byte >> 5)
+        .chain(iter::once(0))
+        .chain(prefix.bytes().map(|byte| byte & 31))
+}
+
+/// Computes the Bech32m checksum of `data` under `prefix`.
+#[inline]
+fn checksum(prefix: &str, data: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let polymod = polymod(
+        expand_prefix(prefix)
+            .chain(data.iter().copied())
+            .chain([0; CHECKSUM_LENGTH]),
+    ) ^ BECH32M_CONSTANT;
+    let mut checksum = [0; CHECKSUM_LENGTH];
+    for (i, symbol) in checksum.iter_mut().enumerate() {
+        *symbol = ((polymod >> (5 * (CHECKSUM_LENGTH - 1 - i))) & 31) as u8;
+    }
+    checksum
+}
+
+/// Regroups the `from`-bit values of `data` into `to`-bit values, padding the last value with
+/// zeroes if `pad` is set. Returns `None` if a value of `data` does not fit in `from` bits, or if
+/// `pad` is not set and the leftover bits are not a valid zero padding.
+#[inline]
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut accumulator = 0u32;
+    let mut bits = 0u32;
+    let max_value = (1u32 << to) - 1;
+    let max_accumulator = (1u32 << (from + to - 1)) - 1;
+    let mut converted = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        let value = u32::from(*value);
+        if value >> from != 0 {
+            return None;
+        }
+        accumulator = ((accumulator << from) | value) & max_accumulator;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            converted.push(((accumulator >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            converted.push(((accumulator << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((accumulator << (to - bits)) & max_value) != 0 {
+        return None;
+    }
+    Some(converted)
+}
+
+/// Encodes the 5-bit values of `data` under `prefix` into a Bech32m string.
+#[inline]
+fn encode_bech32m(prefix: &str, data: &[u8]) -> String {
+    let mut string = String::with_capacity(prefix.len() + 1 + data.len() + CHECKSUM_LENGTH);
+    string.push_str(prefix);
+    string.push(SEPARATOR);
+    for symbol in data.iter().chain(checksum(prefix, data).iter()) {
+        string.push(CHARSET[usize::from(*symbol)] as char);
+    }
+    string
+}
+
+/// Decodes the Bech32m `string` into its lowercase prefix and its 5-bit data values, checking
+/// its checksum.
+#[inline]
+fn decode_bech32m(string: &str) -> Result<(String, Vec<u8>), DecodeError> {
+    if string.len() > MAX_LENGTH {
+        return Err(DecodeError::InvalidLength);
+    }
+    if let Some(character) = string.chars().find(|c| !('!'..='~').contains(c)) {
+        return Err(DecodeError::InvalidCharacter(character));
+    }
+    if string.chars().any(|c| c.is_ascii_lowercase())
+        && string.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return Err(DecodeError::MixedCase);
+    }
+    let string = string.to_ascii_lowercase();
+    let (prefix, data) = string
+        .rsplit_once(SEPARATOR)
+        .ok_or(DecodeError::MissingSeparator)?;
+    if prefix.is_empty() || data.len() < CHECKSUM_LENGTH {
+        return Err(DecodeError::InvalidLength);
+    }
+    let data = data
+        .chars()
+        .map(|character| {
+            CHARSET
+                .iter()
+                .position(|c| char::from(*c) == character)
+                .map(|symbol| symbol as u8)
+                .ok_or(DecodeError::InvalidCharacter(character))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if polymod(expand_prefix(prefix).chain(data.iter().copied())) != BECH32M_CONSTANT {
+        return Err(DecodeError::InvalidChecksum);
+    }
+    Ok((prefix.into(), data[..data.len() - CHECKSUM_LENGTH].to_vec()))
+}
+
+/// Encodes `address` for `network` into a checksummed Bech32m string.
+#[inline]
+pub fn encode(address: &Address, network: Network) -> String {
+    let mut bytes = Vec::new();
+    address
+        .receiving_key
+        .encode(&mut bytes)
+        .expect("Encoding is not allowed to fail.");
+    let mut data = Vec::with_capacity(1 + (bytes.len() * 8).div_ceil(5));
+    data.push(VERSION);
+    data.extend(convert_bits(&bytes, 8, 5, true).expect("Bytes always fit in eight bits."));
+    encode_bech32m(network.address_prefix(), &data)
+}
+
+/// Decodes the checksummed Bech32m `string` into an [`Address`] and the [`Network`] it belongs
+/// to.
+#[inline]
+pub fn decode(string: &str) -> Result<(Network, Address), DecodeError> {
+    let (prefix, data) = decode_bech32m(string)?;
+    let network = Network::from_address_prefix(&prefix).ok_or(DecodeError::UnknownNetwork)?;
+    let (version, data) = data.split_first().ok_or(DecodeError::InvalidLength)?;
+    if *version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(*version));
+    }
+    let bytes = convert_bits(data, 5, 8, false).ok_or(DecodeError::InvalidPadding)?;
+    Ok((
+        network,
+        Address::new(bytes.try_into().map_err(|_| DecodeError::InvalidKey)?),
+    ))
+}
+
+/// Decodes the checksummed Bech32m `string` into an [`Address`], checking that it belongs to
+/// `network`.
+#[inline]
+pub fn decode_for_network(string: &str, network: Network) -> Result<Address, DecodeError> {
+    match decode(string)? {
+        (found, address) if found == network => Ok(address),
+        (found, _) => Err(DecodeError::WrongNetwork {
+            expected: network,
+            found,
+        }),
+    }
+}
+
+/// Test
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::Group;
+    use manta_crypto::rand::{OsRng, Sample};
+
+    /// Checks the Bech32m codec against the test vectors of BIP-350.
+    #[test]
+    fn bech32m_test_vectors() {
+        for valid in [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ] {
+            let (prefix, data) = decode_bech32m(valid).expect("Valid Bech32m string.");
+            assert_eq!(
+                encode_bech32m(&prefix, &data),
+                valid.to_ascii_lowercase(),
+                "Re-encoding should give back the original string."
+            );
+        }
+        for (invalid, error) in [
+            ("A1G7SGD8", DecodeError::InvalidChecksum),
+            ("1xj0phk", DecodeError::InvalidLength),
+            ("qyrz8wqd2c9m", DecodeError::MissingSeparator),
+            ("y1b0jsk6g", DecodeError::InvalidCharacter('b')),
+            ("M1VUXWEZ", DecodeError::InvalidChecksum),
+            ("a1Lqfn3a", DecodeError::MixedCase),
+        ] {
+            assert_eq!(
+                decode_bech32m(invalid),
+                Err(error),
+                "{invalid} should be invalid."
+            );
+        }
+    }
+
+    /// Checks that addresses round-trip on every network, and that addresses from other networks
+    /// or with a mistyped character are rejected.
+    #[test]
+    fn address_encoding() {
+        let mut rng = OsRng;
+        let address = Address::new(Group::gen(&mut rng));
+        for network in [Network::Dolphin, Network::Calamari, Network::Manta] {
+            let encoded = encode(&address, network);
+            assert!(encoded.starts_with(network.address_prefix()));
+            assert_eq!(decode(&encoded), Ok((network, address)));
+            assert_eq!(
+                decode(&encoded.to_ascii_uppercase()),
+                Ok((network, address)),
+                "Uppercase addresses should be accepted."
+            );
+            assert_eq!(decode_for_network(&encoded, network), Ok(address));
+            let other = if network == Network::Manta {
+                Network::Dolphin
+            } else {
+                Network::Manta
+            };
+            assert_eq!(
+                decode_for_network(&encoded, other),
+                Err(DecodeError::WrongNetwork {
+                    expected: other,
+                    found: network
+                })
+            );
+            let mut mistyped = encoded.into_bytes();
+            let index = mistyped.len() / 2;
+            mistyped[index] = if mistyped[index] == b'q' { b'p' } else { b'q' };
+            assert_eq!(
+                decode(&String::from_utf8(mistyped).expect("Encoding is ASCII.")),
+                Err(DecodeError::InvalidChecksum),
+                "A mistyped character should be detected."
+            );
+        }
+    }
+}
@@ -30,6 +30,7 @@ use manta_crypto::arkworks::{
 #[cfg(feature = "bs58")]
 use {alloc::string::String, manta_util::codec::Encode};
 
+pub mod address;
 pub mod poseidon;
 pub mod utxo;
 
@@ -151,6 +152,9 @@ pub type Utxo = transfer::Utxo<Config>;
 /// Note Type
 pub type Note = transfer::Note<Config>;
 
+/// Memo Type
+pub type Memo = transfer::Memo<Config>;
+
 /// Nullifier Type
 pub type Nullifier = transfer::Nullifier<Config>;
 
@@ -215,6 +219,9 @@ pub type IdentityProof = transfer::IdentityProof<Config>;
 pub type TransactionData = transfer::canonical::TransactionData<Config>;
 
 /// Converts an [`Address`] into a base58-encoded string.
+///
+/// The base58 encoding carries no checksum and no network, so it is only kept for compatibility.
+/// Prefer the checksummed [`address::encode`] for new addresses.
 #[cfg(feature = "bs58")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
 #[inline]
@@ -228,6 +235,9 @@ pub fn address_to_base58(address: &Address) -> String {
 }
 
 /// Converts a base58-encoded string into an [`Address`].
+///
+/// See [`address_to_base58`] for the limitations of this encoding and [`address::decode`] for its
+/// checksummed replacement.
 #[cfg(feature = "bs58")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
 #[inline]
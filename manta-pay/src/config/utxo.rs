@@ -168,6 +168,9 @@ pub type LightIncomingNote = protocol::LightIncomingNote<Config>;
 /// Full Incoming Note Type
 pub type FullIncomingNote = protocol::FullIncomingNote<Config>;
 
+/// Encrypted Memo Type
+pub type EncryptedMemo = protocol::EncryptedMemo<Config>;
+
 /// Outgoing Note Type
 pub type OutgoingNote = protocol::OutgoingNote<Config>;
 
@@ -1054,6 +1057,62 @@ pub type IncomingBaseAES<COM = ()> = encryption::convert::key::Converter<
     IncomingAESConverter<COM>,
 >;
 
+/// Memo Size
+pub const MEMO_SIZE: usize = 64;
+
+/// Memo AES Ciphertext Size
+pub const MEMO_AES_CIPHERTEXT_SIZE: usize = MEMO_SIZE + 16;
+
+/// Memo
+pub type Memo = Array<u8, MEMO_SIZE>;
+
+/// Memo AES
+pub type MemoAES = aes::FixedNonceAesGcm<MEMO_SIZE, MEMO_AES_CIPHERTEXT_SIZE>;
+
+/// Memo AES Converter
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MemoAESConverter;
+
+impl encryption::EncryptionKeyType for MemoAESConverter {
+    type EncryptionKey = Group;
+}
+
+impl encryption::convert::key::Encryption for MemoAESConverter {
+    type TargetEncryptionKey = encryption::EncryptionKey<MemoAES>;
+
+    #[inline]
+    fn as_target(source: &Self::EncryptionKey, _: &mut ()) -> Self::TargetEncryptionKey {
+        let key = source.to_vec();
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, key);
+        hasher.finalize().into()
+    }
+}
+
+impl encryption::DecryptionKeyType for MemoAESConverter {
+    type DecryptionKey = Group;
+}
+
+impl encryption::convert::key::Decryption for MemoAESConverter {
+    type TargetDecryptionKey = encryption::DecryptionKey<MemoAES>;
+
+    #[inline]
+    fn as_target(source: &Self::DecryptionKey, _: &mut ()) -> Self::TargetDecryptionKey {
+        let key = source.to_vec();
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, key);
+        hasher.finalize().into()
+    }
+}
+
+/// Memo Base Encryption Scheme
+pub type MemoBaseEncryptionScheme = encryption::convert::key::Converter<MemoAES, MemoAESConverter>;
+
 /// Utxo Accumulator Item Hash Domain Tag
 #[cfg_attr(
     feature = "serde",
@@ -2016,6 +2075,8 @@ impl protocol::BaseConfiguration<Compiler> for Config<Compiler> {
 impl protocol::Configuration for Config {
     type AddressPartitionFunction = AddressPartitionFunction;
     type SchnorrHashFunction = SchnorrHashFunction;
+    type Memo = Memo;
+    type MemoBaseEncryptionScheme = MemoBaseEncryptionScheme;
 }
 
 /// Checkpoint
@@ -2112,8 +2173,8 @@ impl From<Checkpoint> for RawCheckpoint {
 pub mod test {
     use crate::config::{
         utxo::{
-            Config, IncomingBaseAES, IncomingBaseEncryptionScheme, OutgoingBaseAES,
-            AES_CIPHERTEXT_SIZE, OUT_AES_CIPHERTEXT_SIZE,
+            Config, IncomingBaseAES, IncomingBaseEncryptionScheme, Memo, OutgoingBaseAES,
+            AES_CIPHERTEXT_SIZE, MEMO_AES_CIPHERTEXT_SIZE, MEMO_SIZE, OUT_AES_CIPHERTEXT_SIZE,
         },
         ConstraintField, EmbeddedScalar, Group,
     };
@@ -2124,7 +2185,7 @@ pub mod test {
                 self, AddressPartitionFunction, UtxoCommitmentScheme, ViewingKeyDerivationFunction,
                 Visibility,
             },
-            UtxoReconstruct,
+            DeriveMint, NoteMemo, UtxoReconstruct,
         },
     };
     use manta_crypto::{
@@ -2307,4 +2368,46 @@ pub mod test {
         assert_eq!(new_asset_id, asset_id, "Asset ID is not the same.");
         assert_eq!(new_asset_value, asset_value, "Asset value is not the same.");
     }
+
+    /// Checks that a memo attached to a note can only be decrypted by the recipient of the note.
+    #[test]
+    fn check_memo_encryption() {
+        let mut rng = OsRng;
+        let parameters = protocol::Parameters::<Config>::gen(&mut rng);
+        let group_generator = parameters.base.group_generator.generator();
+        let spending_key = EmbeddedScalar::gen(&mut rng);
+        let address = parameters.address_from_spending_key(&spending_key);
+        let decryption_key = parameters
+            .base
+            .viewing_key_derivation_function
+            .viewing_key(&group_generator.scalar_mul(&spending_key, &mut ()), &mut ());
+        let asset = asset::Asset {
+            id: Fp::<ConstraintField>::gen(&mut rng),
+            value: u128::gen(&mut rng),
+        };
+        let (_, _, mut note) = parameters.derive_mint(address, asset, Visibility::Opaque, &mut rng);
+        assert!(
+            parameters.open_memo(&decryption_key, &note).is_none(),
+            "Notes are minted without a memo."
+        );
+        let memo = Memo::from(<[u8; MEMO_SIZE]>::gen(&mut rng));
+        parameters.attach_memo(&address, &memo, &mut note, &mut rng);
+        let encrypted_memo = note.memo.as_ref().expect("The memo was just attached.");
+        assert_eq!(
+            MEMO_AES_CIPHERTEXT_SIZE,
+            encrypted_memo.ciphertext.ciphertext.len(),
+            "Memo ciphertext length doesn't match."
+        );
+        assert_eq!(
+            parameters.open_memo(&decryption_key, &note),
+            Some(memo),
+            "The recipient should be able to decrypt the memo."
+        );
+        assert!(
+            parameters
+                .open_memo(&EmbeddedScalar::gen(&mut rng), &note)
+                .is_none(),
+            "Only the recipient should be able to decrypt the memo."
+        );
+    }
 }
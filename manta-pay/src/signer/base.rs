@@ -40,7 +40,7 @@ use manta_crypto::{
     accumulator::ItemHashFunction,
     arkworks::{
         constraint::fp::Fp,
-        ed_on_bn254::FrParameters,
+        ed_on_bls12_381::FrParameters,
         ff::{Fp256, PrimeField, Zero},
     },
     merkle_tree::{self, forest::Configuration},
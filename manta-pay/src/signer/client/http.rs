@@ -20,13 +20,18 @@ use crate::{
     config::{utxo::Address, Config},
     signer::{
         client::network::{Message, Network},
-        AssetMetadata, Checkpoint, GetRequest, IdentityRequest, IdentityResponse, SignError,
-        SignRequest, SignResponse, SignWithTransactionDataResult, SyncError, SyncRequest,
-        SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        AssetMetadata, Checkpoint, ConsolidateRequest, ConsolidateResult, GetRequest,
+        IdentityRequest, IdentityResponse, PlanRequest, PlanResult, RewindRequest, RewindResult,
+        SignError, SignRequest, SignResponse, SignWithTransactionDataResult, SyncError,
+        SyncRequest, SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        TransactionHistoryRequest, TransactionHistoryResponse,
     },
 };
 use alloc::boxed::Box;
-use manta_accounting::wallet::{self, signer};
+use manta_accounting::{
+    key::AccountIndex,
+    wallet::{self, signer},
+};
 use manta_util::{
     future::LocalBoxFutureResult,
     http::reqwest::{self, IntoUrl, KnownUrlClient},
@@ -108,6 +113,18 @@ impl signer::Connection<Config> for Client {
         })
     }
 
+    #[inline]
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
+        Box::pin(async move {
+            self.base
+                .post("account_address", &self.wrap_request(account))
+                .await
+        })
+    }
+
     #[inline]
     fn transaction_data(
         &mut self,
@@ -143,4 +160,41 @@ impl signer::Connection<Config> for Client {
                 .await
         })
     }
+
+    #[inline]
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse, Self::Error> {
+        Box::pin(async move {
+            self.base
+                .post("transaction_history", &self.wrap_request(request))
+                .await
+        })
+    }
+
+    #[inline]
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest,
+    ) -> LocalBoxFutureResult<ConsolidateResult, Self::Error> {
+        Box::pin(async move {
+            self.base
+                .post("consolidate", &self.wrap_request(request))
+                .await
+        })
+    }
+
+    #[inline]
+    fn plan(&mut self, request: PlanRequest) -> LocalBoxFutureResult<PlanResult, Self::Error> {
+        Box::pin(async move { self.base.post("plan", &self.wrap_request(request)).await })
+    }
+
+    #[inline]
+    fn rewind(
+        &mut self,
+        request: RewindRequest,
+    ) -> LocalBoxFutureResult<RewindResult, Self::Error> {
+        Box::pin(async move { self.base.post("rewind", &self.wrap_request(request)).await })
+    }
 }
@@ -42,6 +42,27 @@ pub enum Network {
     Manta,
 }
 
+impl Network {
+    /// Returns the human-readable prefix of the [`address`](crate::config::address) encoding on
+    /// `self`.
+    #[inline]
+    pub const fn address_prefix(&self) -> &'static str {
+        match self {
+            Network::Dolphin => "dolphin",
+            Network::Calamari => "calamari",
+            Network::Manta => "manta",
+        }
+    }
+
+    /// Returns the [`Network`] whose [`address_prefix`](Self::address_prefix) is `prefix`.
+    #[inline]
+    pub fn from_address_prefix(prefix: &str) -> Option<Self> {
+        [Network::Dolphin, Network::Calamari, Network::Manta]
+            .into_iter()
+            .find(|network| network.address_prefix() == prefix)
+    }
+}
+
 impl Display for Network {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
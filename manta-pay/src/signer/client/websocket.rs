@@ -21,15 +21,20 @@
 use crate::{
     config::{utxo::Address, Config},
     signer::{
-        AssetMetadata, Checkpoint, GetRequest, IdentityRequest, IdentityResponse, SignError,
-        SignRequest, SignResponse, SignWithTransactionDataResult, SyncError, SyncRequest,
-        SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        AssetMetadata, Checkpoint, ConsolidateRequest, ConsolidateResult, GetRequest,
+        IdentityRequest, IdentityResponse, PlanRequest, PlanResult, RewindRequest, RewindResult,
+        SignError, SignRequest, SignResponse, SignWithTransactionDataResult, SyncError,
+        SyncRequest, SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        TransactionHistoryRequest, TransactionHistoryResponse,
     },
 };
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::String};
 use core::marker::Unpin;
 use futures::{SinkExt, StreamExt};
-use manta_accounting::wallet::{self, signer};
+use manta_accounting::{
+    key::AccountIndex,
+    wallet::{self, signer},
+};
 use manta_util::{
     from_variant,
     future::LocalBoxFutureResult,
@@ -61,6 +66,12 @@ pub enum Error {
     /// Serialization Error
     SerializationError(serde_json::Error),
 
+    /// Server Error
+    ///
+    /// The server failed to execute the request and answered with an [`ErrorResponse`] carrying
+    /// this message.
+    ServerError(String),
+
     /// WebSocket Error
     WebSocket(WebSocketError),
 }
@@ -87,6 +98,21 @@ pub struct Request<R> {
     pub request: R,
 }
 
+/// Error Response
+///
+/// This is the response sent by the server in place of the result of a request which it failed to
+/// parse or execute.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ErrorResponse {
+    /// Error Message
+    pub error: String,
+}
+
 /// Wallet Associated to [`Client`]
 pub type Wallet<L> = wallet::Wallet<Config, L, Client>;
 
@@ -119,7 +145,13 @@ impl Client {
             })?))
             .await?;
         match self.0.next().await {
-            Some(Ok(Message::Text(message))) => Ok(serde_json::from_str(&message)?),
+            Some(Ok(Message::Text(message))) => match serde_json::from_str(&message) {
+                Ok(response) => Ok(response),
+                Err(err) => match serde_json::from_str::<ErrorResponse>(&message) {
+                    Ok(response) => Err(Error::ServerError(response.error)),
+                    _ => Err(Error::SerializationError(err)),
+                },
+            },
             Some(Ok(_)) => Err(Error::InvalidMessageFormat),
             Some(Err(err)) => Err(Error::WebSocket(err)),
             _ => Err(Error::EndOfStream),
@@ -153,6 +185,14 @@ impl signer::Connection<Config> for Client {
         Box::pin(async move { self.send("address", GetRequest::Get).await })
     }
 
+    #[inline]
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
+        Box::pin(async move { self.send("account_address", account).await })
+    }
+
     #[inline]
     fn transaction_data(
         &mut self,
@@ -176,4 +216,33 @@ impl signer::Connection<Config> for Client {
     ) -> LocalBoxFutureResult<SignWithTransactionDataResult, Self::Error> {
         Box::pin(async move { self.send("sign_with_transaction_data", request).await })
     }
+
+    #[inline]
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse, Self::Error> {
+        Box::pin(async move { self.send("transaction_history", request).await })
+    }
+
+    #[inline]
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest,
+    ) -> LocalBoxFutureResult<ConsolidateResult, Self::Error> {
+        Box::pin(async move { self.send("consolidate", request).await })
+    }
+
+    #[inline]
+    fn plan(&mut self, request: PlanRequest) -> LocalBoxFutureResult<PlanResult, Self::Error> {
+        Box::pin(async move { self.send("plan", request).await })
+    }
+
+    #[inline]
+    fn rewind(
+        &mut self,
+        request: RewindRequest,
+    ) -> LocalBoxFutureResult<RewindResult, Self::Error> {
+        Box::pin(async move { self.send("rewind", request).await })
+    }
 }
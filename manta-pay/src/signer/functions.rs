@@ -18,20 +18,30 @@
 
 use crate::{
     config::{
-        Address, AuthorizationContext, Config, EmbeddedScalar, FullParameters, IdentifiedAsset,
-        IdentityProof, MultiProvingContext, Parameters, Transaction, TransactionData, TransferPost,
-        UtxoAccumulatorModel,
+        Address, Asset, AssetId, AuthorizationContext, Config, EmbeddedScalar, FullParameters,
+        IdentifiedAsset, IdentityProof, Memo, MultiProvingContext, Parameters, Transaction,
+        TransactionData, TransferPost, UtxoAccumulatorModel,
     },
     key::{KeySecret, Mnemonic},
     signer::{
         base::{Signer, SignerParameters, UtxoAccumulator},
-        AccountTable, AssetMap, Checkpoint, SignResult, SignerRng, StorageState,
-        StorageStateOption, SyncRequest, SyncResult,
+        AccountAssetMap, AccountTable, AuthorizationContextMap, Checkpoint, ConsolidateResult,
+        PlanResult, SignError, SignResult, SignerRng, StorageState, StorageStateOption,
+        SyncRequest, SyncResult, TransactionHistory,
     },
 };
-use manta_accounting::{key::DeriveAddress, wallet::signer::functions};
+use alloc::collections::BTreeSet;
+use manta_accounting::{
+    asset::CoinSelection,
+    key::{AccountIndex, DeriveAddress},
+    transfer::utxo::protocol::Visibility,
+    wallet::signer::functions,
+};
 use manta_crypto::{accumulator::Accumulator, rand::FromEntropy};
 
+#[cfg(feature = "fs")]
+use crate::signer::{FileBackend, FileBackendError, PersistentSigner};
+
 /// Builds a new [`Signer`] from `parameters` and `proving_context`,
 /// loading its state from `storage_state`, if possible.
 #[inline]
@@ -51,6 +61,44 @@ pub fn new_signer(
     signer
 }
 
+/// Builds a new view-only [`Signer`] from `parameters`, `proving_context` and the
+/// `authorization_context` of the account to watch, loading its state from `storage_state`,
+/// if possible.
+///
+/// # Implementation Note
+///
+/// The returned signer can synchronize with the ledger and recover [`TransactionData`], but it
+/// fails to sign transactions or generate identity proofs with
+/// [`NoSpendingAuthority`](manta_accounting::wallet::signer::SignError::NoSpendingAuthority).
+/// See [`authorization_context_from_mnemonic`] for deriving the `authorization_context`.
+#[inline]
+pub fn new_view_only_signer(
+    parameters: FullParameters,
+    proving_context: MultiProvingContext,
+    authorization_context: AuthorizationContext,
+    storage_state: &StorageStateOption,
+) -> Signer {
+    let mut signer = new_signer(parameters, proving_context, storage_state);
+    signer.load_authorization_context(Default::default(), authorization_context);
+    signer
+}
+
+/// Builds a new [`PersistentSigner`] from `signer` which stores its state at `path` encrypted
+/// with `password`, restoring the last snapshot saved there if there is one.
+#[cfg(feature = "fs")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "fs")))]
+#[inline]
+pub fn new_persistent_signer<P>(
+    signer: Signer,
+    path: P,
+    password: &[u8],
+) -> Result<PersistentSigner, FileBackendError>
+where
+    P: AsRef<std::path::Path>,
+{
+    PersistentSigner::new(signer, FileBackend::new(path, password))
+}
+
 /// Builds a new [`StorageStateOption`] from `signer`.
 #[inline]
 pub fn set_storage(signer: &Signer) -> StorageStateOption {
@@ -136,13 +184,15 @@ pub fn address_from_mnemonic(mnemonic: Mnemonic, parameters: &Parameters) -> Add
         .address(parameters)
 }
 
-/// Updates `assets`, `checkpoint` and `utxo_accumulator`, returning the new asset distribution.
-#[allow(clippy::result_large_err)]
+/// Updates `assets`, `history`, `checkpoint` and `utxo_accumulator`, returning the new asset distribution
+/// of the account requested in `request`.
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
 #[inline]
 pub fn sync(
     parameters: &SignerParameters,
-    authorization_context: &mut AuthorizationContext,
-    assets: &mut AssetMap,
+    authorization_contexts: &mut AuthorizationContextMap,
+    assets: &mut AccountAssetMap,
+    history: &mut TransactionHistory,
     checkpoint: &mut Checkpoint,
     utxo_accumulator: &mut UtxoAccumulator,
     request: SyncRequest,
@@ -150,8 +200,9 @@ pub fn sync(
 ) -> SyncResult {
     functions::sync(
         parameters,
-        authorization_context,
+        authorization_contexts,
         assets,
+        history,
         checkpoint,
         utxo_accumulator,
         request,
@@ -159,30 +210,99 @@ pub fn sync(
     )
 }
 
-/// Signs the `transaction`, generating transfer posts.
+/// Signs the `transaction` spending from `account` with the assets selected by `coin_selection`,
+/// treating the assets in `non_fungible` as non-fungible tokens, and minting the UTXOs it pays out
+/// with `visibility` and `memo`, generating transfer posts.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sign(
     parameters: &SignerParameters,
     accounts: &AccountTable,
-    assets: &AssetMap,
+    account: AccountIndex,
+    assets: &AccountAssetMap,
     utxo_accumulator: &mut UtxoAccumulator,
     transaction: Transaction,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<AssetId>,
+    visibility: Visibility,
+    memo: Option<Memo>,
     rng: &mut SignerRng,
 ) -> SignResult {
     functions::sign(
         parameters,
         accounts,
+        account,
+        assets,
+        utxo_accumulator,
+        transaction,
+        coin_selection,
+        non_fungible,
+        visibility,
+        memo,
+        rng,
+    )
+}
+
+/// Signs self-transfers merging the UTXOs of `asset_id` owned by `account`, using at most
+/// `max_posts` posts.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn consolidate(
+    parameters: &SignerParameters,
+    accounts: &AccountTable,
+    account: AccountIndex,
+    assets: &AccountAssetMap,
+    utxo_accumulator: &mut UtxoAccumulator,
+    asset_id: AssetId,
+    max_posts: usize,
+    rng: &mut SignerRng,
+) -> ConsolidateResult {
+    functions::consolidate(
+        parameters,
+        accounts,
+        account,
         assets,
         utxo_accumulator,
+        asset_id,
+        max_posts,
+        rng,
+    )
+}
+
+/// Plans the `transaction` spending from `account` with the assets selected by `coin_selection`,
+/// treating the assets in `non_fungible` as non-fungible tokens, without building any proofs.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn plan(
+    parameters: &SignerParameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+    account: AccountIndex,
+    assets: &AccountAssetMap,
+    transaction: Transaction,
+    coin_selection: CoinSelection,
+    non_fungible: &BTreeSet<AssetId>,
+    rng: &mut SignerRng,
+) -> PlanResult {
+    functions::plan(
+        parameters,
+        utxo_accumulator_model,
+        account,
+        assets,
         transaction,
+        coin_selection,
+        non_fungible,
         rng,
     )
 }
 
-/// Returns the [`Address`] corresponding to `accounts`.
+/// Returns the [`Address`] of `account` in `accounts`, if it exists.
 #[inline]
-pub fn address(parameters: &SignerParameters, accounts: &AccountTable) -> Address {
-    functions::address(parameters, accounts)
+pub fn address(
+    parameters: &SignerParameters,
+    accounts: &AccountTable,
+    account: AccountIndex,
+) -> Option<Address> {
+    functions::address(parameters, accounts, account)
 }
 
 /// Returns the associated [`TransactionData`] of `post`. Returns `None` if `post` has an invalid shape,
@@ -196,6 +316,29 @@ pub fn transaction_data(
     functions::transaction_data(parameters, accounts, post)
 }
 
+/// Returns the associated [`TransactionData`] of `post` for a view-only signer. Returns `None`
+/// if `post` has an invalid shape, or if none of the `authorization_contexts` own the underlying
+/// assets in `post`.
+#[inline]
+pub fn view_only_transaction_data(
+    parameters: &SignerParameters,
+    authorization_contexts: &mut AuthorizationContextMap,
+    post: TransferPost,
+) -> Option<TransactionData> {
+    functions::authorization_context_transaction_data(parameters, authorization_contexts, post)
+}
+
+/// Returns the memos attached to the UTXOs of `post` owned by `accounts`, along with the asset of
+/// each UTXO.
+#[inline]
+pub fn transaction_memos(
+    parameters: &SignerParameters,
+    accounts: &AccountTable,
+    post: TransferPost,
+) -> Vec<(Asset, Memo)> {
+    functions::transaction_memos(parameters, accounts, post)
+}
+
 /// Generates an [`IdentityProof`] for `identified_asset` by signing a
 /// virtual [`ToPublic`](manta_accounting::transfer::canonical::ToPublic) transaction.
 #[inline]
@@ -205,7 +348,7 @@ pub fn identity_proof(
     utxo_accumulator_model: &UtxoAccumulatorModel,
     identified_asset: IdentifiedAsset,
     rng: &mut SignerRng,
-) -> Option<IdentityProof> {
+) -> Result<IdentityProof, SignError> {
     functions::identity_proof(
         parameters,
         accounts,
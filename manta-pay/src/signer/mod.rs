@@ -37,6 +37,10 @@ pub mod base;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "wallet")))]
 pub mod functions;
 
+#[cfg(feature = "server")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "server")))]
+pub mod server;
+
 /// Synchronization Request
 pub type SyncRequest = signer::SyncRequest<Config, Checkpoint>;
 
@@ -61,6 +65,39 @@ pub type SignError = signer::SignError<Config>;
 /// Signing Result
 pub type SignResult = signer::SignResult<Config>;
 
+/// Consolidation Request
+pub type ConsolidateRequest = signer::ConsolidateRequest<Config>;
+
+/// Consolidation Response
+pub type ConsolidateResponse = signer::ConsolidateResponse<Config>;
+
+/// Consolidation Result
+pub type ConsolidateResult = signer::ConsolidateResult<Config>;
+
+/// Plan Request
+pub type PlanRequest = signer::PlanRequest<Config>;
+
+/// Transaction Plan
+pub type TransactionPlan = signer::TransactionPlan<Config>;
+
+/// Plan Result
+pub type PlanResult = signer::PlanResult<Config>;
+
+/// Rewind Request
+pub type RewindRequest = signer::RewindRequest<Checkpoint>;
+
+/// Rewind Error
+pub type RewindError = signer::RewindError;
+
+/// Rewind Result
+pub type RewindResult = signer::RewindResult<Config, Checkpoint>;
+
+/// Transaction History Request
+pub type TransactionHistoryRequest = signer::TransactionHistoryRequest;
+
+/// Transaction History Response
+pub type TransactionHistoryResponse = signer::TransactionHistoryResponse<Config, Checkpoint>;
+
 /// Transaction Data Request
 pub type TransactionDataRequest = signer::TransactionDataRequest<Config>;
 
@@ -79,6 +116,15 @@ pub type AccountTable = signer::AccountTable<Config>;
 /// AssetMap Type
 pub type AssetMap = <Config as signer::Configuration>::AssetMap;
 
+/// Account AssetMap Type
+pub type AccountAssetMap = signer::AccountAssetMap<Config>;
+
+/// Authorization Context Map Type
+pub type AuthorizationContextMap = signer::AuthorizationContextMap<Config>;
+
+/// Transaction History Type
+pub type TransactionHistory = signer::history::TransactionHistory<Config>;
+
 /// Rng Type
 pub type SignerRng = <Config as signer::Configuration>::Rng;
 
@@ -94,6 +140,21 @@ pub type StorageState = signer::StorageState<Config>;
 /// Storage State Option
 pub type StorageStateOption = Option<StorageState>;
 
+/// Encrypted File Storage Backend
+#[cfg(feature = "fs")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "fs")))]
+pub type FileBackend = signer::storage::FileBackend<manta_accounting::fs::cocoon::File>;
+
+/// Encrypted File Storage Backend Error
+#[cfg(feature = "fs")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "fs")))]
+pub type FileBackendError = signer::storage::FileBackendError<manta_accounting::fs::cocoon::File>;
+
+/// Persistent Signer
+#[cfg(all(feature = "fs", feature = "wallet"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "fs", feature = "wallet"))))]
+pub type PersistentSigner = signer::storage::PersistentSigner<Config, FileBackend>;
+
 /// Receiving Key Request
 #[cfg_attr(
     feature = "serde",
@@ -0,0 +1,442 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reference Signer Server
+//!
+//! This module implements the server side of the signer protocol spoken by the clients in
+//! [`client`](crate::signer::client). The HTTP [`Server`] hosts one [`Signer`] per [`Network`] and
+//! expects every request to be wrapped in a network [`Message`], while the WebSocket server
+//! exposes the [`Signer`] of a single [`Network`] since its client does not select one.
+
+use crate::{
+    config::Address,
+    signer::{
+        base::Signer,
+        client::{
+            network::{Message, Network, NetworkError, NetworkSpecific},
+            websocket::ErrorResponse,
+        },
+        ConsolidateRequest, ConsolidateResult, GetRequest, IdentityRequest, IdentityResponse,
+        PlanRequest, PlanResult, RewindRequest, RewindResult, SignRequest, SignResult,
+        SignWithTransactionDataResult, SyncRequest, SyncResult, TransactionDataRequest,
+        TransactionDataResponse, TransactionHistoryRequest, TransactionHistoryResponse,
+    },
+};
+use alloc::{format, string::String, sync::Arc};
+use futures::{SinkExt, StreamExt};
+use manta_accounting::key::AccountIndex;
+use manta_util::{
+    from_variant,
+    http::tide::{self, listener::ToListener, register_post, StatusCode},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+};
+use serde_json::Value;
+use std::panic;
+use tokio::{
+    io,
+    net::{TcpListener, TcpStream},
+    runtime::Handle,
+    sync::Mutex,
+};
+use tokio_tungstenite::{
+    accept_async,
+    tungstenite::{self, Message as WebSocketMessage},
+};
+
+/// Web Socket Error
+pub type WebSocketError = tungstenite::error::Error;
+
+/// WebSocket Connection Error
+#[derive(Debug)]
+pub enum Error {
+    /// Unknown Command
+    ///
+    /// The command of the incoming request is not part of the signer protocol.
+    UnknownCommand(String),
+
+    /// Network Error
+    Network(NetworkError),
+
+    /// Serialization Error
+    SerializationError(serde_json::Error),
+
+    /// WebSocket Error
+    WebSocket(WebSocketError),
+}
+
+from_variant!(Error, Network, NetworkError);
+from_variant!(Error, SerializationError, serde_json::Error);
+from_variant!(Error, WebSocket, WebSocketError);
+
+/// WebSocket Request
+///
+/// This is the server side of [`Request`](crate::signer::client::websocket::Request) with the
+/// request body left unparsed until the command is known.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+struct Request {
+    /// Request Command
+    command: String,
+
+    /// Request Body
+    request: Value,
+}
+
+/// Signer Server State
+#[derive(Clone)]
+pub struct State {
+    /// Signers
+    signers: NetworkSpecific<Option<Arc<Mutex<Signer>>>>,
+
+    /// Runtime Handle
+    ///
+    /// The signer commands are executed on the blocking threads of this runtime, since proving
+    /// can keep a command busy for several seconds.
+    runtime: Handle,
+}
+
+impl State {
+    /// Builds a new server [`State`] from `signers`, executing the signer commands on the blocking
+    /// threads of the current `tokio` runtime.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it is not called from the context of a `tokio` runtime.
+    #[inline]
+    pub fn new(signers: NetworkSpecific<Option<Signer>>) -> Self {
+        let share = |signer: Option<Signer>| signer.map(|signer| Arc::new(Mutex::new(signer)));
+        Self {
+            signers: NetworkSpecific {
+                dolphin: share(signers.dolphin),
+                calamari: share(signers.calamari),
+                manta: share(signers.manta),
+            },
+            runtime: Handle::current(),
+        }
+    }
+
+    /// Executes `f` on the signer for `network` with `request`, holding the lock on the signer
+    /// until `f` returns on a blocking thread of the runtime.
+    #[inline]
+    async fn execute<T, R, F>(&self, network: Network, request: T, f: F) -> Result<R, NetworkError>
+    where
+        T: Send + 'static,
+        R: Send + 'static,
+        F: FnOnce(&mut Signer, T) -> R + Send + 'static,
+    {
+        let mut signer = self.signers[network]
+            .clone()
+            .ok_or(NetworkError::NonexistentWallet(network))?
+            .lock_owned()
+            .await;
+        match self
+            .runtime
+            .spawn_blocking(move || f(&mut signer, request))
+            .await
+        {
+            Ok(response) => Ok(response),
+            Err(err) => panic::resume_unwind(err.into_panic()),
+        }
+    }
+
+    /// Executes the signer protocol `command` on the signer for `network` with `request`.
+    #[inline]
+    async fn dispatch(
+        &self,
+        network: Network,
+        command: &str,
+        request: Value,
+    ) -> Result<Value, Error> {
+        Ok(match command {
+            "sync" => to_json(self.execute(network, parse(request)?, Self::sync).await?),
+            "sign" => to_json(self.execute(network, parse(request)?, Self::sign).await?),
+            "address" => to_json(
+                self.execute(network, parse(request)?, Self::address)
+                    .await?,
+            ),
+            "account_address" => to_json(
+                self.execute(network, parse(request)?, Self::account_address)
+                    .await?,
+            ),
+            "transaction_data" => to_json(
+                self.execute(network, parse(request)?, Self::transaction_data)
+                    .await?,
+            ),
+            "identity" => to_json(
+                self.execute(network, parse(request)?, Self::identity_proof)
+                    .await?,
+            ),
+            "sign_with_transaction_data" => to_json(
+                self.execute(network, parse(request)?, Self::sign_with_transaction_data)
+                    .await?,
+            ),
+            "transaction_history" => to_json(
+                self.execute(network, parse(request)?, Self::transaction_history)
+                    .await?,
+            ),
+            "consolidate" => to_json(
+                self.execute(network, parse(request)?, Self::consolidate)
+                    .await?,
+            ),
+            "plan" => to_json(self.execute(network, parse(request)?, Self::plan).await?),
+            "rewind" => to_json(self.execute(network, parse(request)?, Self::rewind).await?),
+            _ => return Err(Error::UnknownCommand(command.into())),
+        }?)
+    }
+
+    /// Parses the WebSocket `message` and executes its command on the signer for `network`.
+    #[inline]
+    async fn respond(&self, network: Network, message: &str) -> Result<Value, Error> {
+        let request = serde_json::from_str::<Request>(message)?;
+        self.dispatch(network, &request.command, request.request)
+            .await
+    }
+
+    /// Serves the signer protocol for `network` on the WebSocket connection over `stream`.
+    ///
+    /// Requests which fail to be parsed or executed are answered with an [`ErrorResponse`], and
+    /// the connection is kept open. Only the errors of the connection itself are returned.
+    #[inline]
+    async fn serve_connection(self, network: Network, stream: TcpStream) -> Result<(), Error> {
+        let mut stream = accept_async(stream).await?;
+        while let Some(message) = stream.next().await {
+            match message? {
+                WebSocketMessage::Text(message) => {
+                    let response = match self.respond(network, &message).await {
+                        Ok(response) => response,
+                        Err(err) => to_json(into_error_response(err))?,
+                    };
+                    stream
+                        .send(WebSocketMessage::Text(serde_json::to_string(&response)?))
+                        .await?;
+                }
+                WebSocketMessage::Close(_) => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the `sync` command on `signer`.
+    #[allow(clippy::result_large_err)]
+    #[inline]
+    fn sync(signer: &mut Signer, request: SyncRequest) -> SyncResult {
+        signer.sync(request)
+    }
+
+    /// Runs the `sign` command on `signer`.
+    #[inline]
+    fn sign(signer: &mut Signer, request: SignRequest) -> SignResult {
+        signer.sign(request)
+    }
+
+    /// Runs the `address` command on `signer`.
+    #[inline]
+    fn address(signer: &mut Signer, request: GetRequest) -> Option<Address> {
+        let _ = request;
+        signer.address(Default::default())
+    }
+
+    /// Runs the `account_address` command on `signer`.
+    #[inline]
+    fn account_address(signer: &mut Signer, account: AccountIndex) -> Option<Address> {
+        signer.address(account)
+    }
+
+    /// Runs the `transaction_data` command on `signer`.
+    #[inline]
+    fn transaction_data(
+        signer: &mut Signer,
+        request: TransactionDataRequest,
+    ) -> TransactionDataResponse {
+        signer.batched_transaction_data(request.0)
+    }
+
+    /// Runs the `identity` command on `signer`.
+    #[inline]
+    fn identity_proof(signer: &mut Signer, request: IdentityRequest) -> IdentityResponse {
+        signer.batched_identity_proof(request.0)
+    }
+
+    /// Runs the `sign_with_transaction_data` command on `signer`.
+    #[inline]
+    fn sign_with_transaction_data(
+        signer: &mut Signer,
+        request: SignRequest,
+    ) -> SignWithTransactionDataResult {
+        signer.sign_with_transaction_data(request)
+    }
+
+    /// Runs the `transaction_history` command on `signer`.
+    #[inline]
+    fn transaction_history(
+        signer: &mut Signer,
+        request: TransactionHistoryRequest,
+    ) -> TransactionHistoryResponse {
+        signer.transaction_history(request)
+    }
+
+    /// Runs the `consolidate` command on `signer`.
+    #[inline]
+    fn consolidate(signer: &mut Signer, request: ConsolidateRequest) -> ConsolidateResult {
+        signer.consolidate(request.account, request.asset_id, request.max_posts)
+    }
+
+    /// Runs the `plan` command on `signer`.
+    #[inline]
+    fn plan(signer: &mut Signer, request: PlanRequest) -> PlanResult {
+        signer.plan(
+            request.account,
+            request.transaction,
+            request.coin_selection,
+            &request.non_fungible,
+        )
+    }
+
+    /// Runs the `rewind` command on `signer`.
+    #[inline]
+    fn rewind(signer: &mut Signer, request: RewindRequest) -> RewindResult {
+        signer.rewind(request)
+    }
+}
+
+/// Parses a request of type `T` from `request`.
+#[inline]
+fn parse<T>(request: Value) -> Result<T, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(request)
+}
+
+/// Converts `response` into a JSON value.
+#[inline]
+fn to_json<T>(response: T) -> Result<Value, serde_json::Error>
+where
+    T: Serialize,
+{
+    serde_json::to_value(response)
+}
+
+/// Converts `err` into the [`ErrorResponse`] sent back over a WebSocket connection.
+#[inline]
+fn into_error_response(err: Error) -> ErrorResponse {
+    ErrorResponse {
+        error: match err {
+            Error::UnknownCommand(command) => format!("Unknown signer command: {command}."),
+            Error::Network(NetworkError::NonexistentWallet(network)) => {
+                format!("No signer is hosted for the {network} network.")
+            }
+            Error::SerializationError(err) => format!("Invalid request: {err}."),
+            Error::WebSocket(err) => format!("WebSocket error: {err}."),
+        },
+    }
+}
+
+/// Converts `err` into an HTTP error.
+#[inline]
+fn into_http_error(err: NetworkError) -> tide::Error {
+    match err {
+        NetworkError::NonexistentWallet(network) => tide::Error::from_str(
+            StatusCode::NotFound,
+            format!("No signer is hosted for the {network} network."),
+        ),
+    }
+}
+
+/// Registers the HTTP route for the signer protocol `command` at `path` running `f`.
+#[inline]
+fn register<T, R, F>(api: &mut tide::Server<State>, path: &'static str, f: F)
+where
+    T: DeserializeOwned + Send + 'static,
+    R: Serialize + Send + 'static,
+    F: Copy + Send + Sync + 'static + Fn(&mut Signer, T) -> R,
+{
+    register_post(
+        api,
+        path,
+        move |state: State, request: Message<T>| async move {
+            state
+                .execute(request.network, request.message, f)
+                .await
+                .map_err(into_http_error)
+        },
+    );
+}
+
+/// Signer HTTP Server
+#[derive(Clone)]
+pub struct Server(tide::Server<State>);
+
+impl Server {
+    /// Builds a new [`Server`] hosting `signers`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it is not called from the context of a `tokio` runtime. See
+    /// [`State::new`] for more.
+    #[inline]
+    pub fn new(signers: NetworkSpecific<Option<Signer>>) -> Self {
+        let mut api = tide::Server::with_state(State::new(signers));
+        register(&mut api, "/sync", State::sync);
+        register(&mut api, "/sign", State::sign);
+        register(&mut api, "/address", State::address);
+        register(&mut api, "/account_address", State::account_address);
+        register(&mut api, "/transaction_data", State::transaction_data);
+        register(&mut api, "/identity", State::identity_proof);
+        register(
+            &mut api,
+            "/sign_with_transaction_data",
+            State::sign_with_transaction_data,
+        );
+        register(&mut api, "/transaction_history", State::transaction_history);
+        register(&mut api, "/consolidate", State::consolidate);
+        register(&mut api, "/plan", State::plan);
+        register(&mut api, "/rewind", State::rewind);
+        Self(api)
+    }
+
+    /// Returns the shared signer state of `self`.
+    #[inline]
+    pub fn state(&self) -> &State {
+        self.0.state()
+    }
+
+    /// Serves `self` over HTTP at the given `listener`.
+    #[inline]
+    pub async fn serve<L>(self, listener: L) -> Result<(), io::Error>
+    where
+        L: ToListener<State>,
+    {
+        self.0.listen(listener).await
+    }
+
+    /// Serves the signer for `network` over WebSocket connections accepted from `listener`.
+    ///
+    /// Every connection is served on its own task. Requests which fail to be parsed or executed
+    /// are answered with an [`ErrorResponse`] without closing the connection.
+    #[inline]
+    pub async fn serve_websocket(
+        self,
+        network: Network,
+        listener: TcpListener,
+    ) -> Result<(), io::Error> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(self.state().clone().serve_connection(network, stream));
+        }
+    }
+}
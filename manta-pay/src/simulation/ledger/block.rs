@@ -0,0 +1,226 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block Production
+//!
+//! Posts submitted with [`Ledger::submit`](super::Ledger::submit) wait in the [`Mempool`] until
+//! the next call to [`Ledger::produce_block`](super::Ledger::produce_block), which applies them
+//! in submission order and records the [`InclusionStatus`] of every post. The
+//! [`produce_blocks`] task calls it at a fixed interval. Statuses are only kept for
+//! [`STATUS_RETENTION_DEPTH`] blocks after the block which decided them.
+
+use crate::{
+    config::TransferPost,
+    simulation::ledger::{AccountId, SharedLedger},
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::time::Duration;
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Block Number
+pub type BlockNumber = u64;
+
+/// Status Retention Depth
+///
+/// Number of blocks for which the [`Mempool`] keeps the [`InclusionStatus`] of a post after the
+/// block which included, rejected or dropped it. Older statuses are evicted when the next block is
+/// produced.
+pub const STATUS_RETENTION_DEPTH: BlockNumber = 256;
+
+/// Post Identifier
+///
+/// Every post submitted to the [`Mempool`] gets a new identifier which can be used to query its
+/// [`InclusionStatus`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields, transparent)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PostId(pub u64);
+
+/// Inclusion Status
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InclusionStatus {
+    /// Pending
+    ///
+    /// The post is waiting in the [`Mempool`] for the next block.
+    Pending,
+
+    /// Included
+    ///
+    /// The post was applied to the ledger in the given block.
+    Included(BlockNumber),
+
+    /// Conflict
+    ///
+    /// The post spends a nullifier which was already spent by an earlier post in the same block.
+    Conflict(BlockNumber),
+
+    /// Rejected
+    ///
+    /// The post was not valid against the state of the ledger at its turn in the given block.
+    Rejected(BlockNumber),
+
+    /// Dropped
+    ///
+    /// The post was included in the given block, which was later dropped by a
+    /// [`reorganize`](super::Ledger::reorganize) call.
+    Dropped(BlockNumber),
+}
+
+impl InclusionStatus {
+    /// Returns `true` if `self` is [`Pending`](Self::Pending).
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending)
+    }
+
+    /// Returns `true` if `self` is [`Included`](Self::Included).
+    #[inline]
+    pub fn is_included(&self) -> bool {
+        matches!(self, Self::Included(_))
+    }
+
+    /// Returns the number of the block which decided `self`, or `None` if `self` is
+    /// [`Pending`](Self::Pending).
+    #[inline]
+    pub fn block_number(&self) -> Option<BlockNumber> {
+        match self {
+            Self::Pending => None,
+            Self::Included(number)
+            | Self::Conflict(number)
+            | Self::Rejected(number)
+            | Self::Dropped(number) => Some(*number),
+        }
+    }
+}
+
+/// Block
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Block {
+    /// Block Number
+    pub number: BlockNumber,
+
+    /// Post Statuses in Application Order
+    pub posts: Vec<(PostId, InclusionStatus)>,
+}
+
+impl Block {
+    /// Returns the number of posts in `self` which were applied to the ledger.
+    #[inline]
+    pub fn included_count(&self) -> usize {
+        self.posts
+            .iter()
+            .filter(|(_, status)| status.is_included())
+            .count()
+    }
+}
+
+/// Mempool
+#[derive(Debug, Default)]
+pub struct Mempool {
+    /// Next Post Identifier
+    next_id: u64,
+
+    /// Pending Posts in Submission Order
+    pending: VecDeque<(PostId, AccountId, TransferPost)>,
+
+    /// Post Statuses
+    statuses: HashMap<PostId, InclusionStatus>,
+}
+
+impl Mempool {
+    /// Returns the number of posts waiting for the next block.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if no posts are waiting for the next block.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the inclusion status of the post with the given `id`, or `None` if no such post
+    /// was submitted or if its status was decided more than [`STATUS_RETENTION_DEPTH`] blocks
+    /// ago.
+    #[inline]
+    pub fn status(&self, id: PostId) -> Option<InclusionStatus> {
+        self.statuses.get(&id).copied()
+    }
+
+    /// Queues `post` submitted by `account`, returning its identifier.
+    #[inline]
+    pub(super) fn insert(&mut self, account: AccountId, post: TransferPost) -> PostId {
+        let id = PostId(self.next_id);
+        self.next_id += 1;
+        self.pending.push_back((id, account, post));
+        self.statuses.insert(id, InclusionStatus::Pending);
+        id
+    }
+
+    /// Removes all the pending posts from `self` in submission order.
+    #[inline]
+    pub(super) fn take_pending(&mut self) -> VecDeque<(PostId, AccountId, TransferPost)> {
+        core::mem::take(&mut self.pending)
+    }
+
+    /// Sets the inclusion status of the post with the given `id` to `status`.
+    #[inline]
+    pub(super) fn set_status(&mut self, id: PostId, status: InclusionStatus) {
+        self.statuses.insert(id, status);
+    }
+
+    /// Evicts the statuses decided more than [`STATUS_RETENTION_DEPTH`] blocks before the block
+    /// with the given `number`.
+    #[inline]
+    pub(super) fn prune(&mut self, number: BlockNumber) {
+        self.statuses
+            .retain(|_, status| match status.block_number() {
+                Some(decided) => decided.saturating_add(STATUS_RETENTION_DEPTH) > number,
+                _ => true,
+            });
+    }
+}
+
+/// Produces a block on `ledger` every `block_time`, forever.
+///
+/// The ledger lock is only held while a block is being produced, so clients can keep submitting
+/// posts and pulling data in between blocks.
+#[inline]
+pub async fn produce_blocks(ledger: SharedLedger, block_time: Duration) {
+    let mut interval = tokio::time::interval(block_time);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        ledger.write().await.produce_block();
+    }
+}
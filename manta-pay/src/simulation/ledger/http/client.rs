@@ -21,14 +21,20 @@ use crate::{
         utxo::{AssetId, AssetValue},
         Config, TransferPost,
     },
-    simulation::ledger::{http::Request, AccountId, Checkpoint},
+    simulation::ledger::{
+        block::{InclusionStatus, PostId},
+        http::{PullRequest, Request},
+        AccountId, Checkpoint, PostError, ReceiverLedgerError, SenderLedgerError,
+        TransferLedgerError,
+    },
 };
 use manta_accounting::{
     asset::AssetList,
+    transfer::UtxoAccumulatorOutput,
     wallet::{
         ledger::{self, ReadResponse},
         signer::SyncData,
-        test::PublicBalanceOracle,
+        test::{PublicBalanceOracle, ValidationOracle},
     },
 };
 use manta_util::{
@@ -36,6 +42,20 @@ use manta_util::{
     http::reqwest::{Error, IntoUrl, KnownUrlClient},
 };
 
+#[cfg(feature = "websocket")]
+use {
+    crate::simulation::ledger::LedgerEvent,
+    core::marker::Unpin,
+    futures::StreamExt,
+    manta_util::from_variant,
+    tokio::net::TcpStream,
+    tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest, error::Error as WebSocketError, Message},
+        MaybeTlsStream, WebSocketStream,
+    },
+};
+
 /// HTTP Ledger Client
 pub struct Client {
     /// Account Id
@@ -43,6 +63,9 @@ pub struct Client {
 
     /// Client Connection
     client: KnownUrlClient,
+
+    /// Page Size
+    page_size: Option<usize>,
 }
 
 impl Client {
@@ -55,8 +78,45 @@ impl Client {
         Ok(Self {
             account,
             client: KnownUrlClient::new(server_url)?,
+            page_size: None,
         })
     }
+
+    /// Sets the maximum number of receivers and nullifiers requested from the server in a single
+    /// pull to `page_size`, or leaves it to the server if `page_size` is `None`.
+    #[inline]
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        self.page_size = page_size;
+    }
+
+    /// Submits `posts` to the ledger mempool, returning their identifiers.
+    #[inline]
+    pub async fn submit(&self, posts: Vec<TransferPost>) -> Result<Vec<PostId>, Error> {
+        self.client
+            .post(
+                "submit",
+                &Request {
+                    account: self.account,
+                    request: posts,
+                },
+            )
+            .await
+    }
+
+    /// Returns the inclusion status of the post with the given `id`. See
+    /// [`Ledger::status`](crate::simulation::ledger::Ledger::status) for more.
+    #[inline]
+    pub async fn status(&self, id: PostId) -> Result<Option<InclusionStatus>, Error> {
+        self.client
+            .post(
+                "status",
+                &Request {
+                    account: self.account,
+                    request: id,
+                },
+            )
+            .await
+    }
 }
 
 impl ledger::Connection for Client {
@@ -77,7 +137,10 @@ impl ledger::Read<SyncData<Config>> for Client {
                     "pull",
                     &Request {
                         account: self.account,
-                        request: checkpoint,
+                        request: PullRequest {
+                            checkpoint: *checkpoint,
+                            page_size: self.page_size,
+                        },
                     },
                 )
                 .await
@@ -119,3 +182,206 @@ impl PublicBalanceOracle<Config> for Client {
         })
     }
 }
+
+impl ValidationOracle<Config> for Client {
+    type AccountId = AccountId;
+    type SenderError = SenderLedgerError;
+    type ReceiverError = ReceiverLedgerError;
+    type TransferError = TransferLedgerError;
+
+    #[inline]
+    fn try_write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<Result<(), PostError>, Self::Error> {
+        Box::pin(async move {
+            self.client
+                .post(
+                    "tryPush",
+                    &Request {
+                        account: self.account,
+                        request: posts,
+                    },
+                )
+                .await
+        })
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output<'s>(
+        &'s self,
+        output: &'s UtxoAccumulatorOutput<Config>,
+    ) -> LocalBoxFuture<'s, Option<bool>> {
+        Box::pin(async move {
+            self.client
+                .post(
+                    "hasMatchingUtxoAccumulatorOutput",
+                    &Request {
+                        account: self.account,
+                        request: output,
+                    },
+                )
+                .await
+                .ok()
+        })
+    }
+}
+
+/// Subscription Client Error
+#[cfg(feature = "websocket")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "websocket")))]
+#[derive(Debug)]
+pub enum SubscriptionError {
+    /// HTTP Error
+    Http(Error),
+
+    /// WebSocket Error
+    WebSocket(WebSocketError),
+
+    /// Serialization Error
+    SerializationError(serde_json::Error),
+
+    /// End of Stream Error
+    ///
+    /// The server closed the event stream.
+    EndOfStream,
+}
+
+#[cfg(feature = "websocket")]
+from_variant!(SubscriptionError, Http, Error);
+
+#[cfg(feature = "websocket")]
+from_variant!(SubscriptionError, WebSocket, WebSocketError);
+
+#[cfg(feature = "websocket")]
+from_variant!(SubscriptionError, SerializationError, serde_json::Error);
+
+/// Subscription Client
+///
+/// This is an HTTP ledger [`Client`] which also listens to the events served by
+/// [`Server::serve_events`](super::server::Server::serve_events), so that wallets can synchronize
+/// as soon as the ledger has new data with [`Wallet::sync_on_update`].
+///
+/// [`Wallet::sync_on_update`]: manta_accounting::wallet::Wallet::sync_on_update
+#[cfg(feature = "websocket")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "websocket")))]
+pub struct SubscriptionClient {
+    /// HTTP Client
+    client: Client,
+
+    /// Event Stream
+    events: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+#[cfg(feature = "websocket")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "websocket")))]
+impl SubscriptionClient {
+    /// Builds a new [`SubscriptionClient`] from `client` which listens to the events served at
+    /// `events_url`.
+    #[inline]
+    pub async fn new<U>(client: Client, events_url: U) -> Result<Self, WebSocketError>
+    where
+        U: IntoClientRequest + Unpin,
+    {
+        Ok(Self {
+            client,
+            events: connect_async(events_url).await?.0,
+        })
+    }
+
+    /// Returns a shared reference to the underlying HTTP client.
+    #[inline]
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns a mutable reference to the underlying HTTP client.
+    #[inline]
+    pub fn client_mut(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    /// Waits for the next event served by the ledger.
+    #[inline]
+    pub async fn next_event(&mut self) -> Result<LedgerEvent, SubscriptionError> {
+        loop {
+            match self.events.next().await {
+                Some(Ok(Message::Text(message))) => return Ok(serde_json::from_str(&message)?),
+                Some(Ok(Message::Close(_))) | None => return Err(SubscriptionError::EndOfStream),
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl ledger::Connection for SubscriptionClient {
+    type Error = SubscriptionError;
+}
+
+#[cfg(feature = "websocket")]
+impl ledger::Read<SyncData<Config>> for SubscriptionClient {
+    type Checkpoint = Checkpoint;
+
+    #[inline]
+    fn read<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, ReadResponse<SyncData<Config>>, Self::Error> {
+        Box::pin(async move { Ok(self.client.read(checkpoint).await?) })
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl ledger::Subscribe<SyncData<Config>> for SubscriptionClient {
+    #[inline]
+    fn wait_for_update(&mut self) -> LocalBoxFutureResult<(), Self::Error> {
+        Box::pin(async move { self.next_event().await.map(|_| ()) })
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl ledger::Write<Vec<TransferPost>> for SubscriptionClient {
+    type Response = bool;
+
+    #[inline]
+    fn write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<Self::Response, Self::Error> {
+        Box::pin(async move { Ok(self.client.write(posts).await?) })
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl PublicBalanceOracle<Config> for SubscriptionClient {
+    #[inline]
+    fn public_balances(&self) -> LocalBoxFuture<Option<AssetList<AssetId, AssetValue>>> {
+        self.client.public_balances()
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl ValidationOracle<Config> for SubscriptionClient {
+    type AccountId = AccountId;
+    type SenderError = SenderLedgerError;
+    type ReceiverError = ReceiverLedgerError;
+    type TransferError = TransferLedgerError;
+
+    #[inline]
+    fn try_write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<Result<(), PostError>, Self::Error> {
+        Box::pin(async move { Ok(self.client.try_write(posts).await?) })
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output<'s>(
+        &'s self,
+        output: &'s UtxoAccumulatorOutput<Config>,
+    ) -> LocalBoxFuture<'s, Option<bool>> {
+        self.client.has_matching_utxo_accumulator_output(output)
+    }
+}
@@ -16,7 +16,7 @@
 
 //! Ledger HTTP Client and Server
 
-use crate::simulation::ledger::AccountId;
+use crate::simulation::ledger::{AccountId, Checkpoint};
 use manta_util::serde::{Deserialize, Serialize};
 
 pub mod client;
@@ -32,3 +32,18 @@ pub struct Request<T> {
     /// Request Payload
     pub request: T,
 }
+
+/// Pull Request
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct PullRequest {
+    /// Checkpoint
+    pub checkpoint: Checkpoint,
+
+    /// Page Size
+    ///
+    /// If set, the server returns at most this many receivers and nullifiers, or fewer if its own
+    /// page size is smaller.
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
@@ -21,12 +21,17 @@ use crate::{
         utxo::{AssetId, AssetValue},
         Config, TransferPost,
     },
-    simulation::ledger::{http::Request, AccountId, Checkpoint, Ledger, SharedLedger},
+    simulation::ledger::{
+        block::{InclusionStatus, PostId},
+        http::{PullRequest, Request},
+        AccountId, Ledger, PostError, SharedLedger,
+    },
 };
 use alloc::sync::Arc;
 use core::future::Future;
 use manta_accounting::{
     asset::AssetList,
+    transfer::{sender::SenderLedger, UtxoAccumulatorOutput},
     wallet::{ledger::ReadResponse, signer::SyncData},
 };
 use manta_util::{
@@ -35,6 +40,22 @@ use manta_util::{
 };
 use tokio::{io, sync::RwLock};
 
+#[cfg(feature = "serde_json")]
+use {core::time::Duration, std::path::PathBuf, tokio::time};
+
+#[cfg(feature = "websocket")]
+use {
+    futures::{SinkExt, StreamExt},
+    tokio::{
+        net::{TcpListener, TcpStream},
+        sync::broadcast::error::RecvError,
+    },
+    tokio_tungstenite::{
+        accept_async,
+        tungstenite::{error::Error as WebSocketError, Message},
+    },
+};
+
 /// Ledger HTTP Server State
 #[derive(Clone, Debug)]
 pub struct State(SharedLedger);
@@ -46,15 +67,26 @@ impl State {
         Self(Arc::new(RwLock::new(ledger)))
     }
 
-    /// Pulls data from the ledger at the given `checkpoint`.
+    /// Returns the shared ledger of `self`.
+    #[inline]
+    pub fn ledger(&self) -> &SharedLedger {
+        &self.0
+    }
+
+    /// Pulls data from the ledger at the checkpoint of `request`, returning a page no larger than
+    /// the page size of the ledger or the one in `request`.
     #[inline]
     async fn pull(
         self,
         account: AccountId,
-        checkpoint: Checkpoint,
+        request: PullRequest,
     ) -> ReadResponse<SyncData<Config>> {
         let _ = account;
-        self.0.read().await.pull(&checkpoint)
+        let ledger = self.0.read().await;
+        let page_size = request.page_size.map_or(ledger.page_size(), |page_size| {
+            page_size.clamp(1, ledger.page_size())
+        });
+        ledger.pull_page(&request.checkpoint, page_size)
     }
 
     /// Pushes data to the ledger with the given `account` and `posts`.
@@ -63,6 +95,41 @@ impl State {
         self.0.write().await.push(account, posts)
     }
 
+    /// Pushes data to the ledger with the given `account` and `posts`, returning the reason the
+    /// first invalid post was rejected.
+    #[inline]
+    async fn try_push(self, account: AccountId, posts: Vec<TransferPost>) -> Result<(), PostError> {
+        self.0.write().await.try_push(account, posts)
+    }
+
+    /// Queues `posts` from `account` in the ledger mempool, returning their identifiers.
+    #[inline]
+    async fn submit(self, account: AccountId, posts: Vec<TransferPost>) -> Vec<PostId> {
+        self.0.write().await.submit(account, posts)
+    }
+
+    /// Returns the inclusion status of the post with the given `id`.
+    #[inline]
+    async fn status(self, account: AccountId, id: PostId) -> Option<InclusionStatus> {
+        let _ = account;
+        self.0.read().await.status(id)
+    }
+
+    /// Returns `true` if the ledger accepts senders built against `output`.
+    #[inline]
+    async fn has_matching_utxo_accumulator_output(
+        self,
+        account: AccountId,
+        output: UtxoAccumulatorOutput<Config>,
+    ) -> bool {
+        let _ = account;
+        self.0
+            .read()
+            .await
+            .has_matching_utxo_accumulator_output(output)
+            .is_ok()
+    }
+
     /// Returns the public balances associated to `account` if they exist.
     #[inline]
     async fn public_balances(self, account: AccountId) -> Option<AssetList<AssetId, AssetValue>> {
@@ -79,17 +146,31 @@ impl Server {
     #[inline]
     pub fn new(ledger: Ledger) -> Self {
         let mut api = tide::Server::with_state(State::new(ledger));
-        api.at("/pull").get(|r| Self::execute_with(r, State::pull));
+        api.at("/pull").post(|r| Self::execute_with(r, State::pull));
         api.at("/push").post(|r| Self::execute_with(r, State::push));
+        api.at("/tryPush")
+            .post(|r| Self::execute_with(r, State::try_push));
+        api.at("/submit")
+            .post(|r| Self::execute_with(r, State::submit));
+        api.at("/status")
+            .post(|r| Self::execute_with(r, State::status));
+        api.at("/hasMatchingUtxoAccumulatorOutput")
+            .post(|r| Self::execute_with(r, State::has_matching_utxo_accumulator_output));
         api.at("/publicBalances")
             .post(|r| Self::execute(r, State::public_balances));
         Self(api)
     }
 
+    /// Returns the shared ledger served by `self`.
+    #[inline]
+    pub fn ledger(&self) -> &SharedLedger {
+        self.0.state().ledger()
+    }
+
     /// Executes `f` on the incoming `request`.
     #[inline]
     async fn execute<R, F, Fut>(
-        request: tide::Request<State>,
+        mut request: tide::Request<State>,
         f: F,
     ) -> Result<Response, tide::Error>
     where
@@ -97,14 +178,14 @@ impl Server {
         F: FnOnce(State, AccountId) -> Fut,
         Fut: Future<Output = R>,
     {
-        let account = request.query::<AccountId>()?;
+        let account = request.body_json::<AccountId>().await?;
         Self::into_body(move || async move { f(request.state().clone(), account).await }).await
     }
 
-    /// Executes `f` on the incoming `request` parsing the full query.
+    /// Executes `f` on the incoming `request` parsing the full JSON body.
     #[inline]
     async fn execute_with<T, R, F, Fut>(
-        request: tide::Request<State>,
+        mut request: tide::Request<State>,
         f: F,
     ) -> Result<Response, tide::Error>
     where
@@ -113,7 +194,7 @@ impl Server {
         F: FnOnce(State, AccountId, T) -> Fut,
         Fut: Future<Output = R>,
     {
-        let args = request.query::<Request<T>>()?;
+        let args = request.body_json::<Request<T>>().await?;
         Self::into_body(move || async move {
             f(request.state().clone(), args.account, args.request).await
         })
@@ -139,4 +220,96 @@ impl Server {
     {
         self.0.listen(listener).await
     }
+
+    /// Serves the ledger events over WebSocket connections accepted from `listener`.
+    ///
+    /// Every connection is served on its own task and receives the
+    /// [`LedgerEvent`](crate::simulation::ledger::LedgerEvent)s applied to the ledger after it was
+    /// accepted, each one as a JSON text message. Connections which fall behind skip the oldest
+    /// events, so clients should use them to know when to pull instead of as a complete record of
+    /// the ledger.
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "websocket")))]
+    #[inline]
+    pub async fn serve_events(self, listener: TcpListener) -> Result<(), io::Error> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(Self::serve_event_connection(
+                self.0.state().ledger().clone(),
+                stream,
+            ));
+        }
+    }
+
+    /// Forwards the events of `ledger` to the WebSocket connection over `stream` until either side
+    /// closes it.
+    #[cfg(feature = "websocket")]
+    #[inline]
+    async fn serve_event_connection(
+        ledger: SharedLedger,
+        stream: TcpStream,
+    ) -> Result<(), WebSocketError> {
+        let mut stream = accept_async(stream).await?;
+        let mut events = ledger.read().await.subscribe();
+        loop {
+            tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(err)) => return Err(err),
+                    _ => {}
+                },
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        let event = serde_json::to_string(&event)
+                            .expect("Ledger events are always serializable.");
+                        stream.send(Message::Text(event)).await?;
+                    }
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Serves `self` at the given `listener`, saving a ledger
+    /// [`Snapshot`](crate::simulation::ledger::Snapshot) to `path` every `interval`.
+    ///
+    /// The snapshot is taken while holding the ledger lock and written to disk after releasing it.
+    /// The server stops and returns the error if a snapshot cannot be saved.
+    #[cfg(feature = "serde_json")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "serde_json")))]
+    #[inline]
+    pub async fn serve_with_snapshots<L>(
+        self,
+        listener: L,
+        path: PathBuf,
+        interval: Duration,
+    ) -> Result<(), io::Error>
+    where
+        L: ToListener<State>,
+    {
+        let ledger = self.0.state().ledger().clone();
+        tokio::select! {
+            result = self.serve(listener) => result,
+            result = Self::save_snapshots(ledger, path, interval) => result,
+        }
+    }
+
+    /// Saves a snapshot of `ledger` to `path` every `interval` until one of them fails.
+    #[cfg(feature = "serde_json")]
+    #[inline]
+    async fn save_snapshots(
+        ledger: SharedLedger,
+        path: PathBuf,
+        interval: Duration,
+    ) -> Result<(), io::Error> {
+        let mut timer = time::interval(interval);
+        timer.tick().await;
+        loop {
+            timer.tick().await;
+            let snapshot = ledger.read().await.snapshot();
+            snapshot.save(&path)?;
+        }
+    }
 }
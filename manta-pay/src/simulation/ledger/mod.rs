@@ -16,18 +16,24 @@
 
 //! Ledger Simulation
 
-// TODO: How to model existential deposits and fee payments?
 // TODO: Add in some concurrency (and measure how much we need it).
 
-use crate::config::{
-    utxo::{
-        AssetId, AssetValue, Checkpoint, FullIncomingNote, MerkleTreeConfiguration, Parameters,
+use crate::{
+    config::{
+        utxo::{
+            AssetId, AssetValue, Checkpoint, Config as UtxoConfig, FullIncomingNote,
+            MerkleTreeConfiguration, Parameters,
+        },
+        Config, MultiVerifyingContext, Nullifier, ProofSystem, TransferPost, Utxo,
+        UtxoAccumulatorModel,
+    },
+    simulation::{
+        ledger::block::{Block, BlockNumber, InclusionStatus, Mempool, PostId},
+        report::ShapeLatencies,
     },
-    Config, MultiVerifyingContext, Nullifier, ProofSystem, TransferPost, Utxo,
-    UtxoAccumulatorModel,
 };
 use alloc::{sync::Arc, vec::Vec};
-use core::convert::Infallible;
+use core::{convert::Infallible, time::Duration};
 use indexmap::IndexSet;
 use manta_accounting::{
     asset::{Asset, AssetList},
@@ -35,6 +41,7 @@ use manta_accounting::{
         canonical::TransferShape,
         receiver::{ReceiverLedger, ReceiverPostError},
         sender::{SenderLedger, SenderPostError},
+        utxo::protocol::Nullifier as NullifierCommitment,
         InvalidAuthorizationSignature, InvalidSinkAccount, InvalidSourceAccount, SinkPostingKey,
         SourcePostingKey, TransferLedger, TransferLedgerSuperPostingKey, TransferPostError,
         TransferPostingKeyRef, UtxoAccumulatorOutput,
@@ -42,7 +49,7 @@ use manta_accounting::{
     wallet::{
         ledger::{self, ReadResponse},
         signer::SyncData,
-        test::PublicBalanceOracle,
+        test::{PublicBalanceOracle, ValidationOracle},
     },
 };
 use manta_crypto::{
@@ -54,12 +61,24 @@ use manta_crypto::{
     },
 };
 use manta_util::future::{LocalBoxFuture, LocalBoxFutureResult};
-use std::collections::{HashMap, HashSet};
-use tokio::sync::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+use tokio::sync::{broadcast, RwLock};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
 
+#[cfg(all(feature = "serde", feature = "serde_json", feature = "std"))]
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+pub mod block;
+
 #[cfg(feature = "http")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "http")))]
 pub mod http;
@@ -115,12 +134,185 @@ impl<L, R> AsRef<R> for WrapPair<L, R> {
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct AccountId(pub u64);
 
+/// Fee Schedule
+///
+/// Fees are paid in the public asset of a transfer by the public account which funds it, for
+/// [`ToPrivate`](TransferShape::ToPrivate), or which receives it, for
+/// [`ToPublic`](TransferShape::ToPublic). Private transfers touch no public account, so they are
+/// always free.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FeeSchedule {
+    /// To-Private Fee
+    pub to_private: AssetValue,
+
+    /// To-Public Fee
+    pub to_public: AssetValue,
+}
+
+impl FeeSchedule {
+    /// Returns the fee charged for posting a transfer of the given `shape`.
+    #[inline]
+    pub const fn fee(&self, shape: TransferShape) -> AssetValue {
+        match shape {
+            TransferShape::ToPrivate => self.to_private,
+            TransferShape::PrivateTransfer => 0,
+            TransferShape::ToPublic => self.to_public,
+        }
+    }
+}
+
+/// Ledger Snapshot
+///
+/// This snapshot holds the state of a [`Ledger`] which cannot be recomputed, namely its nullifiers,
+/// receivers and public accounts. The UTXO forest is rebuilt from the receivers on
+/// [`Ledger::from_snapshot`] and the verifying contexts and parameters are supplied again when
+/// restoring.
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct Snapshot {
+    /// Nullifiers
+    nullifiers: Vec<Nullifier>,
+
+    /// Receivers in Shard Order
+    receivers: Vec<(Utxo, FullIncomingNote)>,
+
+    /// Account Table
+    accounts: Vec<(AccountId, Vec<(AssetId, AssetValue)>)>,
+
+    /// Page Size
+    page_size: usize,
+
+    /// Fee Schedule
+    #[serde(default)]
+    fee_schedule: FeeSchedule,
+
+    /// Existential Deposit
+    #[serde(default)]
+    existential_deposit: AssetValue,
+}
+
+#[cfg(feature = "serde")]
+impl Snapshot {
+    /// Returns the number of nullifiers in `self`.
+    #[inline]
+    pub fn nullifier_count(&self) -> usize {
+        self.nullifiers.len()
+    }
+
+    /// Returns the number of receivers in `self`.
+    #[inline]
+    pub fn receiver_count(&self) -> usize {
+        self.receivers.len()
+    }
+
+    /// Saves `self` to `path` as JSON.
+    ///
+    /// The snapshot is written to a temporary file next to `path` first and then renamed over
+    /// `path`, so a crash while saving never leaves a partially written snapshot behind.
+    #[cfg(all(feature = "serde", feature = "serde_json", feature = "std"))]
+    #[cfg_attr(
+        doc_cfg,
+        doc(cfg(all(feature = "serde", feature = "serde_json", feature = "std")))
+    )]
+    #[inline]
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        let mut file = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut file, self)?;
+        file.into_inner()?.sync_all()?;
+        fs::rename(temporary_path, path)
+    }
+
+    /// Loads a [`Snapshot`] from the JSON file at `path`.
+    #[cfg(all(feature = "serde", feature = "serde_json", feature = "std"))]
+    #[cfg_attr(
+        doc_cfg,
+        doc(cfg(all(feature = "serde", feature = "serde_json", feature = "std")))
+    )]
+    #[inline]
+    pub fn load<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+/// Capacity of the Channel of [`LedgerEvent`]s
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Ledger Event
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug)]
+pub enum LedgerEvent {
+    /// Post
+    ///
+    /// A post was applied to the ledger, appending its receivers and nullifiers.
+    Post {
+        /// Receivers of the Post
+        receivers: Vec<(Utxo, FullIncomingNote)>,
+
+        /// Nullifiers of the Post
+        nullifiers: Vec<Nullifier>,
+
+        /// Checkpoint of the Ledger right after the Post
+        checkpoint: Checkpoint,
+    },
+
+    /// Reorganization
+    ///
+    /// The ledger dropped its latest blocks and returned to the state at `fork_point`. See
+    /// [`Ledger::reorganize`] for more.
+    Reorganization {
+        /// Fork Point
+        fork_point: Checkpoint,
+    },
+}
+
+/// Block Record
+///
+/// This is the state of the [`Ledger`] right before it produced a block, which is restored when
+/// the block is dropped by [`Ledger::reorganize`].
+#[derive(Debug)]
+struct BlockRecord {
+    /// Checkpoint before the Block
+    checkpoint: Checkpoint,
+
+    /// Account Table before the Block
+    accounts: HashMap<AccountId, HashMap<AssetId, AssetValue>>,
+
+    /// Posts Included in the Block
+    posts: Vec<PostId>,
+}
+
 /// Ledger
 #[derive(Debug)]
 pub struct Ledger {
     /// Nullifier
     nullifiers: IndexSet<Nullifier>,
 
+    /// Spent Nullifier Commitments
+    ///
+    /// The nullifiers above carry an outgoing note which is encrypted anew on every spend, so
+    /// double spends are detected using only their commitments.
+    spent: HashSet<NullifierCommitment<UtxoConfig>>,
+
     /// UTXOs
     utxos: HashSet<Utxo>,
 
@@ -138,6 +330,38 @@ pub struct Ledger {
 
     /// UTXO Configuration Parameters
     parameters: Parameters,
+
+    /// Page Size
+    ///
+    /// Maximum number of receivers and nullifiers returned by a single call to
+    /// [`pull`](Self::pull).
+    page_size: usize,
+
+    /// Fee Schedule
+    fee_schedule: FeeSchedule,
+
+    /// Existential Deposit
+    ///
+    /// Minimum nonzero public balance an account is allowed to be left with after a post.
+    existential_deposit: AssetValue,
+
+    /// Mempool
+    mempool: Mempool,
+
+    /// Number of Blocks Produced
+    block_number: BlockNumber,
+
+    /// Block Records
+    blocks: Vec<BlockRecord>,
+
+    /// Fork Points of the Reorganizations in Order
+    forks: Vec<Checkpoint>,
+
+    /// Verification Latencies
+    verification_latencies: ShapeLatencies,
+
+    /// Event Channel
+    events: broadcast::Sender<LedgerEvent>,
 }
 
 impl Ledger {
@@ -150,6 +374,7 @@ impl Ledger {
     ) -> Self {
         Self {
             nullifiers: Default::default(),
+            spent: Default::default(),
             utxos: Default::default(),
             shards: (0..MerkleTreeConfiguration::FOREST_WIDTH)
                 .map(move |i| (MerkleForestIndex::from_index(i), Default::default()))
@@ -158,7 +383,227 @@ impl Ledger {
             accounts: Default::default(),
             verifying_context,
             parameters,
+            page_size: usize::MAX,
+            fee_schedule: Default::default(),
+            existential_deposit: 0,
+            mempool: Default::default(),
+            block_number: 0,
+            blocks: Default::default(),
+            forks: Default::default(),
+            verification_latencies: Default::default(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Returns the maximum number of receivers and nullifiers returned by a single call to
+    /// [`pull`](Self::pull).
+    #[inline]
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Sets the maximum number of receivers and nullifiers returned by a single call to
+    /// [`pull`](Self::pull) to `page_size`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `page_size` is zero, since the ledger could never make progress.
+    #[inline]
+    pub fn set_page_size(&mut self, page_size: usize) {
+        assert_ne!(page_size, 0, "Page size can't be zero!");
+        self.page_size = page_size;
+    }
+
+    /// Returns the fee schedule of `self`.
+    #[inline]
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        self.fee_schedule
+    }
+
+    /// Sets the fee schedule of `self` to `fee_schedule`.
+    #[inline]
+    pub fn set_fee_schedule(&mut self, fee_schedule: FeeSchedule) {
+        self.fee_schedule = fee_schedule;
+    }
+
+    /// Returns the existential deposit of `self`.
+    #[inline]
+    pub fn existential_deposit(&self) -> AssetValue {
+        self.existential_deposit
+    }
+
+    /// Sets the existential deposit of `self` to `existential_deposit`.
+    ///
+    /// Posts which would leave a public balance strictly between zero and `existential_deposit`
+    /// are rejected with [`TransferLedgerError::ExistentialDeposit`].
+    #[inline]
+    pub fn set_existential_deposit(&mut self, existential_deposit: AssetValue) {
+        self.existential_deposit = existential_deposit;
+    }
+
+    /// Returns the public `balance` of `account_id` left after paying `fee`, checking it against
+    /// the existential deposit.
+    #[inline]
+    fn check_balance_after_fee(
+        &self,
+        account_id: AccountId,
+        balance: AssetValue,
+        fee: AssetValue,
+    ) -> Result<AssetValue, TransferLedgerError> {
+        let balance = balance
+            .checked_sub(fee)
+            .ok_or(TransferLedgerError::InsufficientFee { account_id, fee })?;
+        if balance != 0 && balance < self.existential_deposit {
+            return Err(TransferLedgerError::ExistentialDeposit {
+                account_id,
+                balance,
+            });
         }
+        Ok(balance)
+    }
+
+    /// Checks that every public account in `posting_key` can pay `fee` without being left with a
+    /// balance below the existential deposit.
+    #[inline]
+    fn check_fees(
+        &self,
+        posting_key: &TransferPostingKeyRef<Config, Self>,
+        fee: AssetValue,
+    ) -> Result<(), TransferLedgerError> {
+        let asset_id = match posting_key.asset_id {
+            Some(asset_id) => asset_id,
+            _ => return Ok(()),
+        };
+        let balance = |account_id| {
+            self.accounts
+                .get(account_id)
+                .and_then(|balances| balances.get(asset_id))
+                .copied()
+                .unwrap_or_default()
+        };
+        for WrapPair(account_id, withdraw) in posting_key.sources {
+            self.check_balance_after_fee(
+                *account_id,
+                balance(account_id).saturating_sub(*withdraw),
+                fee,
+            )?;
+        }
+        for WrapPair(account_id, deposit) in posting_key.sinks {
+            self.check_balance_after_fee(
+                *account_id,
+                balance(account_id).saturating_add(*deposit),
+                fee,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Builds a new [`Ledger`] from `snapshot`, rebuilding the UTXO forest from its receivers.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+    #[inline]
+    pub fn from_snapshot(
+        snapshot: Snapshot,
+        utxo_accumulator_model: UtxoAccumulatorModel,
+        verifying_context: MultiVerifyingContext,
+        parameters: Parameters,
+    ) -> Self {
+        let mut ledger = Self::new(utxo_accumulator_model, verifying_context, parameters);
+        ledger.spent = snapshot
+            .nullifiers
+            .iter()
+            .map(|nullifier| nullifier.nullifier)
+            .collect();
+        ledger.nullifiers = snapshot.nullifiers.into_iter().collect();
+        for (utxo, note) in snapshot.receivers {
+            let utxo_hash = ledger.parameters.item_hash(&utxo, &mut ());
+            ledger
+                .shards
+                .get_mut(&MerkleTreeConfiguration::tree_index(&utxo_hash))
+                .expect("All shards are created when building the ledger.")
+                .insert((utxo, note));
+            ledger.utxos.insert(utxo);
+            ledger.utxo_forest.push(&utxo_hash);
+        }
+        ledger.accounts = snapshot
+            .accounts
+            .into_iter()
+            .map(|(account, balances)| (account, balances.into_iter().collect()))
+            .collect();
+        ledger.page_size = snapshot.page_size;
+        ledger.fee_schedule = snapshot.fee_schedule;
+        ledger.existential_deposit = snapshot.existential_deposit;
+        ledger
+    }
+
+    /// Takes a [`Snapshot`] of `self`.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+    #[inline]
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            nullifiers: self.nullifiers.iter().cloned().collect(),
+            receivers: (0..MerkleTreeConfiguration::FOREST_WIDTH)
+                .flat_map(|i| {
+                    self.shards[&MerkleForestIndex::from_index(i)]
+                        .iter()
+                        .cloned()
+                })
+                .collect(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(account, balances)| {
+                    (
+                        *account,
+                        balances.iter().map(|(id, value)| (*id, *value)).collect(),
+                    )
+                })
+                .collect(),
+            page_size: self.page_size,
+            fee_schedule: self.fee_schedule,
+            existential_deposit: self.existential_deposit,
+        }
+    }
+
+    /// Saves a [`Snapshot`] of `self` to `path`. See [`Snapshot::save`] for more.
+    #[cfg(all(feature = "serde", feature = "serde_json", feature = "std"))]
+    #[cfg_attr(
+        doc_cfg,
+        doc(cfg(all(feature = "serde", feature = "serde_json", feature = "std")))
+    )]
+    #[inline]
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.snapshot().save(path)
+    }
+
+    /// Loads a [`Ledger`] from the [`Snapshot`] saved at `path`. See [`from_snapshot`] for more.
+    ///
+    /// [`from_snapshot`]: Self::from_snapshot
+    #[cfg(all(feature = "serde", feature = "serde_json", feature = "std"))]
+    #[cfg_attr(
+        doc_cfg,
+        doc(cfg(all(feature = "serde", feature = "serde_json", feature = "std")))
+    )]
+    #[inline]
+    pub fn load<P>(
+        path: P,
+        utxo_accumulator_model: UtxoAccumulatorModel,
+        verifying_context: MultiVerifyingContext,
+        parameters: Parameters,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::from_snapshot(
+            Snapshot::load(path)?,
+            utxo_accumulator_model,
+            verifying_context,
+            parameters,
+        ))
     }
 
     /// Returns the public balances of `account` if it exists.
@@ -180,25 +625,56 @@ impl Ledger {
         self.accounts.entry(account).or_default().insert(id, value);
     }
 
-    /// Pulls the data from the ledger later than the given `checkpoint`.
+    /// Pulls the data from the ledger later than the given `checkpoint`, returning at most
+    /// [`page_size`](Self::page_size) receivers and nullifiers.
     #[inline]
     pub fn pull(&self, checkpoint: &Checkpoint) -> ReadResponse<SyncData<Config>> {
+        self.pull_page(checkpoint, self.page_size)
+    }
+
+    /// Pulls the data from the ledger later than the given `checkpoint`, returning at most
+    /// `page_size` receivers and nullifiers.
+    ///
+    /// # Pagination
+    ///
+    /// Receivers are paged shard by shard in order and nullifiers are only returned once every
+    /// receiver after `checkpoint` fits in the page, so that a signer never observes a nullifier
+    /// before the receiver it spends.
+    #[inline]
+    pub fn pull_page(
+        &self,
+        checkpoint: &Checkpoint,
+        page_size: usize,
+    ) -> ReadResponse<SyncData<Config>> {
         let mut receivers = Vec::new();
-        for (i, mut index) in checkpoint.receiver_index.iter().copied().enumerate() {
+        let mut remaining = 0;
+        for (i, index) in checkpoint.receiver_index.iter().copied().enumerate() {
             let shard = &self.shards[&MerkleForestIndex::from_index(i)];
-            while let Some(entry) = shard.get_index(index) {
-                receivers.push(entry.clone());
-                index += 1;
-            }
+            let available = shard.len().saturating_sub(index);
+            let count = available.min(page_size - receivers.len());
+            receivers.extend((index..index + count).filter_map(|j| shard.get_index(j).cloned()));
+            remaining += available - count;
         }
+        let available = self
+            .nullifiers
+            .len()
+            .saturating_sub(checkpoint.sender_index);
+        let count = if remaining == 0 {
+            available.min(page_size - receivers.len())
+        } else {
+            0
+        };
         let senders = self
             .nullifiers
             .iter()
             .skip(checkpoint.sender_index)
+            .take(count)
             .cloned()
             .collect();
+        remaining += available - count;
         ReadResponse {
-            should_continue: false,
+            should_continue: remaining != 0,
+            remaining: Some(remaining),
             data: SyncData {
                 utxo_note_data: receivers,
                 nullifier_data: senders,
@@ -206,25 +682,269 @@ impl Ledger {
         }
     }
 
+    /// Validates `post` from `account` against the current state of the ledger and applies it,
+    /// returning the reason it was rejected if it was not valid.
+    #[inline]
+    fn apply(&mut self, account: AccountId, post: TransferPost) -> Result<(), PostError> {
+        let shape = TransferShape::from_post(&post).ok_or(TransferPostError::InvalidShape)?;
+        let (sources, sinks) = match shape {
+            TransferShape::ToPrivate => (vec![account], vec![]),
+            TransferShape::PrivateTransfer => (vec![], vec![]),
+            TransferShape::ToPublic => (vec![], vec![account]),
+        };
+        let event = (self.events.receiver_count() > 0).then(|| {
+            (
+                post.body
+                    .receiver_posts
+                    .iter()
+                    .map(|receiver| (receiver.utxo, receiver.note.clone()))
+                    .collect(),
+                post.body
+                    .sender_posts
+                    .iter()
+                    .map(|sender| sender.nullifier)
+                    .collect(),
+            )
+        });
+        let start = Instant::now();
+        let posting_key = post.validate(&self.parameters, &*self, sources, sinks);
+        self.verification_latencies.record(shape, start.elapsed());
+        posting_key?.post(&mut *self, &()).unwrap();
+        if let Some((receivers, nullifiers)) = event {
+            let _ = self.events.send(LedgerEvent::Post {
+                receivers,
+                nullifiers,
+                checkpoint: self.checkpoint(),
+            });
+        }
+        Ok(())
+    }
+
     /// Pushes the data from `posts` to the ledger.
     #[inline]
     pub fn push(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        self.try_push(account, posts).is_ok()
+    }
+
+    /// Pushes the data from `posts` to the ledger, returning the reason the first invalid post was
+    /// rejected. The posts before it stay applied.
+    #[inline]
+    pub fn try_push(
+        &mut self,
+        account: AccountId,
+        posts: Vec<TransferPost>,
+    ) -> Result<(), PostError> {
         for post in posts {
-            let (sources, sinks) = match TransferShape::from_post(&post) {
-                Some(TransferShape::ToPrivate) => (vec![account], vec![]),
-                Some(TransferShape::PrivateTransfer) => (vec![], vec![]),
-                Some(TransferShape::ToPublic) => (vec![], vec![account]),
-                _ => return false,
+            self.apply(account, post)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the time spent validating posts in [`push`](Self::push) and
+    /// [`produce_block`](Self::produce_block) by `self`, including the posts which were rejected.
+    #[inline]
+    pub fn verification_latencies(&self) -> &ShapeLatencies {
+        &self.verification_latencies
+    }
+
+    /// Subscribes to the events of `self`, starting from the next one.
+    ///
+    /// Subscribers which fall behind by more than [`EVENT_CHANNEL_CAPACITY`] events skip the
+    /// oldest ones.
+    #[inline]
+    pub fn subscribe(&self) -> broadcast::Receiver<LedgerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Returns the mempool of `self`.
+    #[inline]
+    pub fn mempool(&self) -> &Mempool {
+        &self.mempool
+    }
+
+    /// Returns the number of blocks produced by `self`.
+    #[inline]
+    pub fn block_number(&self) -> BlockNumber {
+        self.block_number
+    }
+
+    /// Queues `posts` from `account` in the mempool until the next call to
+    /// [`produce_block`](Self::produce_block), returning their identifiers in the same order.
+    #[inline]
+    pub fn submit(&mut self, account: AccountId, posts: Vec<TransferPost>) -> Vec<PostId> {
+        posts
+            .into_iter()
+            .map(|post| self.mempool.insert(account, post))
+            .collect()
+    }
+
+    /// Returns the inclusion status of the post with the given `id`. See [`Mempool::status`] for
+    /// more.
+    #[inline]
+    pub fn status(&self, id: PostId) -> Option<InclusionStatus> {
+        self.mempool.status(id)
+    }
+
+    /// Applies every post waiting in the mempool in submission order, returning the new [`Block`].
+    ///
+    /// A post which spends a nullifier already spent by an earlier post of the same block is
+    /// reported as a [`Conflict`](InclusionStatus::Conflict), without being validated. Every
+    /// other post is validated against the state left by the posts before it, so a post built
+    /// against a UTXO accumulator root that an earlier post in the block replaced is
+    /// [`Rejected`](InclusionStatus::Rejected). Statuses decided more than
+    /// [`STATUS_RETENTION_DEPTH`](block::STATUS_RETENTION_DEPTH) blocks before the new block are
+    /// evicted from the mempool.
+    #[inline]
+    pub fn produce_block(&mut self) -> Block {
+        self.blocks.push(BlockRecord {
+            checkpoint: self.checkpoint(),
+            accounts: self.accounts.clone(),
+            posts: Default::default(),
+        });
+        self.block_number += 1;
+        let number = self.block_number;
+        let mut spent = HashSet::new();
+        let mut posts = Vec::new();
+        for (id, account, post) in self.mempool.take_pending() {
+            let nullifiers = post
+                .body
+                .sender_posts
+                .iter()
+                .map(|sender| sender.nullifier.nullifier)
+                .collect::<Vec<_>>();
+            let status = if nullifiers.iter().any(|nullifier| spent.contains(nullifier)) {
+                InclusionStatus::Conflict(number)
+            } else if self.apply(account, post).is_ok() {
+                spent.extend(nullifiers);
+                InclusionStatus::Included(number)
+            } else {
+                InclusionStatus::Rejected(number)
             };
-            match post.validate(&self.parameters, &*self, sources, sinks) {
-                Ok(posting_key) => posting_key.post(&mut *self, &()).unwrap(),
-                _ => return false,
+            self.mempool.set_status(id, status);
+            posts.push((id, status));
+        }
+        self.mempool.prune(number);
+        if let Some(record) = self.blocks.last_mut() {
+            record.posts = posts
+                .iter()
+                .filter(|(_, status)| status.is_included())
+                .map(|(id, _)| *id)
+                .collect();
+        }
+        Block { number, posts }
+    }
+
+    /// Returns the checkpoint of the current state of `self`, which is where a wallet that read
+    /// all of its data ends up.
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(
+            core::array::from_fn(|i| self.shards[&MerkleForestIndex::from_index(i)].len()).into(),
+            self.nullifiers.len(),
+        )
+    }
+
+    /// Drops the last `depth` blocks produced by `self`, like a chain reorganization, and returns
+    /// the checkpoint of the state they were produced on.
+    ///
+    /// Every post applied since the first of those blocks was produced is dropped, including those
+    /// pushed without going through the mempool, and the posts included in the dropped blocks are
+    /// marked as [`Dropped`](InclusionStatus::Dropped). Blocks produced before `self` was restored
+    /// from a [`Snapshot`] cannot be dropped.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `depth` is larger than the number of blocks which can be dropped.
+    #[inline]
+    pub fn reorganize(&mut self, depth: usize) -> Checkpoint {
+        assert!(
+            depth <= self.blocks.len(),
+            "Only {} blocks can be dropped.",
+            self.blocks.len()
+        );
+        if depth == 0 {
+            return self.checkpoint();
+        }
+        let dropped = self.blocks.split_off(self.blocks.len() - depth);
+        for (number, record) in (self.block_number - depth as BlockNumber + 1..).zip(&dropped) {
+            for id in &record.posts {
+                self.mempool
+                    .set_status(*id, InclusionStatus::Dropped(number));
+            }
+        }
+        self.block_number -= depth as BlockNumber;
+        let BlockRecord {
+            checkpoint,
+            accounts,
+            ..
+        } = dropped
+            .into_iter()
+            .next()
+            .expect("At least one block is dropped.");
+        self.accounts = accounts;
+        for (i, count) in checkpoint.receiver_index.iter().enumerate() {
+            let shard = self
+                .shards
+                .get_mut(&MerkleForestIndex::from_index(i))
+                .expect("All shards are created when building the ledger.");
+            for (utxo, _) in shard.drain(*count..) {
+                self.utxos.remove(&utxo);
+            }
+        }
+        for nullifier in self.nullifiers.drain(checkpoint.sender_index..) {
+            self.spent.remove(&nullifier.nullifier);
+        }
+        self.utxo_forest = UtxoMerkleForest::new(self.utxo_forest.parameters().clone());
+        for i in 0..MerkleTreeConfiguration::FOREST_WIDTH {
+            for (utxo, _) in &self.shards[&MerkleForestIndex::from_index(i)] {
+                self.utxo_forest
+                    .push(&self.parameters.item_hash(utxo, &mut ()));
             }
         }
-        true
+        self.forks.push(checkpoint);
+        let _ = self.events.send(LedgerEvent::Reorganization {
+            fork_point: checkpoint,
+        });
+        checkpoint
+    }
+
+    /// Returns the number of reorganizations performed by `self`.
+    #[inline]
+    pub fn reorganization_count(&self) -> usize {
+        self.forks.len()
+    }
+
+    /// Returns the fork point of `checkpoint` with respect to the reorganizations of `self` after
+    /// the first `seen` ones, or `None` if none of them dropped data read up to `checkpoint`.
+    ///
+    /// The fork point is the latest checkpoint at or before `checkpoint` and every fork point of
+    /// those reorganizations, shard by shard.
+    #[inline]
+    pub fn fork_point(&self, checkpoint: &Checkpoint, seen: usize) -> Option<Checkpoint> {
+        let mut fork_point = *checkpoint;
+        for fork in self.forks.iter().skip(seen) {
+            for (index, fork_index) in fork_point
+                .receiver_index
+                .iter_mut()
+                .zip(fork.receiver_index.iter())
+            {
+                *index = (*index).min(*fork_index);
+            }
+            fork_point.sender_index = fork_point.sender_index.min(fork.sender_index);
+        }
+        (fork_point != *checkpoint).then_some(fork_point)
     }
 }
 
+/// Ledger Post Error Type
+pub type PostError = TransferPostError<
+    Config,
+    AccountId,
+    SenderLedgerError,
+    ReceiverLedgerError,
+    TransferLedgerError,
+>;
+
 /// Sender Ledger Error
 #[cfg_attr(
     feature = "serde",
@@ -331,6 +1051,28 @@ pub enum TransferLedgerError {
     /// Validity of the transfer could not be proved by the ledger.
     InvalidProof,
 
+    /// Insufficient Fee Error
+    ///
+    /// The public account charged for the transfer cannot pay its fee.
+    InsufficientFee {
+        /// Account Id
+        account_id: AccountId,
+
+        /// Fee
+        fee: AssetValue,
+    },
+
+    /// Existential Deposit Error
+    ///
+    /// The transfer would leave a nonzero public balance below the existential deposit.
+    ExistentialDeposit {
+        /// Account Id
+        account_id: AccountId,
+
+        /// Remaining Balance
+        balance: AssetValue,
+    },
+
     /// Unexpected Error
     ///
     /// An unexpected error occured.
@@ -360,9 +1102,9 @@ impl From<TransferLedgerError>
             TransferLedgerError::DuplicateSpend => Self::DuplicateSpend,
             TransferLedgerError::DuplicateMint => Self::DuplicateMint,
             TransferLedgerError::InvalidProof => Self::InvalidProof,
-            TransferLedgerError::UnexpectedError => {
-                Self::UnexpectedError(TransferLedgerError::UnexpectedError)
-            }
+            err @ (TransferLedgerError::InsufficientFee { .. }
+            | TransferLedgerError::ExistentialDeposit { .. }
+            | TransferLedgerError::UnexpectedError) => Self::UnexpectedError(err),
         }
     }
 }
@@ -384,12 +1126,12 @@ impl From<SenderLedgerError> for TransferLedgerError {
 impl SenderLedger<Parameters> for Ledger {
     type ValidNullifier = Wrap<Nullifier>;
     type ValidUtxoAccumulatorOutput = Wrap<UtxoAccumulatorOutput<Config>>;
-    type SuperPostingKey = (Wrap<()>, ());
+    type SuperPostingKey = (Wrap<AssetValue>, ());
     type Error = SenderLedgerError;
 
     #[inline]
     fn is_unspent(&self, nullifier: Nullifier) -> Result<Self::ValidNullifier, Self::Error> {
-        if self.nullifiers.contains(&nullifier) {
+        if self.spent.contains(&nullifier.nullifier) {
             Err(SenderLedgerError::AssetSpent)
         } else {
             Ok(Wrap(nullifier))
@@ -420,6 +1162,7 @@ impl SenderLedger<Parameters> for Ledger {
         nullifier: Self::ValidNullifier,
     ) -> Result<(), Self::Error> {
         let _ = (utxo_accumulator_output, super_key);
+        self.spent.insert(nullifier.0.nullifier);
         self.nullifiers.insert(nullifier.0);
         Ok(())
     }
@@ -427,7 +1170,7 @@ impl SenderLedger<Parameters> for Ledger {
 
 impl ReceiverLedger<Parameters> for Ledger {
     type ValidUtxo = Wrap<Utxo>;
-    type SuperPostingKey = (Wrap<()>, ());
+    type SuperPostingKey = (Wrap<AssetValue>, ());
     type Error = ReceiverLedgerError;
 
     #[inline]
@@ -463,7 +1206,7 @@ impl TransferLedger<Config> for Ledger {
     type Event = ();
     type ValidSourceAccount = WrapPair<Self::AccountId, AssetValue>;
     type ValidSinkAccount = WrapPair<Self::AccountId, AssetValue>;
-    type ValidProof = Wrap<()>;
+    type ValidProof = Wrap<AssetValue>;
     type SuperPostingKey = ();
     type Error = TransferLedgerError;
 
@@ -547,19 +1290,17 @@ impl TransferLedger<Config> for Ledger {
             posting_key.receivers.len(),
             posting_key.sinks.len(),
         );
-        if transfershape.is_none() {
-            return Err(TransferLedgerError::InvalidShape);
-        }
-        let verifying_context = self
-            .verifying_context
-            .select(transfershape.expect("This never fails because of the check above."));
+        let transfershape = transfershape.ok_or(TransferLedgerError::InvalidShape)?;
+        let fee = self.fee_schedule.fee(transfershape);
+        self.check_fees(&posting_key, fee)?;
+        let verifying_context = self.verifying_context.select(transfershape);
         ProofSystem::verify(
             verifying_context,
             &posting_key.generate_proof_input(),
             &posting_key.proof,
         )
         .map_err(|_| TransferLedgerError::InvalidProof)?;
-        Ok((Wrap(()), ()))
+        Ok((Wrap(fee), ()))
     }
 
     #[inline]
@@ -571,7 +1312,8 @@ impl TransferLedger<Config> for Ledger {
         sinks: Vec<SinkPostingKey<Config, Self>>,
         proof: Self::ValidProof,
     ) -> Result<(), <Self as TransferLedger<Config>>::Error> {
-        let _ = (proof, super_key);
+        let _ = super_key;
+        let Wrap(fee) = proof;
         for WrapPair(account_id, withdraw) in sources {
             *self
                 .accounts
@@ -590,10 +1332,10 @@ impl TransferLedger<Config> for Ledger {
                         asset_id,
                         withdraw,
                     },
-                ))? -= withdraw;
+                ))? -= withdraw + fee;
         }
         for WrapPair(account_id, deposit) in sinks {
-            *self
+            let balance = self
                 .accounts
                 .get_mut(&account_id)
                 .ok_or(TransferLedgerError::InvalidSinkAccount(
@@ -604,7 +1346,8 @@ impl TransferLedger<Config> for Ledger {
                     },
                 ))?
                 .entry(asset_id)
-                .or_default() += deposit;
+                .or_default();
+            *balance = *balance + deposit - fee;
         }
         Ok(())
     }
@@ -614,20 +1357,89 @@ impl TransferLedger<Config> for Ledger {
 pub type SharedLedger = Arc<RwLock<Ledger>>;
 
 /// Ledger Connection
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct LedgerConnection {
     /// Ledger Account
     account: AccountId,
 
     /// Ledger Accessor
     ledger: SharedLedger,
+
+    /// Inclusion Polling Interval
+    ///
+    /// If this is set, posts written to the ledger go through its mempool and the connection
+    /// waits for them to be included in a block, checking their status at this interval.
+    poll_interval: Option<Duration>,
+
+    /// Number of Ledger Reorganizations Reported
+    ///
+    /// Only the reorganizations after these ones are considered by
+    /// [`fork_point`](ledger::Reorganization::fork_point).
+    reorganizations_seen: usize,
+
+    /// Ledger Event Subscription
+    ///
+    /// This is set by the first call to [`wait_for_update`](ledger::Subscribe::wait_for_update).
+    events: Option<broadcast::Receiver<LedgerEvent>>,
 }
 
 impl LedgerConnection {
-    /// Builds a new [`LedgerConnection`] for `account` and `ledger`.
+    /// Builds a new [`LedgerConnection`] for `account` and `ledger` which pushes posts to the
+    /// ledger immediately.
     #[inline]
     pub fn new(account: AccountId, ledger: SharedLedger) -> Self {
-        Self { account, ledger }
+        Self {
+            account,
+            ledger,
+            poll_interval: None,
+            reorganizations_seen: 0,
+            events: None,
+        }
+    }
+
+    /// Builds a new [`LedgerConnection`] for `account` and `ledger` which submits posts to the
+    /// ledger mempool and checks whether they were included every `poll_interval`.
+    ///
+    /// Some task has to produce blocks on `ledger`, like [`block::produce_blocks`], for writes
+    /// over this connection to finish.
+    #[inline]
+    pub fn with_mempool(account: AccountId, ledger: SharedLedger, poll_interval: Duration) -> Self {
+        Self {
+            account,
+            ledger,
+            poll_interval: Some(poll_interval),
+            reorganizations_seen: 0,
+            events: None,
+        }
+    }
+
+    /// Submits `posts` to the ledger mempool, returning their identifiers.
+    #[inline]
+    pub async fn submit(&self, posts: Vec<TransferPost>) -> Vec<PostId> {
+        self.ledger.write().await.submit(self.account, posts)
+    }
+
+    /// Waits until none of the posts with the given `ids` are pending, checking every
+    /// `poll_interval`, and returns their inclusion statuses.
+    #[inline]
+    pub async fn wait_for_inclusion(
+        &self,
+        ids: &[PostId],
+        poll_interval: Duration,
+    ) -> Vec<Option<InclusionStatus>> {
+        loop {
+            let statuses = {
+                let ledger = self.ledger.read().await;
+                ids.iter().map(|id| ledger.status(*id)).collect::<Vec<_>>()
+            };
+            if !statuses
+                .iter()
+                .any(|status| matches!(status, Some(InclusionStatus::Pending)))
+            {
+                return statuses;
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
     }
 }
 
@@ -647,6 +1459,53 @@ impl ledger::Read<SyncData<Config>> for LedgerConnection {
     }
 }
 
+impl Clone for LedgerConnection {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            account: self.account,
+            ledger: self.ledger.clone(),
+            poll_interval: self.poll_interval,
+            reorganizations_seen: self.reorganizations_seen,
+            events: self.events.as_ref().map(broadcast::Receiver::resubscribe),
+        }
+    }
+}
+
+impl ledger::Subscribe<SyncData<Config>> for LedgerConnection {
+    /// Waits for the next event of the ledger.
+    ///
+    /// The first call subscribes to the ledger and returns right away, so that the data posted
+    /// before the subscription is read by the following synchronization.
+    #[inline]
+    fn wait_for_update(&mut self) -> LocalBoxFutureResult<(), Self::Error> {
+        Box::pin(async move {
+            match &mut self.events {
+                Some(events) => {
+                    let _ = events.recv().await;
+                }
+                _ => self.events = Some(self.ledger.read().await.subscribe()),
+            }
+            Ok(())
+        })
+    }
+}
+
+impl ledger::Reorganization<SyncData<Config>> for LedgerConnection {
+    #[inline]
+    fn fork_point<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Option<Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let ledger = self.ledger.read().await;
+            let fork_point = ledger.fork_point(checkpoint, self.reorganizations_seen);
+            self.reorganizations_seen = ledger.reorganization_count();
+            Ok(fork_point)
+        })
+    }
+}
+
 impl ledger::Write<Vec<TransferPost>> for LedgerConnection {
     type Response = bool;
 
@@ -655,7 +1514,19 @@ impl ledger::Write<Vec<TransferPost>> for LedgerConnection {
         &mut self,
         posts: Vec<TransferPost>,
     ) -> LocalBoxFutureResult<Self::Response, Self::Error> {
-        Box::pin(async move { Ok(self.ledger.write().await.push(self.account, posts)) })
+        Box::pin(async move {
+            match self.poll_interval {
+                Some(poll_interval) => {
+                    let ids = self.submit(posts).await;
+                    Ok(self
+                        .wait_for_inclusion(&ids, poll_interval)
+                        .await
+                        .iter()
+                        .all(|status| matches!(status, Some(status) if status.is_included())))
+                }
+                _ => Ok(self.ledger.write().await.push(self.account, posts)),
+            }
+        })
     }
 }
 
@@ -665,3 +1536,34 @@ impl PublicBalanceOracle<Config> for LedgerConnection {
         Box::pin(async move { self.ledger.read().await.public_balances(self.account) })
     }
 }
+
+impl ValidationOracle<Config> for LedgerConnection {
+    type AccountId = AccountId;
+    type SenderError = SenderLedgerError;
+    type ReceiverError = ReceiverLedgerError;
+    type TransferError = TransferLedgerError;
+
+    #[inline]
+    fn try_write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<Result<(), PostError>, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.write().await.try_push(self.account, posts)) })
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output<'s>(
+        &'s self,
+        output: &'s UtxoAccumulatorOutput<Config>,
+    ) -> LocalBoxFuture<'s, Option<bool>> {
+        Box::pin(async move {
+            Some(
+                self.ledger
+                    .read()
+                    .await
+                    .has_matching_utxo_accumulator_output(*output)
+                    .is_ok(),
+            )
+        })
+    }
+}
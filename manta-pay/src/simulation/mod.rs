@@ -23,27 +23,44 @@ use crate::{
     },
     key::KeySecret,
     signer::{base::Signer, functions},
-    simulation::ledger::{AccountId, Ledger, LedgerConnection},
+    simulation::{
+        ledger::{AccountId, Ledger, LedgerConnection, SharedLedger},
+        report::{MeteredSigner, Report, SharedSignerMetrics},
+    },
 };
 use alloc::{format, sync::Arc};
-use core::fmt::Debug;
+use core::{fmt::Debug, future};
 use manta_accounting::{
     self,
     asset::AssetList,
     key::AccountTable,
     wallet::{
         self,
-        test::{self, PublicBalanceOracle},
+        test::{self, PublicBalanceOracle, ValidationError, ValidationOracle},
         Error,
     },
 };
 use manta_crypto::rand::{ChaCha20Rng, CryptoRng, RngCore, SeedableRng};
+use parking_lot::Mutex;
 use tokio::{
     io::{self, AsyncWriteExt},
     sync::RwLock,
 };
 
 pub mod ledger;
+pub mod report;
+
+/// Action Log
+pub type ActionLog = test::ActionLog<Config>;
+
+/// Action Log Record
+pub type Record = test::Record<Config>;
+
+/// Replay Report
+pub type ReplayReport = test::ReplayReport<Config>;
+
+/// Simulation Summary
+pub type Summary = test::Summary<Config>;
 
 /// Samples a new signer.
 #[inline]
@@ -81,6 +98,13 @@ pub struct Simulation {
 
     /// Starting Balance
     pub starting_balance: AssetValue,
+
+    /// Attack Weight
+    ///
+    /// Weight of each adversarial action in the action distribution, which disables them when set
+    /// to zero.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = 0))]
+    pub attack_weight: u64,
 }
 
 impl Simulation {
@@ -90,7 +114,13 @@ impl Simulation {
         test::Config {
             actor_count: self.actor_count,
             actor_lifetime: self.actor_lifetime,
-            action_distribution: Default::default(),
+            action_distribution: test::ActionDistributionPMF {
+                replay_post: self.attack_weight,
+                double_spend: self.attack_weight,
+                stale_root: self.attack_weight,
+                tamper_signature: self.attack_weight,
+                ..Default::default()
+            },
         }
     }
 
@@ -122,18 +152,144 @@ impl Simulation {
     ) where
         R: CryptoRng + RngCore + ?Sized,
     {
+        self.run_with(
+            connections(self.ledger(parameters, utxo_accumulator_model, verifying_context)),
+            move |_| sample_signer(proving_context, parameters, utxo_accumulator_model, rng),
+        )
+        .await
+    }
+
+    /// Builds a new ledger set up for the simulation.
+    #[inline]
+    fn ledger(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        verifying_context: MultiVerifyingContext,
+    ) -> SharedLedger {
         let mut ledger = Ledger::new(
             utxo_accumulator_model.clone(),
             verifying_context,
             parameters.clone(),
         );
         self.setup(&mut ledger);
-        let ledger = Arc::new(RwLock::new(ledger));
-        self.run_with(
-            move |i| LedgerConnection::new(AccountId(i as u64), ledger.clone()),
-            move |_| sample_signer(proving_context, parameters, utxo_accumulator_model, rng),
-        )
-        .await
+        Arc::new(RwLock::new(ledger))
+    }
+
+    /// Runs the simulation against a fresh ledger like [`run`](Self::run), measuring the signers
+    /// and the ledger to build a [`Report`] instead of writing the events to STDOUT.
+    #[inline]
+    pub async fn report<R>(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        proving_context: &MultiProvingContext,
+        verifying_context: MultiVerifyingContext,
+        rng: &mut R,
+    ) -> Report
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let ledger = self.ledger(parameters, utxo_accumulator_model, verifying_context);
+        let metrics = SharedSignerMetrics::default();
+        let report = Mutex::new(Report::default());
+        let summary = self
+            .config()
+            .run_with_summary::<_, _, _, AssetList<AssetId, AssetValue>, _, _, _, _, _, _>(
+                connections(ledger.clone()),
+                |_| {
+                    MeteredSigner::new(
+                        sample_signer(proving_context, parameters, utxo_accumulator_model, rng),
+                        metrics.clone(),
+                    )
+                },
+                |_| ChaCha20Rng::from_entropy(),
+                |event| {
+                    report.lock().record(event.actor, event.step, &event.event);
+                    future::ready(())
+                },
+            )
+            .await
+            .expect("An error occured during the simulation.");
+        let mut report = report.into_inner();
+        let metrics = *metrics.lock();
+        report.proving = metrics.proving;
+        report.sync = metrics.sync;
+        report.verification = *ledger.read().await.verification_latencies();
+        report.balances_match = summary.balances_match();
+        report.summary = summary;
+        report
+    }
+
+    /// Runs the simulation against a fresh ledger like [`run`](Self::run), recording every action
+    /// into an [`ActionLog`] which can be re-executed with [`replay`](Self::replay).
+    ///
+    /// The signers and the actors are all sampled from `seed`, so that the log is enough to rebuild
+    /// the same initial state. The flag returned along with the log is set to `true` whenever the
+    /// funds before and after the simulation match.
+    #[inline]
+    pub async fn record(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        proving_context: &MultiProvingContext,
+        verifying_context: MultiVerifyingContext,
+        seed: u64,
+    ) -> (bool, ActionLog) {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        self.config()
+            .record::<_, _, _, AssetList<AssetId, AssetValue>, ChaCha20Rng, _, _, _, _>(
+                connections(self.ledger(parameters, utxo_accumulator_model, verifying_context)),
+                |_| {
+                    sample_signer(
+                        proving_context,
+                        parameters,
+                        utxo_accumulator_model,
+                        &mut rng,
+                    )
+                },
+                seed,
+                |record| {
+                    let record = format!("{record:?}\n");
+                    async move {
+                        let _ = write_stdout(record.as_bytes()).await;
+                    }
+                },
+            )
+            .await
+            .expect("An error occured during the simulation.")
+    }
+
+    /// Re-executes the actions in `log` against a fresh ledger, reporting the first event which
+    /// differs from the recorded one and whether the funds before and after the replay match.
+    ///
+    /// The signers are sampled from the seed of `log` in the same way as in
+    /// [`record`](Self::record), so `self` must be the same configuration that recorded `log`.
+    #[inline]
+    pub async fn replay(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        proving_context: &MultiProvingContext,
+        verifying_context: MultiVerifyingContext,
+        log: &ActionLog,
+    ) -> ReplayReport {
+        let mut rng = ChaCha20Rng::seed_from_u64(log.seed);
+        self.config()
+            .replay::<_, _, _, AssetList<AssetId, AssetValue>, _, _>(
+                connections(self.ledger(parameters, utxo_accumulator_model, verifying_context)),
+                |_| {
+                    sample_signer(
+                        proving_context,
+                        parameters,
+                        utxo_accumulator_model,
+                        &mut rng,
+                    )
+                },
+                log,
+            )
+            .await
+            .expect("An error occured during the replay.")
     }
 
     /// Runs the simulation with the given ledger connections and signer connections.
@@ -145,12 +301,13 @@ impl Simulation {
     #[inline]
     pub async fn run_with<L, S, GL, GS>(&self, ledger: GL, signer: GS)
     where
-        L: wallet::test::Ledger<Config> + PublicBalanceOracle<Config>,
+        L: wallet::test::Ledger<Config> + PublicBalanceOracle<Config> + ValidationOracle<Config>,
         S: wallet::signer::Connection<Config, Checkpoint = L::Checkpoint>,
         S::Error: Debug,
         GL: FnMut(usize) -> L,
         GS: FnMut(usize) -> S,
         Error<Config, L, S>: Debug,
+        ValidationError<Config, L>: Debug,
     {
         assert!(
             self.config()
@@ -167,6 +324,12 @@ impl Simulation {
     }
 }
 
+/// Returns the builder of the connections of every actor to `ledger`.
+#[inline]
+fn connections(ledger: SharedLedger) -> impl FnMut(usize) -> LedgerConnection {
+    move |i| LedgerConnection::new(AccountId(i as u64), ledger.clone())
+}
+
 /// Writes `bytes` to STDOUT using `tokio`.
 #[inline]
 async fn write_stdout(bytes: &[u8]) -> io::Result<()> {
@@ -0,0 +1,424 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Simulation Report
+//!
+//! The [`Report`] built by [`Simulation::report`](super::Simulation::report) summarizes a
+//! simulation run with the outcome of every action type, the latencies of the signers and the
+//! ledger, and the final balances of every actor.
+
+use crate::{
+    config::{utxo::Address, Config, TransferPost},
+    simulation::Summary,
+};
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
+use core::{fmt::Debug, time::Duration};
+use manta_accounting::{
+    key::AccountIndex,
+    transfer::canonical::TransferShape,
+    wallet::{
+        signer::{
+            Connection, ConsolidateRequest, ConsolidateResult, IdentityRequest, IdentityResponse,
+            PlanRequest, PlanResult, RewindRequest, RewindResult, SignRequest, SignResult,
+            SignWithTransactionDataResult, SyncRequest, SyncResult, TransactionDataRequest,
+            TransactionDataResponse, TransactionHistoryRequest, TransactionHistoryResponse,
+        },
+        test::{ActionType, Event, Ledger},
+        Error,
+    },
+};
+use manta_util::future::LocalBoxFutureResult;
+use parking_lot::Mutex;
+use std::time::Instant;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Latency Statistics
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Latency {
+    /// Number of Measurements
+    pub count: usize,
+
+    /// Total Duration in Milliseconds
+    pub total_ms: f64,
+
+    /// Mean Duration in Milliseconds
+    pub mean_ms: f64,
+
+    /// Shortest Duration in Milliseconds
+    pub min_ms: f64,
+
+    /// Longest Duration in Milliseconds
+    pub max_ms: f64,
+}
+
+impl Latency {
+    /// Adds `duration` to the measurements of `self`.
+    #[inline]
+    pub fn record(&mut self, duration: Duration) {
+        let duration = duration.as_secs_f64() * 1000.0;
+        if self.count == 0 || duration < self.min_ms {
+            self.min_ms = duration;
+        }
+        if duration > self.max_ms {
+            self.max_ms = duration;
+        }
+        self.count += 1;
+        self.total_ms += duration;
+        self.mean_ms = self.total_ms / self.count as f64;
+    }
+}
+
+/// Latency Statistics per [`TransferShape`]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ShapeLatencies {
+    /// [`ToPrivate`](TransferShape::ToPrivate) Latencies
+    pub to_private: Latency,
+
+    /// [`PrivateTransfer`](TransferShape::PrivateTransfer) Latencies
+    pub private_transfer: Latency,
+
+    /// [`ToPublic`](TransferShape::ToPublic) Latencies
+    pub to_public: Latency,
+}
+
+impl ShapeLatencies {
+    /// Returns the latencies of `shape`.
+    #[inline]
+    pub fn get(&self, shape: TransferShape) -> &Latency {
+        match shape {
+            TransferShape::ToPrivate => &self.to_private,
+            TransferShape::PrivateTransfer => &self.private_transfer,
+            TransferShape::ToPublic => &self.to_public,
+        }
+    }
+
+    /// Returns a mutable reference to the latencies of `shape`.
+    #[inline]
+    pub fn get_mut(&mut self, shape: TransferShape) -> &mut Latency {
+        match shape {
+            TransferShape::ToPrivate => &mut self.to_private,
+            TransferShape::PrivateTransfer => &mut self.private_transfer,
+            TransferShape::ToPublic => &mut self.to_public,
+        }
+    }
+
+    /// Adds `duration` to the measurements of `shape`.
+    #[inline]
+    pub fn record(&mut self, shape: TransferShape, duration: Duration) {
+        self.get_mut(shape).record(duration)
+    }
+}
+
+/// Signer Metrics
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SignerMetrics {
+    /// Proving Latencies per Post
+    pub proving: ShapeLatencies,
+
+    /// Synchronization Latencies
+    pub sync: Latency,
+}
+
+impl SignerMetrics {
+    /// Splits `duration` evenly across `posts`, adding each part to the proving latencies of the
+    /// shape of the post.
+    #[inline]
+    fn record_posts<'p, I>(&mut self, posts: I, duration: Duration)
+    where
+        I: ExactSizeIterator<Item = &'p TransferPost>,
+    {
+        if posts.len() == 0 {
+            return;
+        }
+        let duration = duration / posts.len() as u32;
+        for post in posts {
+            if let Some(shape) = TransferShape::from_post(post) {
+                self.proving.record(shape, duration);
+            }
+        }
+    }
+}
+
+/// Shared Signer Metrics
+pub type SharedSignerMetrics = Arc<Mutex<SignerMetrics>>;
+
+/// Metered Signer Connection
+///
+/// This `struct` wraps a signer connection, measuring how long it takes to synchronize and to
+/// build transfer posts. Proving latencies are measured per call, so whenever a call builds more
+/// than one post, its duration is split evenly across them.
+pub struct MeteredSigner<S> {
+    /// Signer Connection
+    signer: S,
+
+    /// Metrics
+    metrics: SharedSignerMetrics,
+}
+
+impl<S> MeteredSigner<S> {
+    /// Builds a new [`MeteredSigner`] over `signer` which adds its measurements to `metrics`.
+    #[inline]
+    pub fn new(signer: S, metrics: SharedSignerMetrics) -> Self {
+        Self { signer, metrics }
+    }
+
+    /// Returns the metrics that `self` adds its measurements to.
+    #[inline]
+    pub fn metrics(&self) -> &SharedSignerMetrics {
+        &self.metrics
+    }
+
+    /// Returns the underlying signer connection, dropping the metrics.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.signer
+    }
+}
+
+impl<S> Connection<Config> for MeteredSigner<S>
+where
+    S: Connection<Config>,
+{
+    type AssetMetadata = S::AssetMetadata;
+    type Checkpoint = S::Checkpoint;
+    type Error = S::Error;
+
+    #[inline]
+    fn sync(
+        &mut self,
+        request: SyncRequest<Config, Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<SyncResult<Config, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sync(request).await?;
+            self.metrics.lock().sync.record(start.elapsed());
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn sign(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<SignResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sign(request).await?;
+            if let Ok(response) = &result {
+                self.metrics
+                    .lock()
+                    .record_posts(response.posts.iter(), start.elapsed());
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn address(&mut self) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
+        self.signer.address()
+    }
+
+    #[inline]
+    fn account_address(
+        &mut self,
+        account: AccountIndex,
+    ) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
+        self.signer.account_address(account)
+    }
+
+    #[inline]
+    fn transaction_data(
+        &mut self,
+        request: TransactionDataRequest<Config>,
+    ) -> LocalBoxFutureResult<TransactionDataResponse<Config>, Self::Error> {
+        self.signer.transaction_data(request)
+    }
+
+    #[inline]
+    fn identity_proof(
+        &mut self,
+        request: IdentityRequest<Config>,
+    ) -> LocalBoxFutureResult<IdentityResponse<Config>, Self::Error> {
+        self.signer.identity_proof(request)
+    }
+
+    #[inline]
+    fn sign_with_transaction_data(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<SignWithTransactionDataResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sign_with_transaction_data(request).await?;
+            if let Ok(response) = &result {
+                self.metrics
+                    .lock()
+                    .record_posts(response.0.iter().map(|(post, _)| post), start.elapsed());
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn transaction_history(
+        &mut self,
+        request: TransactionHistoryRequest,
+    ) -> LocalBoxFutureResult<TransactionHistoryResponse<Config, Self::Checkpoint>, Self::Error>
+    {
+        self.signer.transaction_history(request)
+    }
+
+    #[inline]
+    fn consolidate(
+        &mut self,
+        request: ConsolidateRequest<Config>,
+    ) -> LocalBoxFutureResult<ConsolidateResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.consolidate(request).await?;
+            if let Ok(response) = &result {
+                self.metrics
+                    .lock()
+                    .record_posts(response.posts.iter(), start.elapsed());
+            }
+            Ok(result)
+        })
+    }
+
+    #[inline]
+    fn plan(
+        &mut self,
+        request: PlanRequest<Config>,
+    ) -> LocalBoxFutureResult<PlanResult<Config>, Self::Error> {
+        self.signer.plan(request)
+    }
+
+    #[inline]
+    fn rewind(
+        &mut self,
+        request: RewindRequest<Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<RewindResult<Config, Self::Checkpoint>, Self::Error> {
+        self.signer.rewind(request)
+    }
+}
+
+/// Action Statistics
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ActionStatistics {
+    /// Number of Actions Taken
+    pub count: usize,
+
+    /// Number of Actions Rejected by the Ledger
+    pub rejected: usize,
+
+    /// Number of Actions which Failed with an Error
+    pub errors: usize,
+}
+
+/// Failed Action
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Failure {
+    /// Actor Index
+    pub actor: usize,
+
+    /// Step Index of the Actor
+    pub step: usize,
+
+    /// Action Type
+    pub action: ActionType,
+
+    /// Debug Representation of the Error
+    pub error: String,
+}
+
+/// Simulation Report
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// Statistics per Action Type
+    pub actions: BTreeMap<ActionType, ActionStatistics>,
+
+    /// Actions which Failed with an Error
+    pub failures: Vec<Failure>,
+
+    /// Proving Latencies per Post
+    pub proving: ShapeLatencies,
+
+    /// Verification Latencies per Post
+    pub verification: ShapeLatencies,
+
+    /// Signer Synchronization Latencies
+    pub sync: Latency,
+
+    /// Balances before and after the Simulation
+    pub summary: Summary,
+
+    /// Flag set to `true` whenever the funds before and after the simulation match
+    pub balances_match: bool,
+}
+
+impl Report {
+    /// Adds the outcome of the `event` emitted by `actor` at `step` to `self`.
+    #[inline]
+    pub fn record<L, S>(&mut self, actor: usize, step: usize, event: &Event<Config, L, S>)
+    where
+        L: Ledger<Config>,
+        S: Connection<Config, Checkpoint = L::Checkpoint>,
+        Error<Config, L, S>: Debug,
+    {
+        let statistics = self.actions.entry(event.action).or_default();
+        statistics.count += 1;
+        match &event.value {
+            Ok(true) => {}
+            Ok(false) => statistics.rejected += 1,
+            Err(err) => {
+                statistics.errors += 1;
+                self.failures.push(Failure {
+                    actor,
+                    step,
+                    action: event.action,
+                    error: format!("{err:?}"),
+                });
+            }
+        }
+    }
+}
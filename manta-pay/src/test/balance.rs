@@ -16,15 +16,15 @@
 
 //! Manta Pay Wallet Balance Testing
 
-use crate::config::{AssetId, AssetValue};
+use crate::config::{Asset, AssetId, AssetValue};
 use manta_accounting::{
-    asset,
+    asset::{self, AssetMap, BranchAndBound, CoinSelection, NonFungible, SelectionStrategy},
     wallet::balance::{
         self,
         test::{assert_full_withdraw_should_remove_entry, assert_valid_withdraw},
     },
 };
-use manta_crypto::rand::OsRng;
+use manta_crypto::rand::{OsRng, Rand};
 
 /// Asset List Type
 type AssetList = asset::AssetList<AssetId, AssetValue>;
@@ -97,3 +97,163 @@ fn hash_map_valid_withdraw() {
 fn hash_map_full_withdraw() {
     assert_full_withdraw_should_remove_entry::<_, _, HashMapBalanceState, _>(&mut OsRng);
 }
+
+/// B-Tree Asset Map Type
+type BTreeAssetMap = asset::BTreeAssetMap<u8, AssetId, AssetValue>;
+
+/// Builds a [`BTreeAssetMap`] holding the assets `values` of `id`, along with some zero assets of
+/// `id` and some assets of another asset id which should never be selected.
+fn sample_asset_map(id: AssetId, values: &[AssetValue]) -> BTreeAssetMap {
+    let mut assets = BTreeAssetMap::default();
+    let mut rng = OsRng;
+    assets.insert_all_same(id, (0..).zip(values.iter().copied()));
+    assets.insert_zeroes(id, [100, 101]);
+    assets.insert_all_same(rng.gen(), [(200, 1), (201, 1000)]);
+    assets
+}
+
+/// Selects `target` of `id` from `assets` with `coin_selection`, checking that the selection is
+/// consistent, and returning the selected values in increasing order and the change.
+fn select<T>(
+    assets: &BTreeAssetMap,
+    id: AssetId,
+    target: AssetValue,
+    coin_selection: T,
+) -> (Vec<AssetValue>, AssetValue)
+where
+    T: SelectionStrategy<u8, AssetValue>,
+{
+    let selection = assets.select_with(&Asset::new(id, target), &coin_selection, &mut OsRng);
+    let mut values = selection
+        .values
+        .iter()
+        .map(|(key, value)| {
+            assert!(
+                *key < 100,
+                "Only non-zero assets of {id:?} can be selected."
+            );
+            *value
+        })
+        .collect::<Vec<_>>();
+    if !values.is_empty() {
+        assert_eq!(
+            values.iter().sum::<AssetValue>(),
+            target + selection.change,
+            "The selected values should add up to the target and the change."
+        );
+    }
+    values.sort();
+    (values, selection.change)
+}
+
+/// Checks the assets chosen by each of the [`CoinSelection`] strategies.
+#[test]
+fn coin_selection_strategies() {
+    let id = OsRng.gen();
+    let assets = sample_asset_map(id, &[1, 2, 3, 5, 8, 13]);
+    assert_eq!(
+        select(&assets, id, 11, CoinSelection::Greedy),
+        (vec![13], 2),
+        "Greedy selection should pick the smallest asset covering the target."
+    );
+    assert_eq!(
+        select(&assets, id, 11, CoinSelection::SmallestFirst),
+        (vec![1, 2, 3, 5], 0),
+        "Smallest-first selection should pick the smallest assets first."
+    );
+    assert_eq!(
+        select(&assets, id, 11, CoinSelection::LargestFirst),
+        (vec![13], 2),
+        "Largest-first selection should pick the largest assets first."
+    );
+    assert_eq!(
+        select(&assets, id, 20, CoinSelection::LargestFirst),
+        (vec![8, 13], 1),
+        "Largest-first selection should pick the largest assets first."
+    );
+    assert_eq!(
+        select(&assets, id, 20, CoinSelection::BranchAndBound).1,
+        0,
+        "Branch-and-bound selection should find an exact match."
+    );
+    assert_eq!(
+        select(&assets, id, 32, CoinSelection::BranchAndBound),
+        (vec![1, 2, 3, 5, 8, 13], 0),
+        "Branch-and-bound selection should find an exact match."
+    );
+    let (values, change) = select(&assets, id, 11, CoinSelection::Random);
+    assert!(
+        !values.is_empty() && change < *values.iter().max().expect("Values are not empty."),
+        "Random selection should stop as soon as the target is covered."
+    );
+    for coin_selection in [
+        CoinSelection::Greedy,
+        CoinSelection::SmallestFirst,
+        CoinSelection::LargestFirst,
+        CoinSelection::BranchAndBound,
+        CoinSelection::Random,
+    ] {
+        assert_eq!(
+            select(&assets, id, 32, coin_selection),
+            (vec![1, 2, 3, 5, 8, 13], 0),
+            "Selecting the whole balance should select every asset."
+        );
+        assert!(
+            select(&assets, id, 33, coin_selection).0.is_empty(),
+            "Selections over the balance should be empty."
+        );
+    }
+}
+
+/// Checks that [`BranchAndBound`] selection gives up after [`BranchAndBound::MAX_STEPS`] steps on
+/// a long list of candidates, without running out of stack, and falls back to another strategy.
+#[test]
+fn branch_and_bound_step_limit() {
+    let candidates = (0..2 * BranchAndBound::MAX_STEPS)
+        .map(|key| (key, 1))
+        .collect::<Vec<(usize, AssetValue)>>();
+    let target = BranchAndBound::MAX_STEPS as AssetValue + 1;
+    let selection = BranchAndBound
+        .select(&target, candidates, &mut OsRng)
+        .expect("The candidates cover the target.");
+    assert_eq!(
+        selection.len() as AssetValue,
+        target,
+        "The fallback strategy should select exactly enough assets to cover the target."
+    );
+}
+
+/// Checks that the [`NonFungible`] strategy never splits assets of unit value.
+#[test]
+fn non_fungible_coin_selection() {
+    let id = OsRng.gen();
+    let assets = sample_asset_map(id, &[1, 1, 4, 6]);
+    assert_eq!(
+        select(&assets, id, 5, CoinSelection::SmallestFirst),
+        (vec![1, 1, 4], 1),
+        "Smallest-first selection should merge the unit assets."
+    );
+    for coin_selection in [
+        CoinSelection::Greedy,
+        CoinSelection::SmallestFirst,
+        CoinSelection::LargestFirst,
+        CoinSelection::BranchAndBound,
+        CoinSelection::Random,
+    ] {
+        let coin_selection = NonFungible(coin_selection);
+        assert_eq!(
+            select(&assets, id, 1, coin_selection),
+            (vec![1], 0),
+            "A unit target should be paid with exactly one unit asset."
+        );
+        assert_eq!(
+            select(&assets, id, 10, coin_selection),
+            (vec![4, 6], 0),
+            "Unit assets should never be selected with other assets."
+        );
+        assert!(
+            select(&assets, id, 12, coin_selection).0.is_empty(),
+            "Unit assets should never be merged to cover a larger target."
+        );
+    }
+}
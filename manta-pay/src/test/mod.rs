@@ -33,3 +33,26 @@ pub mod payment;
 #[cfg_attr(doc_cfg, doc(cfg(all(feature = "groth16", feature = "simulation"))))]
 #[cfg(test)]
 pub mod signer;
+
+#[cfg(all(feature = "groth16", feature = "simulation"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "groth16", feature = "simulation"))))]
+#[cfg(test)]
+pub mod wallet;
+
+#[cfg(all(
+    feature = "groth16",
+    feature = "http",
+    feature = "server",
+    feature = "simulation"
+))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(
+        feature = "groth16",
+        feature = "http",
+        feature = "server",
+        feature = "simulation"
+    )))
+)]
+#[cfg(test)]
+pub mod server;
@@ -0,0 +1,147 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer Server Testing Suite
+
+use crate::{
+    config::Address,
+    parameters::load_parameters,
+    signer::{
+        client::{
+            http,
+            network::{Network, NetworkSpecific},
+            websocket::{self, ErrorResponse},
+        },
+        server::Server,
+        GetRequest, TransactionHistoryRequest,
+    },
+    simulation::sample_signer,
+};
+use futures::{SinkExt, StreamExt};
+use manta_accounting::wallet::signer::Connection;
+use manta_crypto::rand::OsRng;
+use tokio::{net::TcpListener, task::yield_now};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Checks that the HTTP and WebSocket clients can reach the signers hosted by the reference
+/// [`Server`], that requests for networks without a signer are rejected, and that invalid
+/// WebSocket requests are answered with an [`ErrorResponse`] without closing the connection.
+#[tokio::test]
+async fn signer_server_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let address = signer.address(Default::default());
+    let server = Server::new(NetworkSpecific {
+        dolphin: Some(signer),
+        calamari: None,
+        manta: None,
+    });
+    let http_address = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Unable to reserve a local address.");
+    tokio::spawn(server.clone().serve(http_address.to_string()));
+    let websocket_listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Unable to bind a local address.");
+    let websocket_address = websocket_listener
+        .local_addr()
+        .expect("Unable to read the local address.");
+    tokio::spawn(server.serve_websocket(Network::Dolphin, websocket_listener));
+    let mut client = http::Client::new(format!("http://{http_address}/"))
+        .expect("Unable to build the HTTP client.");
+    client.set_network(Some(Network::Dolphin));
+    let mut http_result = client.address().await;
+    for _ in 0..1000 {
+        if http_result.is_ok() {
+            break;
+        }
+        yield_now().await;
+        http_result = client.address().await;
+    }
+    assert_eq!(
+        http_result.expect("The HTTP server should be reachable."),
+        address
+    );
+    assert_eq!(
+        client
+            .transaction_history(TransactionHistoryRequest::default())
+            .await
+            .expect("The HTTP server should answer history requests.")
+            .total,
+        0
+    );
+    client.set_network(Some(Network::Calamari));
+    assert!(
+        client.address().await.is_err(),
+        "Requests for networks without a signer should fail."
+    );
+    let mut client = websocket::Client::new(format!("ws://{websocket_address}"))
+        .await
+        .expect("Unable to connect to the WebSocket server.");
+    assert_eq!(
+        client
+            .address()
+            .await
+            .expect("The WebSocket server should answer address requests."),
+        address
+    );
+    let (mut stream, _) = connect_async(format!("ws://{websocket_address}"))
+        .await
+        .expect("Unable to connect to the WebSocket server.");
+    for request in [
+        r#"{"command":"unknown","request":null}"#,
+        r#"{"command":"address","request":"#,
+    ] {
+        stream
+            .send(Message::Text(request.into()))
+            .await
+            .expect("Unable to send the request.");
+        match stream.next().await {
+            Some(Ok(Message::Text(response))) => assert!(
+                serde_json::from_str::<ErrorResponse>(&response).is_ok(),
+                "Invalid requests should be answered with an error response."
+            ),
+            response => panic!("Expected an error response, found: {response:?}."),
+        }
+    }
+    stream
+        .send(Message::Text(
+            serde_json::to_string(&websocket::Request {
+                command: "address",
+                request: GetRequest::Get,
+            })
+            .expect("Unable to serialize the request."),
+        ))
+        .await
+        .expect("Unable to send the request.");
+    match stream.next().await {
+        Some(Ok(Message::Text(response))) => assert_eq!(
+            serde_json::from_str::<Option<Address>>(&response)
+                .expect("Unable to deserialize the response."),
+            address,
+            "The connection should stay open after an invalid request."
+        ),
+        response => panic!("Expected an address response, found: {response:?}."),
+    }
+}
@@ -17,22 +17,46 @@
 //! Signer Testing Suite
 
 use crate::{
-    config::{Asset, Config},
+    config::{
+        utxo::{Checkpoint, Memo, MerkleTreeConfiguration, MEMO_SIZE},
+        Asset, Config, FullParameters, Parameters, Receiver, TransferPost, Utxo,
+    },
     key::Mnemonic,
-    parameters::load_parameters,
+    parameters::{load_parameters, load_transfer_parameters},
     signer::{
-        base::identity_verification,
-        functions::{address_from_mnemonic, authorization_context_from_mnemonic},
+        base::{identity_verification, Signer},
+        functions::{
+            accounts_from_mnemonic, address_from_mnemonic, authorization_context_from_mnemonic,
+            new_signer_from_model, new_view_only_signer, view_only_transaction_data,
+        },
+        SignRequest, SyncRequest,
+    },
+    simulation::{
+        ledger::{AccountId, Ledger},
+        sample_signer,
+    },
+};
+use alloc::collections::BTreeSet;
+use manta_accounting::{
+    key::{AccountIndex, DeriveAddress},
+    transfer::{
+        canonical::{Transaction, TransferShape},
+        utxo::{DeriveDecryptionKey, UtxoReconstruct},
+        IdentifiedAsset, Identifier, InvalidAuthorizationSignature, MEMO_ENCODING_VERSION,
+    },
+    wallet::signer::{
+        history::{self, EntryKind},
+        BalanceUpdate, SignError, SyncData, TransactionHistoryRequest,
     },
-    simulation::sample_signer,
 };
-use manta_accounting::transfer::{canonical::Transaction, IdentifiedAsset, Identifier};
 use manta_crypto::{
+    accumulator::{Accumulator, ItemHashFunction},
     algebra::HasGenerator,
     arkworks::constraint::fp::Fp,
-    rand::{fuzz::Fuzz, OsRng, Rand},
+    merkle_tree::forest::Configuration as _,
+    rand::{fuzz::Fuzz, FromEntropy, OsRng, Rand},
 };
-use manta_util::vec::VecExt;
+use manta_util::{codec::Encode, vec::VecExt};
 
 /// Checks the generation and verification of [`IdentityProof`](manta_accounting::transfer::IdentityProof)s.
 #[test]
@@ -52,7 +76,9 @@ fn identity_proof_test() {
     let identity_proof = signer
         .identity_proof(virtual_asset)
         .expect("Error producing identity proof");
-    let address = signer.address().expect("Sampled signer has a spending key");
+    let address = signer
+        .address(Default::default())
+        .expect("Sampled signer has a spending key");
     assert!(
         identity_verification(
             &identity_proof,
@@ -131,7 +157,7 @@ fn transaction_data_test() {
     );
     let transaction = Transaction::ToPrivate(rng.gen());
     let response = signer
-        .sign_with_transaction_data(transaction)
+        .sign_with_transaction_data(SignRequest::new(transaction))
         .expect("Signing a ToPrivate transaction is not allowed to fail.")
         .0
         .take_first();
@@ -139,7 +165,9 @@ fn transaction_data_test() {
     assert!(
         response.1.check_transaction_data(
             &parameters,
-            &signer.address().expect("Sampled signer has a spending key"),
+            &signer
+                .address(Default::default())
+                .expect("Sampled signer has a spending key"),
             &vec![utxo]
         ),
         "Invalid Transaction Data"
@@ -167,3 +195,1084 @@ pub fn derive_address_works() {
         "Both receiving keys should be the same"
     );
 }
+
+/// Checks that newly created accounts derive addresses which are distinct from the default
+/// account and that only existing accounts have an address.
+#[test]
+fn multiple_account_addresses() {
+    let mut rng = OsRng;
+    let parameters = load_transfer_parameters();
+    let mut accounts = accounts_from_mnemonic(Mnemonic::sample(&mut rng));
+    let account = accounts.create_account();
+    assert_eq!(account, AccountIndex::new(1), "Invalid new account index.");
+    let default_address = accounts.get_default().address(&parameters);
+    let address = accounts
+        .get(account)
+        .expect("The account was just created.")
+        .address(&parameters);
+    assert_ne!(
+        default_address, address,
+        "Different accounts should have different addresses."
+    );
+    assert!(
+        accounts.get(AccountIndex::new(2)).is_none(),
+        "Only created accounts should be available."
+    );
+}
+
+/// Checks that a view-only signer derives the same address as the mnemonic it is watching and
+/// refuses to sign transactions or generate identity proofs.
+#[test]
+fn view_only_signer_has_no_spending_authority() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mnemonic = Mnemonic::sample(&mut rng);
+    let mut signer = Signer::new_view_only(
+        parameters.clone(),
+        proving_context,
+        Accumulator::empty(&utxo_accumulator_model),
+        authorization_context_from_mnemonic(mnemonic.clone(), &parameters),
+        FromEntropy::from_entropy(),
+    );
+    assert!(signer.is_view_only(), "The signer should be view-only.");
+    assert_eq!(
+        signer.address(Default::default()),
+        Some(address_from_mnemonic(mnemonic, &parameters)),
+        "The view-only signer should watch the address of the mnemonic."
+    );
+    assert!(
+        matches!(
+            signer.sign(SignRequest::new(Transaction::ToPrivate(rng.gen()))),
+            Err(SignError::NoSpendingAuthority)
+        ),
+        "View-only signers cannot sign transactions."
+    );
+    let identifier = Identifier::<Config>::new(false, rng.gen());
+    assert!(
+        matches!(
+            signer.identity_proof(IdentifiedAsset::<Config>::new(identifier, rng.gen())),
+            Err(SignError::NoSpendingAuthority)
+        ),
+        "View-only signers cannot generate identity proofs."
+    );
+}
+
+/// Checks that signing a transaction records it as pending in the transaction history of the
+/// signing account until it is observed on the ledger.
+#[test]
+fn signed_transaction_is_pending_in_history() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset = Asset::new(rng.gen(), rng.gen());
+    signer
+        .sign(SignRequest::new(Transaction::ToPrivate(asset)))
+        .expect("Signing a ToPrivate transaction should succeed.");
+    let pending = signer.state().history().pending();
+    assert_eq!(
+        pending.len(),
+        1,
+        "The signed transaction should be pending."
+    );
+    assert_eq!(
+        pending[0].transfers,
+        vec![(EntryKind::ToPrivate, asset)],
+        "Invalid pending transfers."
+    );
+    let response = signer.transaction_history(TransactionHistoryRequest {
+        account: Default::default(),
+        offset: 0,
+        limit: 10,
+    });
+    assert_eq!(
+        response.total, 0,
+        "Pending transactions should not be part of the confirmed history."
+    );
+}
+
+/// Returns the [`SyncData`] which a signer observes once `posts` are on the ledger.
+#[inline]
+fn sync_data(posts: Vec<TransferPost>) -> SyncData<Config> {
+    let mut data = SyncData::default();
+    for post in posts {
+        data.utxo_note_data.extend(
+            post.body
+                .receiver_posts
+                .into_iter()
+                .map(|post| (post.utxo, post.note)),
+        );
+        data.nullifier_data.extend(
+            post.body
+                .sender_posts
+                .into_iter()
+                .map(|post| post.nullifier),
+        );
+    }
+    data
+}
+
+/// Checks that pending transactions enter the transaction history once they are observed on the
+/// ledger.
+#[test]
+fn pending_transaction_is_confirmed() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let deposit = Asset::new(asset_id, 100);
+    let posts = signer
+        .sign(SignRequest::new(Transaction::ToPrivate(deposit)))
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    let origin_checkpoint = signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: sync_data(posts),
+        })
+        .expect("Synchronizing the deposit should succeed.")
+        .checkpoint;
+    assert!(
+        signer.state().history().pending().is_empty(),
+        "The deposit should no longer be pending."
+    );
+    let address = address_from_mnemonic(Mnemonic::sample(&mut rng), &parameters);
+    let payment = Asset::new(asset_id, 30);
+    let posts = signer
+        .sign(SignRequest::new(Transaction::PrivateTransfer(
+            payment, address,
+        )))
+        .expect("Signing a PrivateTransfer transaction should succeed.")
+        .posts;
+    let checkpoint = signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint,
+            data: sync_data(posts),
+        })
+        .expect("Synchronizing the payment should succeed.")
+        .checkpoint;
+    assert!(
+        signer
+            .state()
+            .history()
+            .pending()
+            .iter()
+            .all(|pending| pending.confirmed),
+        "The payment should be confirmed."
+    );
+    assert_eq!(
+        signer.state().history().entries(Default::default())[1..],
+        [history::Entry {
+            kind: EntryKind::Sent(Some(address)),
+            asset: payment,
+            memo: None,
+            checkpoint,
+        }],
+        "The payment should be confirmed with the checkpoint it was observed at."
+    );
+    assert_eq!(
+        signer.state().history().entries(Default::default())[0].kind,
+        EntryKind::ToPrivate,
+        "The deposit should be confirmed."
+    );
+}
+
+/// Checks that a pending transaction is dropped from the transaction history once another
+/// transaction spending the same assets is observed on the ledger.
+#[test]
+fn pending_transaction_is_conflicted() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let posts = signer
+        .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+            asset_id, 100,
+        ))))
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    let origin_checkpoint = signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: sync_data(posts),
+        })
+        .expect("Synchronizing the deposit should succeed.")
+        .checkpoint;
+    let mut sign_payment = |value| {
+        let address = address_from_mnemonic(Mnemonic::sample(&mut rng), &parameters);
+        let payment = Asset::new(asset_id, value);
+        let posts = signer
+            .sign(SignRequest::new(Transaction::PrivateTransfer(
+                payment, address,
+            )))
+            .expect("Signing a PrivateTransfer transaction should succeed.")
+            .posts;
+        (EntryKind::Sent(Some(address)), payment, posts)
+    };
+    sign_payment(30);
+    let (kind, payment, posts) = sign_payment(40);
+    assert_eq!(
+        signer.state().history().pending().len(),
+        2,
+        "Both payments should be pending."
+    );
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint,
+            data: sync_data(posts),
+        })
+        .expect("Synchronizing the second payment should succeed.");
+    let pending = signer.state().history().pending();
+    assert_eq!(
+        pending.len(),
+        1,
+        "The first payment spends the same asset as the second one and can no longer be posted."
+    );
+    assert!(
+        pending[0].confirmed,
+        "The second payment should be confirmed."
+    );
+    let entries = signer.state().history().entries(Default::default());
+    assert_eq!(
+        entries.len(),
+        2,
+        "Only the second payment should be confirmed."
+    );
+    assert_eq!(
+        (&entries[1].kind, &entries[1].asset),
+        (&kind, &payment),
+        "Only the second payment should be confirmed."
+    );
+}
+
+/// Checks that a view-only signer built with [`new_view_only_signer`] observes the deposits and
+/// spends of the account it watches, and recovers the assets of their posts with
+/// [`view_only_transaction_data`].
+#[test]
+fn view_only_signer_sync_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mnemonic = Mnemonic::sample(&mut rng);
+    let mut signer = new_signer_from_model(
+        parameters.clone(),
+        proving_context.clone(),
+        &utxo_accumulator_model,
+    );
+    signer.load_accounts(accounts_from_mnemonic(mnemonic.clone()));
+    signer.update_authorization_context();
+    let mut view_only = new_view_only_signer(
+        FullParameters::new(parameters.clone(), utxo_accumulator_model),
+        proving_context,
+        authorization_context_from_mnemonic(mnemonic, &parameters),
+        &None,
+    );
+    let sync = |signer: &mut Signer, origin_checkpoint: Checkpoint, posts: &[TransferPost]| {
+        signer
+            .sync(SyncRequest {
+                account: Default::default(),
+                origin_checkpoint,
+                data: sync_data(posts.to_vec()),
+            })
+            .expect("Synchronizing should succeed.")
+    };
+    let transaction_data = |signer: &Signer, post: &TransferPost| {
+        view_only_transaction_data(
+            signer.parameters(),
+            &mut signer.state().authorization_contexts().clone(),
+            post.clone(),
+        )
+        .expect("The view-only signer should own the assets of the post.")
+        .open()
+        .into_iter()
+        .map(|(_, asset)| asset)
+        .collect::<Vec<_>>()
+    };
+    let asset_id = rng.gen();
+    let deposit = Asset::new(asset_id, 100);
+    let posts = signer
+        .sign(SignRequest::new(Transaction::ToPrivate(deposit)))
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    let checkpoint = sync(&mut signer, Default::default(), &posts).checkpoint;
+    let response = sync(&mut view_only, Default::default(), &posts);
+    assert_eq!(
+        response.balance_update,
+        BalanceUpdate::Partial {
+            deposit: vec![deposit],
+            withdraw: Vec::new(),
+        },
+        "The view-only signer should observe the deposit."
+    );
+    assert_eq!(
+        transaction_data(&view_only, &posts[0]),
+        [deposit],
+        "The view-only signer should recover the deposited asset."
+    );
+    let posts = signer
+        .sign(SignRequest::new(Transaction::ToPublic(Asset::new(
+            asset_id, 30,
+        ))))
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    sync(&mut signer, checkpoint, &posts);
+    let change = Asset::new(asset_id, 70);
+    assert_eq!(
+        sync(&mut view_only, response.checkpoint, &posts).balance_update,
+        BalanceUpdate::Partial {
+            deposit: vec![change],
+            withdraw: vec![deposit],
+        },
+        "The view-only signer should observe the spend of the deposit and its change."
+    );
+    assert_eq!(
+        transaction_data(&view_only, &posts[0]),
+        [change],
+        "The view-only signer should recover the change of the spend."
+    );
+}
+
+/// Checks that a [`Transaction::BatchPrivateTransfer`] is signed as one valid
+/// [`PrivateTransfer`](manta_accounting::transfer::canonical::PrivateTransfer) per payment, each
+/// of which pays its recipient, without synchronizing in between.
+#[test]
+fn batch_private_transfer_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let deposit = signer
+        .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+            asset_id, 100,
+        ))))
+        .expect("Signing a ToPrivate transaction should succeed.");
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: SyncData {
+                utxo_note_data: deposit
+                    .posts
+                    .into_iter()
+                    .flat_map(|post| post.body.receiver_posts)
+                    .map(|post| (post.utxo, post.note))
+                    .collect(),
+                nullifier_data: Vec::new(),
+            },
+        })
+        .expect("Synchronizing the deposit should succeed.");
+    let mnemonics = [(); 3].map(|_| Mnemonic::sample(&mut rng));
+    let payments = mnemonics
+        .iter()
+        .zip([10, 20, 30])
+        .map(|(mnemonic, value)| {
+            (
+                Asset::new(asset_id, value),
+                address_from_mnemonic(mnemonic.clone(), &parameters),
+            )
+        })
+        .collect::<Vec<_>>();
+    let posts = signer
+        .sign(SignRequest::new(Transaction::BatchPrivateTransfer(
+            payments.clone(),
+        )))
+        .expect("Signing a batch of private transfers should succeed.")
+        .posts;
+    assert_eq!(posts.len(), 3, "Each payment should be signed in one post.");
+    for ((post, mnemonic), (asset, _)) in posts.iter().zip(mnemonics).zip(payments) {
+        assert_eq!(
+            TransferShape::from_post(post),
+            Some(TransferShape::PrivateTransfer),
+            "Each payment should be a private transfer."
+        );
+        assert!(
+            post.has_valid_proof(&verifying_context.private_transfer)
+                .expect("Unable to verify the proof."),
+            "Invalid private transfer proof."
+        );
+        let decryption_key = parameters.derive_decryption_key(
+            &mut authorization_context_from_mnemonic(mnemonic, &parameters),
+        );
+        let receiver_post = post.body.receiver_posts[1].clone();
+        assert_eq!(
+            parameters
+                .open_with_check(&decryption_key, &receiver_post.utxo, receiver_post.note)
+                .map(|(_, asset)| asset),
+            Some(asset),
+            "The recipient should be able to open its payment."
+        );
+    }
+}
+
+/// Returns `true` if a post of `posts` spends a UTXO which shares a shard with the receiver of an
+/// earlier post, where the first post paying each asset spends the matching UTXO in `deposits`
+/// and every other post spends the change of the previous one.
+#[inline]
+fn spends_after_shard_collision(
+    parameters: &Parameters,
+    deposits: &[Utxo],
+    posts: &[TransferPost],
+    payments_per_asset: usize,
+) -> bool {
+    let shard = |utxo| MerkleTreeConfiguration::tree_index(&parameters.item_hash(utxo, &mut ()));
+    (0..posts.len()).any(|index| {
+        let spent = if index % payments_per_asset == 0 {
+            &deposits[index / payments_per_asset]
+        } else {
+            &posts[index - 1].body.receiver_posts[0].utxo
+        };
+        posts[..index]
+            .iter()
+            .any(|earlier| shard(&earlier.body.receiver_posts[1].utxo) == shard(spent))
+    })
+}
+
+/// Checks that every post of a [`Transaction::BatchPrivateTransfer`] paying several assets is
+/// accepted by the ledger in order, even when a post spends a UTXO whose shard received the
+/// outputs of earlier posts in the same batch.
+#[test]
+fn batch_private_transfer_ledger_test() {
+    const PAYMENTS_PER_ASSET: usize = 8;
+    // NOTE: About one batch in three spends after a shard collision, so the chance that none of
+    //       the attempts does is negligible.
+    const MAX_ATTEMPTS: usize = 64;
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    let asset_ids = [rng.gen(), rng.gen()];
+    let mut deposits = Vec::new();
+    for asset_id in asset_ids {
+        ledger.set_public_balance(AccountId(0), asset_id, 1000);
+        let posts = signer
+            .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+                asset_id, 1000,
+            ))))
+            .expect("Signing a ToPrivate transaction should succeed.")
+            .posts;
+        deposits.push(posts[0].body.receiver_posts[0].utxo);
+        assert_eq!(ledger.try_push(AccountId(0), posts), Ok(()));
+    }
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: ledger.pull(&Default::default()).data,
+        })
+        .expect("Synchronizing the deposits should succeed.");
+    let address = address_from_mnemonic(Mnemonic::sample(&mut rng), &parameters);
+    let payments = asset_ids
+        .into_iter()
+        .flat_map(|asset_id| {
+            (1..=PAYMENTS_PER_ASSET as u128)
+                .map(move |value| (Asset::new(asset_id, value), address))
+        })
+        .collect::<Vec<_>>();
+    let posts = (0..MAX_ATTEMPTS)
+        .find_map(|_| {
+            let posts = signer
+                .sign(SignRequest::new(Transaction::BatchPrivateTransfer(
+                    payments.clone(),
+                )))
+                .expect("Signing a batch of private transfers should succeed.")
+                .posts;
+            assert_eq!(
+                posts.len(),
+                payments.len(),
+                "Each payment should be signed in one post."
+            );
+            spends_after_shard_collision(&parameters, &deposits, &posts, PAYMENTS_PER_ASSET)
+                .then_some(posts)
+        })
+        .unwrap_or_else(|| {
+            panic!(
+                "No batch spending after a shard collision was signed in {MAX_ATTEMPTS} attempts."
+            )
+        });
+    assert_eq!(
+        ledger.try_push(AccountId(0), posts),
+        Ok(()),
+        "The ledger should accept every post of the batch."
+    );
+}
+
+/// Checks that a memo attached to a [`PrivateTransfer`](manta_accounting::transfer::canonical::PrivateTransfer)
+/// is surfaced to the recipient, and only to the recipient, during synchronization, and that the
+/// memo is signed along with the versioned encoding of the post body.
+#[test]
+fn memo_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut sender = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut recipient = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let deposit = sender
+        .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+            asset_id, 100,
+        ))))
+        .expect("Signing a ToPrivate transaction should succeed.");
+    let sync = |signer: &mut Signer, origin_checkpoint, posts: Vec<TransferPost>| {
+        signer
+            .sync(SyncRequest {
+                account: Default::default(),
+                origin_checkpoint,
+                data: SyncData {
+                    utxo_note_data: posts
+                        .into_iter()
+                        .flat_map(|post| post.body.receiver_posts)
+                        .map(|post| (post.utxo, post.note))
+                        .collect(),
+                    nullifier_data: Vec::new(),
+                },
+            })
+            .expect("Synchronizing should succeed.")
+    };
+    let response = sync(&mut sender, Default::default(), deposit.posts);
+    assert!(
+        response.memos.is_empty(),
+        "The deposit was signed without a memo."
+    );
+    let mut memo = [0; MEMO_SIZE];
+    memo[..17].copy_from_slice(b"Thanks for lunch!");
+    let memo = Memo::from(memo);
+    let asset = Asset::new(asset_id, 10);
+    let post = sender
+        .sign(SignRequest {
+            memo: Some(memo),
+            ..SignRequest::new(Transaction::PrivateTransfer(
+                asset,
+                recipient
+                    .address(Default::default())
+                    .expect("Sampled signer has a spending key"),
+            ))
+        })
+        .expect("Signing a PrivateTransfer should succeed.")
+        .posts
+        .take_first();
+    let mut stripped = post.clone();
+    for receiver in &mut stripped.body.receiver_posts {
+        receiver.note.memo = None;
+    }
+    assert_eq!(
+        post.body
+            .receiver_posts
+            .iter()
+            .map(|receiver| receiver.note.to_vec())
+            .collect::<Vec<_>>(),
+        stripped
+            .body
+            .receiver_posts
+            .iter()
+            .map(|receiver| receiver.note.to_vec())
+            .collect::<Vec<_>>(),
+        "Notes should be encoded without their memo."
+    );
+    let (encoded, original) = (post.body.to_vec(), stripped.body.to_vec());
+    assert!(
+        matches!(original[0], 0 | 1),
+        "A body without memos should start with the tag of its asset id."
+    );
+    assert_eq!(
+        encoded[0], MEMO_ENCODING_VERSION,
+        "A body with memos should start with the memo encoding version."
+    );
+    assert_eq!(
+        encoded[1..=original.len()],
+        original[..],
+        "A body with memos should be followed by its original encoding."
+    );
+    assert_eq!(post.has_valid_authorization_signature(&parameters), Ok(()));
+    assert_eq!(
+        stripped.has_valid_authorization_signature(&parameters),
+        Err(InvalidAuthorizationSignature::BadSignature),
+        "Stripping the memo of a signed post should invalidate its signature."
+    );
+    assert_eq!(
+        recipient.transaction_memos(post.clone()),
+        vec![(asset, memo)],
+        "The recipient should be able to read the memo of the post."
+    );
+    assert!(
+        sender.transaction_memos(post.clone()).is_empty(),
+        "The change returned to the sender should not carry a memo."
+    );
+    assert_eq!(
+        sync(&mut recipient, Default::default(), vec![post.clone()]).memos,
+        vec![(asset, memo)],
+        "The memo should be surfaced to the recipient during synchronization."
+    );
+    assert!(
+        sync(&mut sender, response.checkpoint, vec![post])
+            .memos
+            .is_empty(),
+        "The memo should not be surfaced to the sender."
+    );
+}
+
+/// Checks that the memos received by every account of a signer are kept in the history of the
+/// account which owns them, even when another account is being synchronized.
+#[test]
+fn memo_history_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut sender = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut recipient = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let account = recipient
+        .create_account()
+        .expect("The sampled signer should have its accounts loaded.");
+    let asset_id = rng.gen();
+    let deposit = sender
+        .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+            asset_id, 100,
+        ))))
+        .expect("Signing a ToPrivate transaction should succeed.");
+    let sync = |signer: &mut Signer, posts: Vec<TransferPost>| {
+        signer
+            .sync(SyncRequest {
+                account: Default::default(),
+                origin_checkpoint: Default::default(),
+                data: SyncData {
+                    utxo_note_data: posts
+                        .into_iter()
+                        .flat_map(|post| post.body.receiver_posts)
+                        .map(|post| (post.utxo, post.note))
+                        .collect(),
+                    nullifier_data: Vec::new(),
+                },
+            })
+            .expect("Synchronizing should succeed.")
+    };
+    sync(&mut sender, deposit.posts);
+    let mut memo = [0; MEMO_SIZE];
+    memo[..14].copy_from_slice(b"Split the bill");
+    let memo = Memo::from(memo);
+    let payments = [Default::default(), account]
+        .into_iter()
+        .zip([10, 20])
+        .map(|(index, value)| {
+            (
+                Asset::new(asset_id, value),
+                recipient
+                    .address(index)
+                    .expect("The recipient account should exist."),
+            )
+        })
+        .collect::<Vec<_>>();
+    let posts = sender
+        .sign(SignRequest {
+            memo: Some(memo),
+            ..SignRequest::new(Transaction::BatchPrivateTransfer(payments.clone()))
+        })
+        .expect("Signing a batch of private transfers should succeed.")
+        .posts;
+    let response = sync(&mut recipient, posts);
+    assert_eq!(
+        response.memos,
+        vec![(payments[0].0, memo)],
+        "Only the memo of the synchronized account should be surfaced."
+    );
+    for (index, (asset, _)) in [Default::default(), account].into_iter().zip(payments) {
+        assert_eq!(
+            recipient
+                .transaction_history(TransactionHistoryRequest {
+                    account: index,
+                    offset: 0,
+                    limit: 10,
+                })
+                .entries,
+            vec![history::Entry {
+                kind: EntryKind::Received,
+                asset,
+                memo: Some(memo),
+                checkpoint: response.checkpoint,
+            }],
+            "Each account should keep the memo of its payment."
+        );
+    }
+}
+
+/// Checks that consolidating the UTXOs of an asset merges them pairwise into a single
+/// [`PrivateTransfer`](manta_accounting::transfer::canonical::PrivateTransfer) chain, and that
+/// the number of posts is capped by `max_posts`.
+#[test]
+fn consolidate_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let mut utxo_note_data = Vec::new();
+    for _ in 0..4 {
+        let deposit = signer
+            .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+                asset_id, 10,
+            ))))
+            .expect("Signing a ToPrivate transaction should succeed.");
+        utxo_note_data.extend(
+            deposit
+                .posts
+                .into_iter()
+                .flat_map(|post| post.body.receiver_posts)
+                .map(|post| (post.utxo, post.note)),
+        );
+    }
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: SyncData {
+                utxo_note_data,
+                nullifier_data: Vec::new(),
+            },
+        })
+        .expect("Synchronizing the deposits should succeed.");
+    let capped = signer
+        .consolidate(Default::default(), asset_id, 2)
+        .expect("Consolidating should succeed.");
+    assert_eq!(
+        capped.posts.len(),
+        2,
+        "Consolidation should stop at max_posts."
+    );
+    assert_eq!(capped.utxo_count, 2, "Two UTXOs should remain.");
+    let full = signer
+        .consolidate(Default::default(), asset_id, usize::MAX)
+        .expect("Consolidating should succeed.");
+    assert_eq!(
+        full.posts.len(),
+        3,
+        "Four UTXOs should be merged in three posts."
+    );
+    assert_eq!(full.utxo_count, 1, "A single UTXO should remain.");
+    for post in &full.posts {
+        assert_eq!(
+            TransferShape::from_post(post),
+            Some(TransferShape::PrivateTransfer),
+            "Each consolidation post should be a private transfer."
+        );
+        assert!(
+            post.has_valid_proof(&verifying_context.private_transfer)
+                .expect("Unable to verify the proof."),
+            "Invalid private transfer proof."
+        );
+    }
+}
+
+/// Checks that the plan of a withdrawal predicts the spent assets, the change and the shapes of
+/// the posts which are generated when signing it.
+#[test]
+fn plan_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let deposit = signer
+        .plan(
+            Default::default(),
+            Transaction::ToPrivate(Asset::new(asset_id, 10)),
+            None,
+            &Default::default(),
+        )
+        .expect("Planning a ToPrivate transaction should succeed.");
+    assert_eq!(deposit.shapes, [TransferShape::ToPrivate]);
+    assert!(deposit.selected.is_empty() && deposit.change.is_empty());
+    let mut utxo_note_data = Vec::new();
+    for _ in 0..5 {
+        utxo_note_data.extend(
+            signer
+                .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+                    asset_id, 10,
+                ))))
+                .expect("Signing a ToPrivate transaction should succeed.")
+                .posts
+                .into_iter()
+                .flat_map(|post| post.body.receiver_posts)
+                .map(|post| (post.utxo, post.note)),
+        );
+    }
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: SyncData {
+                utxo_note_data,
+                nullifier_data: Vec::new(),
+            },
+        })
+        .expect("Synchronizing the deposits should succeed.");
+    let withdraw = Transaction::ToPublic(Asset::new(asset_id, 45));
+    let plan = signer
+        .plan(
+            Default::default(),
+            withdraw.clone(),
+            None,
+            &Default::default(),
+        )
+        .expect("Planning a ToPublic transaction should succeed.");
+    assert_eq!(plan.selected.len(), 5, "All five UTXOs should be spent.");
+    assert_eq!(plan.change, [Asset::new(asset_id, 5)]);
+    assert_eq!(
+        plan.shapes,
+        [
+            TransferShape::PrivateTransfer,
+            TransferShape::PrivateTransfer,
+            TransferShape::PrivateTransfer,
+            TransferShape::ToPublic,
+        ],
+        "Five UTXOs should be joined in three posts before the final post."
+    );
+    assert!(plan.size.constraint_count > 0);
+    assert!(!plan.estimated_proving_time.is_zero());
+    let posts = signer
+        .sign(SignRequest::new(withdraw))
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    assert_eq!(
+        posts
+            .iter()
+            .map(TransferShape::from_post)
+            .collect::<Option<Vec<_>>>()
+            .expect("All posts should have a canonical shape."),
+        plan.shapes,
+        "The signed posts should match the plan."
+    );
+    assert!(matches!(
+        signer.plan(
+            Default::default(),
+            Transaction::ToPublic(Asset::new(asset_id, 100)),
+            None,
+            &Default::default()
+        ),
+        Err(SignError::InsufficientBalance(_))
+    ));
+}
+
+/// Checks that only the assets listed as non-fungible are selected as non-fungible tokens, while
+/// UTXOs of unit value of every other asset are merged as usual.
+#[test]
+fn non_fungible_plan_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let (nft, token) = (rng.gen(), rng.gen());
+    let mut posts = Vec::new();
+    for asset_id in [nft, nft, token, token] {
+        posts.extend(
+            signer
+                .sign(SignRequest::new(Transaction::ToPrivate(Asset::new(
+                    asset_id, 1,
+                ))))
+                .expect("Signing a ToPrivate transaction should succeed.")
+                .posts,
+        );
+    }
+    signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: sync_data(posts),
+        })
+        .expect("Synchronizing the deposits should succeed.");
+    let non_fungible = [nft].into_iter().collect::<BTreeSet<_>>();
+    let mut plan = |asset| {
+        signer.plan(
+            Default::default(),
+            Transaction::ToPublic(asset),
+            None,
+            &non_fungible,
+        )
+    };
+    assert_eq!(
+        plan(Asset::new(token, 2))
+            .expect("Planning a ToPublic transaction should succeed.")
+            .selected
+            .len(),
+        2,
+        "Fungible UTXOs of unit value should be merged."
+    );
+    assert_eq!(
+        plan(Asset::new(nft, 1))
+            .expect("Planning a ToPublic transaction should succeed.")
+            .selected
+            .into_iter()
+            .map(|(_, asset)| asset)
+            .collect::<Vec<_>>(),
+        [Asset::new(nft, 1)],
+        "A non-fungible token should be paid with exactly one UTXO."
+    );
+    assert!(
+        matches!(
+            plan(Asset::new(nft, 2)),
+            Err(SignError::InsufficientBalance(_))
+        ),
+        "Non-fungible tokens should never be merged."
+    );
+}
+
+/// Checks that synchronizing many notes, of which only a few belong to the signer, finds the
+/// deposits in ledger order and inserts them in the right positions of the UTXO accumulator, so
+/// that they can be spent afterwards.
+#[test]
+fn sync_many_notes_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let asset_id = rng.gen();
+    let address = signer
+        .address(Default::default())
+        .expect("Sampled signer has a spending key");
+    let foreign_address = address_from_mnemonic(Mnemonic::sample(&mut rng), &parameters);
+    let deposits = [(3, 10), (97, 20), (150, 30)];
+    let utxo_note_data = (0..200u128)
+        .map(|index| {
+            let (address, value) = match deposits.iter().find(|(i, _)| *i == index) {
+                Some((_, value)) => (address, *value),
+                _ => (foreign_address, 1000 + index),
+            };
+            let post = Receiver::sample(
+                &parameters,
+                address,
+                Asset::new(asset_id, value),
+                Default::default(),
+                &mut rng,
+            )
+            .into_post();
+            (post.utxo, post.note)
+        })
+        .collect();
+    let response = signer
+        .sync(SyncRequest {
+            account: Default::default(),
+            origin_checkpoint: Default::default(),
+            data: SyncData {
+                utxo_note_data,
+                nullifier_data: Vec::new(),
+            },
+        })
+        .expect("Synchronizing the notes should succeed.");
+    match response.balance_update {
+        BalanceUpdate::Partial { deposit, withdraw } => {
+            assert_eq!(
+                deposit,
+                deposits.map(|(_, value)| Asset::new(asset_id, value)),
+                "The deposits should be found in ledger order."
+            );
+            assert!(withdraw.is_empty(), "There should be no withdraws.");
+        }
+        _ => panic!("The first synchronization should return a partial balance update."),
+    }
+    let posts = signer
+        .sign(SignRequest::new(Transaction::ToPublic(Asset::new(
+            asset_id, 60,
+        ))))
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    for post in &posts {
+        let verifying_context = match TransferShape::from_post(post) {
+            Some(TransferShape::PrivateTransfer) => &verifying_context.private_transfer,
+            Some(TransferShape::ToPublic) => &verifying_context.to_public,
+            shape => panic!("Unexpected transfer shape: {shape:?}"),
+        };
+        assert!(
+            post.has_valid_proof(verifying_context)
+                .expect("Unable to verify the proof."),
+            "Spending the synchronized deposits should produce valid proofs."
+        );
+    }
+}
@@ -17,10 +17,16 @@
 //! Manta Pay Transfer Testing
 
 use crate::{
-    config::{FullParametersRef, Parameters, PrivateTransfer, ProofSystem, ToPrivate, ToPublic},
+    config::{
+        Asset, Config, FullParametersRef, Parameters, PrivateTransfer, ProofSystem, ToPrivate,
+        ToPublic, Transaction,
+    },
     test::payment::UtxoAccumulator,
 };
-use manta_accounting::transfer::test::validity_check_with_fuzzing;
+use manta_accounting::transfer::{
+    canonical::{self, TransactionCheckError, TransactionKind},
+    test::validity_check_with_fuzzing,
+};
 use manta_crypto::{
     accumulator::Accumulator,
     constraint::{measure::Measure, ProofSystem as _},
@@ -105,6 +111,99 @@ fn to_public() {
     );
 }
 
+/// Tests that a batch of [`ToPrivate`] and [`PrivateTransfer`] posts is verified by batching
+/// proofs of the same shape, and that replacing the proof of one post invalidates the batch.
+#[test]
+fn batch_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (proving_context, verifying_context) = canonical::generate_context::<Config, _>(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let mut posts = Vec::new();
+    for _ in 0..3 {
+        posts.push(
+            ToPrivate::sample_post(
+                &proving_context.to_private,
+                &parameters,
+                &mut utxo_accumulator,
+                None,
+                &mut rng,
+            )
+            .expect("Unable to build ToPrivate proof.")
+            .expect("Unable to build ToPrivate proof."),
+        );
+        posts.push(
+            PrivateTransfer::sample_post(
+                &proving_context.private_transfer,
+                &parameters,
+                &mut utxo_accumulator,
+                Some(&rng.gen()),
+                &mut rng,
+            )
+            .expect("Unable to build PrivateTransfer proof.")
+            .expect("Unable to build PrivateTransfer proof."),
+        );
+    }
+    assert!(
+        verifying_context
+            .has_valid_proofs(&posts, &mut rng)
+            .expect("Unable to verify proofs."),
+        "The batch of proofs should have been valid."
+    );
+    posts[1].body.proof = posts[3].body.proof.clone();
+    assert!(
+        !verifying_context
+            .has_valid_proofs(&posts, &mut rng)
+            .expect("Unable to verify proofs."),
+        "The batch of proofs should have been invalid after replacing one of the proofs."
+    );
+}
+
+/// Checks that [`Transaction::check`] rejects transactions which withdraw only part of a
+/// non-fungible asset.
+#[test]
+fn non_fungible_transaction_check() {
+    let mut rng = OsRng;
+    let (nft, token) = (rng.gen(), rng.gen());
+    let address = rng.gen();
+    let is_non_fungible = |id: &_| *id == nft;
+    assert_eq!(
+        Transaction::PrivateTransfer(Asset::new(nft, 1), address).check(|_| true, is_non_fungible),
+        Ok(TransactionKind::Withdraw(Asset::new(nft, 1))),
+        "Withdrawing a whole non-fungible asset should be allowed."
+    );
+    assert_eq!(
+        Transaction::ToPublic(Asset::new(nft, 0)).check(|_| true, is_non_fungible),
+        Err(TransactionCheckError::PartialNonFungibleTransfer(
+            Asset::new(nft, 0)
+        )),
+        "Withdrawing part of a non-fungible asset should fail."
+    );
+    assert_eq!(
+        Transaction::BatchPrivateTransfer(vec![
+            (Asset::new(token, 5), address),
+            (Asset::new(nft, 2), address),
+        ])
+        .check(|_| true, is_non_fungible),
+        Err(TransactionCheckError::PartialNonFungibleTransfer(
+            Asset::new(nft, 2)
+        )),
+        "Batches which split a non-fungible asset should fail."
+    );
+    assert_eq!(
+        Transaction::ToPublic(Asset::new(token, 5)).check(|_| false, is_non_fungible),
+        Err(TransactionCheckError::InsufficientBalance(Asset::new(
+            token, 5
+        ))),
+        "Fungible assets should only be checked against the balance."
+    );
+}
+
 /// Checks that an empty message will produce a valid signature.
 #[test]
 fn check_empty_message_signature() {
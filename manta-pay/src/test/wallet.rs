@@ -0,0 +1,1147 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Wallet Testing Suite
+
+use crate::{
+    config::{
+        utxo::{AssetId, Memo, MerkleTreeConfiguration, MEMO_SIZE},
+        Asset, Config, Utxo,
+    },
+    parameters::load_parameters,
+    signer::base::Signer,
+    simulation::{
+        ledger::{
+            block::{produce_blocks, InclusionStatus, STATUS_RETENTION_DEPTH},
+            AccountId, FeeSchedule, Ledger, LedgerConnection, LedgerEvent, TransferLedgerError,
+        },
+        report::Report,
+        sample_signer, Simulation,
+    },
+};
+use alloc::sync::Arc;
+use core::time::Duration;
+use manta_accounting::{
+    transfer::{
+        canonical::{Transaction, TransferShape},
+        receiver::ReceiverPostError,
+        sender::SenderPostError,
+        utxo::protocol::Visibility,
+        InvalidAuthorizationSignature, TransferPostError,
+    },
+    wallet::{SignOptions, SyncProgress, Wallet},
+};
+use manta_crypto::{
+    accumulator::ItemHashFunction, merkle_tree::forest::Configuration as _, rand::OsRng,
+};
+use manta_util::ops::ControlFlow;
+use tokio::sync::RwLock;
+
+#[cfg(feature = "http")]
+use {
+    crate::simulation::ledger::{
+        block::PostId,
+        http::{client::Client, server::Server},
+    },
+    tokio::task::yield_now,
+};
+
+#[cfg(feature = "serde_json")]
+use crate::simulation::ledger::Snapshot;
+
+#[cfg(feature = "fs")]
+use {
+    crate::signer::{
+        functions::new_persistent_signer, FileBackend, FileBackendError, PersistentSigner,
+    },
+    manta_accounting::wallet::signer::storage::Backend,
+    std::fs,
+};
+
+/// Checks that [`Wallet::sync_with_progress`] reports every synchronized item along with the
+/// checkpoint committed by the signer, and that [`Wallet::resume`] restores that checkpoint.
+#[tokio::test]
+async fn sync_with_progress_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut sender = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    for value in [100, 200] {
+        let posts = sender
+            .sign(Transaction::ToPrivate(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Signing a ToPrivate transaction should succeed.")
+            .posts;
+        assert!(
+            ledger.write().await.push(AccountId(0), posts),
+            "The ledger should accept the deposit."
+        );
+    }
+    let mut reports = Vec::<SyncProgress<_>>::new();
+    let flow = sender
+        .sync_with_progress(|progress| {
+            reports.push(*progress);
+            ControlFlow::CONTINUE
+        })
+        .await
+        .expect("Synchronizing with progress should succeed.");
+    assert!(
+        flow.is_break(),
+        "The wallet should have caught up with the ledger."
+    );
+    let last = reports
+        .last()
+        .expect("At least one step should be reported.");
+    assert_eq!(
+        last.processed, 2,
+        "Both deposits should have been synchronized."
+    );
+    assert_eq!(last.total(), Some(2));
+    assert_eq!(&last.checkpoint, sender.checkpoint());
+    assert_eq!(sender.balance(&asset_id), 300);
+    sender
+        .resume()
+        .await
+        .expect("Resuming from the signer state should succeed.");
+    assert_eq!(
+        &last.checkpoint,
+        sender.checkpoint(),
+        "Resuming should restore the last committed checkpoint."
+    );
+    assert_eq!(sender.balance(&asset_id), 300);
+}
+
+/// Checks that a [`PersistentSigner`] saves its state after signing and synchronizing, restores it
+/// when it is rebuilt over the same file, and detects a corrupted snapshot.
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn persistent_signer_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let path = directory.path().join("signer.state");
+    let password = b"password";
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut sender = Wallet::<Config, _, PersistentSigner>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        new_persistent_signer(signer.clone(), &path, password)
+            .expect("There should be no snapshot to load yet."),
+    );
+    for value in [100, 200] {
+        let posts = sender
+            .sign(Transaction::ToPrivate(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Signing a ToPrivate transaction should succeed.")
+            .posts;
+        assert!(path.exists(), "Signing should save the snapshot.");
+        assert!(
+            ledger.write().await.push(AccountId(0), posts),
+            "The ledger should accept the deposit."
+        );
+    }
+    sender.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(sender.balance(&asset_id), 300);
+    assert!(path.exists(), "The snapshot should have been saved.");
+    assert!(
+        !sender.signer_mut().backend().temporary_path().exists(),
+        "The temporary snapshot should have been moved into place."
+    );
+    let mut restored = Wallet::<Config, _, PersistentSigner>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        new_persistent_signer(signer, &path, password)
+            .expect("Loading the snapshot should succeed."),
+    );
+    restored
+        .resume()
+        .await
+        .expect("Resuming from the restored signer should succeed.");
+    assert_eq!(restored.checkpoint(), sender.checkpoint());
+    assert_eq!(restored.balance(&asset_id), 300);
+    assert!(matches!(
+        Backend::<Config>::load(&mut FileBackend::new(&path, b"wrong password")),
+        Err(FileBackendError::Corrupted(_))
+    ));
+    let mut bytes = fs::read(&path).expect("Unable to read the snapshot.");
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xff;
+    fs::write(&path, bytes).expect("Unable to write the snapshot.");
+    assert!(matches!(
+        Backend::<Config>::load(&mut FileBackend::new(&path, password)),
+        Err(FileBackendError::Corrupted(_))
+    ));
+}
+
+/// Checks that the wallet synchronizes over several pages when the ledger limits the size of each
+/// pull, including pages with nullifiers.
+#[tokio::test]
+async fn paginated_sync_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    ledger.set_page_size(1);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut sender = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    for value in [100, 200] {
+        let posts = sender
+            .sign(Transaction::ToPrivate(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Signing a ToPrivate transaction should succeed.")
+            .posts;
+        assert!(ledger.write().await.push(AccountId(0), posts));
+    }
+    let response = ledger.read().await.pull(sender.checkpoint());
+    assert!(response.should_continue);
+    assert_eq!(response.remaining, Some(1));
+    assert_eq!(response.data.len(), 1);
+    let mut reports = Vec::<SyncProgress<_>>::new();
+    assert!(sender
+        .sync_with_progress(|progress| {
+            reports.push(*progress);
+            ControlFlow::CONTINUE
+        })
+        .await
+        .expect("Synchronizing with progress should succeed.")
+        .is_break());
+    assert_eq!(
+        reports.iter().map(|r| r.processed).collect::<Vec<_>>(),
+        [1, 2],
+        "Each deposit should have been synchronized in its own page."
+    );
+    assert_eq!(sender.balance(&asset_id), 300);
+    let posts = sender
+        .sign(Transaction::ToPublic(Asset::new(asset_id, 250)), None)
+        .await
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    assert!(ledger.write().await.push(AccountId(0), posts));
+    reports.clear();
+    assert!(sender
+        .sync_with_progress(|progress| {
+            reports.push(*progress);
+            ControlFlow::CONTINUE
+        })
+        .await
+        .expect("Synchronizing with progress should succeed.")
+        .is_break());
+    let last = reports
+        .last()
+        .expect("At least one page should be reported.");
+    assert!(reports.len() > 1, "The spend should span several pages.");
+    assert_eq!(last.remaining, Some(0));
+    assert_eq!(sender.balance(&asset_id), 50);
+}
+
+/// Checks that a ledger restored from a saved [`Snapshot`] serves the same data and keeps
+/// accepting transfers built against the state the wallet synchronized before the restore.
+#[cfg(feature = "serde_json")]
+#[tokio::test]
+async fn ledger_snapshot_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let path = directory.path().join("ledger.json");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut sender = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    for value in [100, 200] {
+        assert!(sender
+            .post(Transaction::ToPrivate(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Posting a ToPrivate transaction should succeed."));
+    }
+    sender.sync().await.expect("Synchronizing should succeed.");
+    ledger
+        .read()
+        .await
+        .save(&path)
+        .expect("Saving the ledger snapshot should succeed.");
+    let snapshot = Snapshot::load(&path).expect("Loading the ledger snapshot should succeed.");
+    assert_eq!(snapshot.receiver_count(), 2);
+    assert_eq!(snapshot.nullifier_count(), 0);
+    let restored = Ledger::from_snapshot(
+        snapshot,
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    let checkpoint = Default::default();
+    assert_eq!(
+        restored.pull(&checkpoint).data.utxo_note_data,
+        ledger.read().await.pull(&checkpoint).data.utxo_note_data,
+    );
+    assert_eq!(
+        restored.public_balances(AccountId(0)),
+        ledger.read().await.public_balances(AccountId(0)),
+    );
+    *ledger.write().await = restored;
+    assert!(
+        sender
+            .post(Transaction::ToPublic(Asset::new(asset_id, 250)), None)
+            .await
+            .expect("Posting a ToPublic transaction should succeed."),
+        "The restored ledger should accept spends of UTXOs it restored."
+    );
+    sender.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(sender.balance(&asset_id), 50);
+}
+
+/// Checks that the ledger charges the [`FeeSchedule`] to the public account of each transfer and
+/// rejects posts which cannot pay their fee or which would leave dust behind.
+#[tokio::test]
+async fn fee_schedule_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    ledger.set_fee_schedule(FeeSchedule {
+        to_private: 10,
+        to_public: 5,
+    });
+    ledger.set_existential_deposit(100);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let public_balance = || async { ledger.read().await.public_balances(AccountId(0)) };
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 995)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), posts),
+        Err(TransferPostError::UnexpectedError(
+            TransferLedgerError::InsufficientFee {
+                account_id: AccountId(0),
+                fee: 10,
+            }
+        )),
+        "The ledger should reject posts whose source cannot pay the fee."
+    );
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 950)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), posts),
+        Err(TransferPostError::UnexpectedError(
+            TransferLedgerError::ExistentialDeposit {
+                account_id: AccountId(0),
+                balance: 40,
+            }
+        )),
+        "The ledger should reject posts which leave a balance below the existential deposit."
+    );
+    assert_eq!(
+        public_balance()
+            .await
+            .map(|balances| balances.value(&asset_id)),
+        Some(1000)
+    );
+    assert!(wallet
+        .post(Transaction::ToPrivate(Asset::new(asset_id, 990)), None)
+        .await
+        .expect("Posting a ToPrivate transaction should succeed."));
+    assert_eq!(
+        public_balance()
+            .await
+            .map(|balances| balances.value(&asset_id)),
+        Some(0)
+    );
+    assert!(wallet
+        .post(Transaction::ToPublic(Asset::new(asset_id, 300)), None)
+        .await
+        .expect("Posting a ToPublic transaction should succeed."));
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(wallet.balance(&asset_id), 690);
+    assert_eq!(
+        public_balance()
+            .await
+            .map(|balances| balances.value(&asset_id)),
+        Some(295)
+    );
+}
+
+/// Checks that a block applies the posts of the mempool in order, reports a second spend of the
+/// same UTXO as a conflict, and that wallets can wait for the inclusion of their posts.
+#[tokio::test]
+async fn block_production_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::with_mempool(AccountId(0), ledger.clone(), Duration::from_millis(5)),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 100)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    assert!(ledger.write().await.push(AccountId(0), posts));
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    let mut submissions = Vec::new();
+    for value in [50, 60] {
+        let posts = wallet
+            .sign(Transaction::ToPublic(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Signing a ToPublic transaction should succeed.")
+            .posts;
+        submissions.push(ledger.write().await.submit(AccountId(0), posts));
+    }
+    {
+        let ledger = ledger.read().await;
+        assert!(submissions
+            .iter()
+            .flatten()
+            .all(|id| ledger.status(*id) == Some(InclusionStatus::Pending)));
+    }
+    let block = ledger.write().await.produce_block();
+    assert_eq!(block.number, 1);
+    assert!(ledger.read().await.mempool().is_empty());
+    let status = |id| block.posts.iter().find(|(i, _)| *i == id).map(|(_, s)| *s);
+    assert!(submissions[0]
+        .iter()
+        .all(|id| status(*id) == Some(InclusionStatus::Included(1))));
+    assert!(
+        submissions[1]
+            .iter()
+            .any(|id| status(*id) == Some(InclusionStatus::Conflict(1))),
+        "Spending the same UTXO twice in one block should be reported as a conflict."
+    );
+    assert!(!submissions[1]
+        .iter()
+        .any(|id| status(*id) == Some(InclusionStatus::Included(1))));
+    {
+        let mut ledger = ledger.write().await;
+        for _ in 1..STATUS_RETENTION_DEPTH {
+            ledger.produce_block();
+        }
+        assert_eq!(
+            ledger.status(submissions[0][0]),
+            Some(InclusionStatus::Included(1))
+        );
+        ledger.produce_block();
+        assert!(
+            submissions
+                .iter()
+                .flatten()
+                .all(|id| ledger.status(*id).is_none()),
+            "Statuses should be evicted once they are older than the retention depth."
+        );
+    }
+    let producer = tokio::spawn(produce_blocks(ledger.clone(), Duration::from_millis(10)));
+    assert!(wallet
+        .post(Transaction::ToPublic(Asset::new(asset_id, 30)), None)
+        .await
+        .expect("Posting a ToPublic transaction should succeed."));
+    producer.abort();
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(wallet.balance(&asset_id), 20);
+    assert!(ledger.read().await.block_number() > 1);
+}
+
+/// Checks that posts submitted through the ledger HTTP server wait in its mempool until the next
+/// block, that their inclusion status can be queried over HTTP, and that a wallet synchronizes
+/// with the server once they are included.
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn ledger_server_mempool_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let server = Server::new(ledger);
+    let ledger = server.ledger().clone();
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Unable to reserve a local address.");
+    tokio::spawn(server.serve(address.to_string()));
+    let client = Client::new(AccountId(0), format!("http://{address}/"))
+        .expect("Unable to build the HTTP client.");
+    let mut unknown = client.status(PostId(0)).await;
+    for _ in 0..1000 {
+        if unknown.is_ok() {
+            break;
+        }
+        yield_now().await;
+        unknown = client.status(PostId(0)).await;
+    }
+    assert_eq!(
+        unknown.expect("The HTTP server should be reachable."),
+        None,
+        "No post should have been submitted yet."
+    );
+    let mut wallet = Wallet::<Config, _, _>::new(
+        Client::new(AccountId(0), format!("http://{address}/"))
+            .expect("Unable to build the HTTP client."),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 100)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    let ids = client
+        .submit(posts)
+        .await
+        .expect("The HTTP server should accept submissions.");
+    assert_eq!(ids.len(), 1);
+    assert_eq!(
+        client
+            .status(ids[0])
+            .await
+            .expect("The HTTP server should answer status requests."),
+        Some(InclusionStatus::Pending)
+    );
+    ledger.write().await.produce_block();
+    assert_eq!(
+        client
+            .status(ids[0])
+            .await
+            .expect("The HTTP server should answer status requests."),
+        Some(InclusionStatus::Included(1))
+    );
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(wallet.balance(&asset_id), 100);
+}
+
+/// Checks that [`Wallet::rewind`] undoes the blocks dropped by [`Ledger::reorganize`] and that the
+/// posts from those blocks can be submitted again on the new fork.
+#[tokio::test]
+async fn reorganization_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::with_mempool(AccountId(0), ledger.clone(), Duration::from_millis(5)),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    wallet.signer_mut().set_retained_checkpoints(4);
+    for value in [100, 50] {
+        let posts = wallet
+            .sign(Transaction::ToPrivate(Asset::new(asset_id, value)), None)
+            .await
+            .expect("Signing a ToPrivate transaction should succeed.")
+            .posts;
+        let ids = ledger.write().await.submit(AccountId(0), posts);
+        let block = ledger.write().await.produce_block();
+        assert_eq!(block.included_count(), ids.len());
+        wallet.sync().await.expect("Synchronizing should succeed.");
+    }
+    assert_eq!(wallet.balance(&asset_id), 150);
+    assert!(
+        !wallet.rewind().await.expect("Rewinding should succeed."),
+        "Nothing should be rewound before a reorganization."
+    );
+    let fork_point = ledger.write().await.reorganize(1);
+    assert_eq!(ledger.read().await.block_number(), 1);
+    assert_eq!(
+        ledger
+            .read()
+            .await
+            .public_balances(AccountId(0))
+            .map(|balances| balances.value(&asset_id)),
+        Some(900)
+    );
+    assert!(wallet.rewind().await.expect("Rewinding should succeed."));
+    assert_eq!(wallet.checkpoint(), &fork_point);
+    assert_eq!(wallet.balance(&asset_id), 100);
+    assert!(!wallet.rewind().await.expect("Rewinding should succeed."));
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(wallet.balance(&asset_id), 100);
+    let producer = tokio::spawn(produce_blocks(ledger.clone(), Duration::from_millis(10)));
+    assert!(wallet
+        .post(Transaction::ToPrivate(Asset::new(asset_id, 25)), None)
+        .await
+        .expect("Posting a ToPrivate transaction should succeed."));
+    producer.abort();
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(wallet.balance(&asset_id), 125);
+}
+
+/// Checks that replaying the action log of a recorded simulation reproduces every event, and that
+/// a tampered log is reported at the first diverging record.
+#[tokio::test]
+async fn simulation_replay_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let simulation = Simulation {
+        actor_count: 2,
+        actor_lifetime: 4,
+        asset_id_count: 1,
+        starting_balance: 1000,
+        attack_weight: 0,
+    };
+    let (balances_match, log) = simulation
+        .record(
+            &parameters,
+            &utxo_accumulator_model,
+            &proving_context,
+            verifying_context.clone(),
+            7,
+        )
+        .await;
+    assert!(
+        balances_match,
+        "Funds before and after the simulation should match."
+    );
+    assert_eq!(log.seed, 7);
+    assert_eq!(log.records.len(), 8);
+    let report = simulation
+        .replay(
+            &parameters,
+            &utxo_accumulator_model,
+            &proving_context,
+            verifying_context.clone(),
+            &log,
+        )
+        .await;
+    assert_eq!(report.divergence, None);
+    assert!(report.balances_match);
+    let mut tampered = log.clone();
+    tampered.records[0].event.value = Err("Tampered".into());
+    let report = simulation
+        .replay(
+            &parameters,
+            &utxo_accumulator_model,
+            &proving_context,
+            verifying_context,
+            &tampered,
+        )
+        .await;
+    let divergence = report
+        .divergence
+        .expect("The tampered record should be reported.");
+    assert_eq!(divergence.index, 0);
+    assert_eq!(divergence.event, log.records[0].event);
+}
+
+/// Checks that the simulation report accounts for every action of every actor and for the proofs
+/// built and verified during the simulation.
+#[tokio::test]
+async fn simulation_report_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let simulation = Simulation {
+        actor_count: 2,
+        actor_lifetime: 5,
+        asset_id_count: 1,
+        starting_balance: 1000,
+        attack_weight: 0,
+    };
+    let report: Report = simulation
+        .report(
+            &parameters,
+            &utxo_accumulator_model,
+            &proving_context,
+            verifying_context,
+            &mut rng,
+        )
+        .await;
+    assert!(report.balances_match);
+    assert_eq!(
+        report
+            .actions
+            .values()
+            .map(|statistics| statistics.count)
+            .sum::<usize>(),
+        10
+    );
+    assert_eq!(
+        report
+            .actions
+            .values()
+            .map(|statistics| statistics.errors)
+            .sum::<usize>(),
+        report.failures.len()
+    );
+    assert_eq!(report.summary.actors.len(), 2);
+    assert!(report.sync.count > 0);
+    for shape in [
+        TransferShape::ToPrivate,
+        TransferShape::PrivateTransfer,
+        TransferShape::ToPublic,
+    ] {
+        assert!(report.proving.get(shape).count <= report.verification.get(shape).count);
+    }
+    #[cfg(feature = "serde_json")]
+    {
+        let json = serde_json::to_string(&report).expect("Serializing the report should succeed.");
+        let decoded = serde_json::from_str::<Report>(&json)
+            .expect("Deserializing the report should succeed.");
+        assert_eq!(decoded.actions, report.actions);
+        assert_eq!(decoded.summary, report.summary);
+    }
+}
+
+/// Checks that the ledger broadcasts the receivers and nullifiers of every post, and that a wallet
+/// waiting on [`Wallet::sync_on_update`] receives a transfer as soon as it is posted.
+#[tokio::test]
+async fn ledger_subscription_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut events = ledger.read().await.subscribe();
+    let mut sender = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let mut receiver = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(1), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let address = receiver
+        .address()
+        .await
+        .expect("Getting the address should succeed.")
+        .expect("The receiver should have an address.");
+    assert!(sender
+        .post(Transaction::ToPrivate(Asset::new(asset_id, 100)), None)
+        .await
+        .expect("Posting a ToPrivate transaction should succeed."));
+    sender.sync().await.expect("Synchronizing should succeed.");
+    receiver
+        .sync_on_update()
+        .await
+        .expect("The first wait should return immediately.");
+    let (received, posted) = tokio::join!(
+        receiver.sync_on_update(),
+        sender.post(
+            Transaction::PrivateTransfer(Asset::new(asset_id, 40), address),
+            None
+        ),
+    );
+    received.expect("Synchronizing on update should succeed.");
+    assert!(posted.expect("Posting a PrivateTransfer transaction should succeed."));
+    assert_eq!(receiver.balance(&asset_id), 40);
+    for (receiver_count, nullifier_count) in [(1, 0), (2, 2)] {
+        match events
+            .recv()
+            .await
+            .expect("The ledger should broadcast every post.")
+        {
+            LedgerEvent::Post {
+                receivers,
+                nullifiers,
+                ..
+            } => {
+                assert_eq!(receivers.len(), receiver_count);
+                assert_eq!(nullifiers.len(), nullifier_count);
+            }
+            event => panic!("Expected a post event, found {event:?}."),
+        }
+    }
+}
+
+/// Checks that the ledger rejects posts built against a stale UTXO accumulator root, replayed
+/// posts, double spends, and tampered authorization signatures with the expected errors, and that
+/// a simulation with adversarial actors keeps its balances.
+#[tokio::test]
+async fn adversarial_posts_test() {
+    // NOTE: The change of a spend shares a shard with the deposit with a chance of one in 256,
+    //       so the chance that every attempt does is negligible.
+    const MAX_ATTEMPTS: usize = 16;
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    wallet.signer_mut().set_retained_checkpoints(2);
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 50)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    ledger.write().await.submit(AccountId(0), posts);
+    ledger.write().await.produce_block();
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    let stale = wallet
+        .sign(Transaction::ToPublic(Asset::new(asset_id, 50)), None)
+        .await
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    ledger.write().await.reorganize(1);
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), stale),
+        Err(TransferPostError::Sender(
+            SenderPostError::InvalidUtxoAccumulatorOutput
+        ))
+    );
+    assert!(wallet.rewind().await.expect("Rewinding should succeed."));
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(asset_id, 100)), None)
+        .await
+        .expect("Signing a ToPrivate transaction should succeed.")
+        .posts;
+    let deposit = posts[0].body.receiver_posts[0].utxo;
+    assert!(ledger.write().await.push(AccountId(0), posts.clone()));
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), posts),
+        Err(TransferPostError::Receiver(
+            ReceiverPostError::AssetRegistered
+        ))
+    );
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    let shard =
+        |utxo: &Utxo| MerkleTreeConfiguration::tree_index(&parameters.item_hash(utxo, &mut ()));
+    let transaction = Transaction::ToPublic(Asset::new(asset_id, 40));
+    let mut signed = None;
+    for _ in 0..MAX_ATTEMPTS {
+        let spend = wallet
+            .sign(transaction.clone(), None)
+            .await
+            .expect("Signing a ToPublic transaction should succeed.")
+            .posts;
+        let double_spend = wallet
+            .sign(transaction.clone(), None)
+            .await
+            .expect("Signing a ToPublic transaction should succeed.")
+            .posts;
+        if spend[0]
+            .body
+            .receiver_posts
+            .iter()
+            .all(|receiver| shard(&receiver.utxo) != shard(&deposit))
+        {
+            signed = Some((spend, double_spend));
+            break;
+        }
+    }
+    let (spend, double_spend) = signed.unwrap_or_else(|| {
+        panic!("No spend avoiding the shard of the deposit was signed in {MAX_ATTEMPTS} attempts.")
+    });
+    assert!(ledger.write().await.push(AccountId(0), spend));
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), double_spend),
+        Err(TransferPostError::Sender(SenderPostError::AssetSpent))
+    );
+    wallet.sync().await.expect("Synchronizing should succeed.");
+    let transaction = Transaction::ToPublic(Asset::new(asset_id, 10));
+    let mut posts = wallet
+        .sign(transaction.clone(), None)
+        .await
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    let mut other = wallet
+        .sign(transaction, None)
+        .await
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    posts[0].authorization_signature = other[0].authorization_signature.take();
+    assert_eq!(
+        ledger.write().await.try_push(AccountId(0), posts),
+        Err(TransferPostError::InvalidAuthorizationSignature(
+            InvalidAuthorizationSignature::BadSignature
+        ))
+    );
+    let simulation = Simulation {
+        actor_count: 2,
+        actor_lifetime: 6,
+        asset_id_count: 1,
+        starting_balance: 1000,
+        attack_weight: 20,
+    };
+    let report: Report = simulation
+        .report(
+            &parameters,
+            &utxo_accumulator_model,
+            &proving_context,
+            verifying_context,
+            &mut rng,
+        )
+        .await;
+    assert!(report.balances_match);
+}
+
+/// Checks that transparent UTXOs publish their asset on the ledger, are reported separately in
+/// the wallet balances of both the sender and the receiver, can carry memos, and can be spent like
+/// opaque UTXOs.
+#[tokio::test]
+async fn transparent_utxo_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let asset_id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_public_balance(AccountId(0), asset_id, 1000);
+    ledger.set_public_balance(AccountId(1), asset_id, 0);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut sender = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(0), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let mut receiver = Wallet::<Config, _, Signer>::new(
+        LedgerConnection::new(AccountId(1), ledger.clone()),
+        sample_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &mut rng,
+        ),
+    );
+    let deposit = Asset::new(asset_id, 100);
+    let posts = sender
+        .sign_with(
+            Transaction::ToPrivate(deposit),
+            None,
+            SignOptions {
+                visibility: Visibility::Transparent,
+                memo: None,
+            },
+        )
+        .await
+        .expect("Signing a transparent ToPrivate transaction should succeed.")
+        .posts;
+    let utxo = &posts[0].body.receiver_posts[0].utxo;
+    assert!(utxo.is_transparent, "The deposit should be transparent.");
+    assert_eq!(
+        utxo.public_asset, deposit,
+        "Transparent UTXOs should publish their asset."
+    );
+    assert!(
+        ledger.write().await.push(AccountId(0), posts),
+        "The ledger should accept the transparent deposit."
+    );
+    assert!(sender
+        .post(Transaction::ToPrivate(Asset::new(asset_id, 50)), None)
+        .await
+        .expect("Posting an opaque ToPrivate transaction should succeed."));
+    sender.sync().await.expect("Synchronizing should succeed.");
+    assert_eq!(sender.balance(&asset_id), 150);
+    assert_eq!(sender.transparent_balance(&asset_id), 100);
+    let address = receiver
+        .address()
+        .await
+        .expect("Getting the address should succeed.")
+        .expect("The receiver should have an address.");
+    let mut memo = [0; MEMO_SIZE];
+    memo[..11].copy_from_slice(b"Audit trail");
+    let memo = Memo::from(memo);
+    assert!(sender
+        .post_with(
+            Transaction::PrivateTransfer(Asset::new(asset_id, 30), address),
+            None,
+            SignOptions {
+                visibility: Visibility::Transparent,
+                memo: Some(memo),
+            },
+        )
+        .await
+        .expect("Posting a transparent PrivateTransfer transaction should succeed."));
+    sender.sync().await.expect("Synchronizing should succeed.");
+    receiver
+        .sync()
+        .await
+        .expect("Synchronizing should succeed.");
+    assert_eq!(sender.balance(&asset_id), 120);
+    assert!(sender.transparent_balance(&asset_id) <= 100);
+    assert_eq!(receiver.balance(&asset_id), 30);
+    assert_eq!(receiver.transparent_balance(&asset_id), 30);
+    assert_eq!(
+        receiver
+            .transaction_history(0, 1)
+            .await
+            .expect("Reading the transaction history should succeed.")
+            .entries[0]
+            .memo,
+        Some(memo),
+        "The transparent payment should carry its memo."
+    );
+    assert!(receiver
+        .post(Transaction::ToPublic(Asset::new(asset_id, 30)), None)
+        .await
+        .expect("Posting a ToPublic transaction should succeed."));
+    receiver
+        .sync()
+        .await
+        .expect("Synchronizing should succeed.");
+    assert_eq!(receiver.balance(&asset_id), 0);
+    assert_eq!(receiver.transparent_balance(&asset_id), 0);
+    assert_eq!(
+        ledger
+            .read()
+            .await
+            .public_balances(AccountId(1))
+            .map(|balances| balances.value(&asset_id)),
+        Some(30),
+    );
+}